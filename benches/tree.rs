@@ -0,0 +1,208 @@
+//! Benchmarks of the common tree operations at several sizes and arities.
+//!
+//! Run with `cargo bench`, an argument filters the benchmarks by name such as
+//! `cargo bench -- breadth_first`. Criterion keeps the results of the previous run under
+//! `target/criterion` and reports the change against them.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lz_eytzinger_tree::{
+    scapegoat::ScapegoatTree, traversal::DepthFirstOrder, EytzingerTree, NodePath,
+};
+use std::cmp::Ordering;
+
+const SIZES: [usize; 2] = [1_000, 100_000];
+const ARITIES: [usize; 3] = [2, 4, 16];
+
+fn construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    for (arity, size) in parameters() {
+        group.bench_with_input(id(arity, size), &(arity, size), |b, &(arity, size)| {
+            b.iter(|| complete_tree(arity, size))
+        });
+    }
+    group.finish();
+}
+
+fn traversal(c: &mut Criterion) {
+    let orders = [
+        ("depth_first_pre", DepthFirstOrder::PreOrder),
+        ("depth_first_post", DepthFirstOrder::PostOrder),
+    ];
+
+    let mut group = c.benchmark_group("breadth_first");
+    for (arity, size) in parameters() {
+        let tree = complete_tree(arity, size);
+        group.bench_with_input(id(arity, size), &tree, |b, tree| {
+            b.iter(|| tree.breadth_first_iter().map(|n| *n.value()).sum::<usize>())
+        });
+    }
+    group.finish();
+
+    for (name, order) in orders {
+        let mut group = c.benchmark_group(name);
+        for (arity, size) in parameters() {
+            let tree = complete_tree(arity, size);
+            group.bench_with_input(id(arity, size), &tree, |b, tree| {
+                b.iter(|| {
+                    tree.depth_first_iter(order)
+                        .map(|n| *n.value())
+                        .sum::<usize>()
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+// a chain is as deep as it is long, so consuming it should still scale linearly with its length
+fn owned_traversal_of_a_chain(c: &mut Criterion) {
+    let orders = [
+        ("into_depth_first_pre_chain", DepthFirstOrder::PreOrder),
+        ("into_depth_first_post_chain", DepthFirstOrder::PostOrder),
+    ];
+
+    for (name, order) in orders {
+        let mut group = c.benchmark_group(name);
+        for size in SIZES {
+            let tree = chain(size);
+            group.bench_with_input(id(1, size), &tree, |b, tree| {
+                b.iter_batched(
+                    || tree.clone(),
+                    |tree| tree.into_depth_first_iterator(order).sum::<usize>(),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+// the clone is included in the time of the structural changes
+fn structural_changes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove");
+    for (arity, size) in parameters() {
+        let tree = complete_tree(arity, size);
+        group.bench_with_input(id(arity, size), &tree, |b, tree| {
+            b.iter(|| {
+                let mut tree = tree.clone();
+                let mut root = tree.root_mut().expect("the tree should not be empty");
+                for child_offset in 0..arity {
+                    root.remove_child_value(child_offset);
+                }
+                tree
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("clear");
+    for (arity, size) in parameters() {
+        let tree = complete_tree(arity, size);
+        group.bench_with_input(id(arity, size), &tree, |b, tree| {
+            b.iter(|| {
+                let mut tree = tree.clone();
+                tree.clear();
+                tree
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("split_off");
+    for (arity, size) in parameters() {
+        let tree = complete_tree(arity, size);
+        group.bench_with_input(id(arity, size), &tree, |b, tree| {
+            b.iter(|| {
+                let mut tree = tree.clone();
+                let root = tree.root_mut().expect("the tree should not be empty");
+                let child = root.to_child(0).expect("the root should have a child");
+                child.split_off()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    for size in SIZES {
+        let tree = search_tree(size);
+        group.bench_with_input(id(2, size), &tree, |b, tree| {
+            b.iter(|| {
+                (0..size)
+                    .step_by(7)
+                    .filter(|value| {
+                        let (node, _) = tree
+                            .descend_while(|current| match value.cmp(current) {
+                                Ordering::Less => Some(0),
+                                Ordering::Equal => None,
+                                Ordering::Greater => Some(1),
+                            })
+                            .expect("the tree should not be empty");
+                        node.value() == value
+                    })
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn parameters() -> impl Iterator<Item = (usize, usize)> {
+    SIZES
+        .into_iter()
+        .flat_map(|size| ARITIES.into_iter().map(move |arity| (arity, size)))
+}
+
+fn id(arity: usize, size: usize) -> BenchmarkId {
+    BenchmarkId::new(arity.to_string(), size)
+}
+
+/// Builds a tree with the first `size` nodes of a complete tree, each valued by its index.
+fn complete_tree(arity: usize, size: usize) -> EytzingerTree<usize> {
+    let mut paths = vec![NodePath::root()];
+    let mut tree = EytzingerTree::new(arity);
+
+    for index in 0..size {
+        let path = paths[index].clone();
+        tree.set_value_at_path(&path, index)
+            .expect("the parent should exist");
+
+        for child_offset in 0..arity {
+            if paths.len() == size {
+                break;
+            }
+            paths.push(path.child(child_offset));
+        }
+    }
+
+    tree
+}
+
+/// Builds a tree where every node but the last has a single child, each valued by its depth.
+fn chain(len: usize) -> EytzingerTree<usize> {
+    let mut tree = EytzingerTree::new(1);
+    {
+        let mut node = tree.set_root_value(0);
+        for value in 1..len {
+            node = node.to_child_entry(0).or_insert(value);
+        }
+    }
+    tree
+}
+
+/// Builds a balanced binary search tree of the values `0..size`.
+fn search_tree(size: usize) -> EytzingerTree<usize> {
+    let tree: ScapegoatTree<_> = (0..size).collect();
+    tree.tree().clone()
+}
+
+criterion_group!(
+    benches,
+    construction,
+    traversal,
+    owned_traversal_of_a_chain,
+    structural_changes,
+    search
+);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+/// What to do with a node whose parent was not given when building a tree from the paths of its
+/// values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AncestorPolicy {
+    /// Building the tree fails with `Error::OrphanInsert`.
+    Require,
+    /// The node and its descendants are left out of the tree.
+    Discard,
+}
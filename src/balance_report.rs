@@ -0,0 +1,98 @@
+use crate::{Depth, EytzingerTree};
+
+/// Statistics about the shape of an Eytzinger tree, see `EytzingerTree::balance_report`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceReport {
+    /// The mean depth of every node, the root has a depth of zero. This is zero for an empty tree.
+    pub average_depth: f64,
+
+    /// The depth of the deepest node or `None` for an empty tree.
+    pub max_depth: Option<Depth>,
+
+    /// A tree with the same shape where each value is the balance factor of the subtree at that
+    /// node. This is `(largest + 1) / (smallest + 1)` where `largest` and `smallest` are the number
+    /// of nodes in the largest and smallest child subtrees, counting vacant children as empty
+    /// subtrees. A perfectly balanced subtree, including a leaf, has a balance factor of `1`.
+    pub balance_factors: EytzingerTree<f64>,
+}
+
+impl BalanceReport {
+    pub(crate) fn new<N>(tree: &EytzingerTree<N>) -> Self {
+        let mut depth_sum = 0;
+        let mut max_depth = None;
+
+        for (depth, level) in tree.index_calculator.level_ranges().enumerate() {
+            if level.start >= tree.nodes.len() {
+                break;
+            }
+
+            let level_len = tree.nodes[level.start..level.end.min(tree.nodes.len())]
+                .iter()
+                .flatten()
+                .count();
+            if level_len > 0 {
+                depth_sum += depth * level_len;
+                max_depth = Some(Depth::saturating_from_usize(depth));
+            }
+        }
+
+        let average_depth = if tree.is_empty() {
+            0.0
+        } else {
+            depth_sum as f64 / tree.len() as f64
+        };
+
+        let max_children_per_node = tree.max_children_per_node();
+        let balance_factors = tree
+            .fold_up(|_, children: &[(usize, f64)]| {
+                let len = 1 + children.iter().map(|(len, _)| len).sum::<usize>();
+                let largest = children.iter().map(|(len, _)| *len).max().unwrap_or(0);
+                let smallest = if children.len() < max_children_per_node {
+                    0
+                } else {
+                    children.iter().map(|(len, _)| *len).min().unwrap_or(0)
+                };
+
+                (len, (largest + 1) as f64 / (smallest + 1) as f64)
+            })
+            .map(|(_, balance_factor)| balance_factor);
+
+        Self {
+            average_depth,
+            max_depth,
+            balance_factors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EytzingerTree;
+
+    #[test]
+    fn empty_tree_has_no_depth() {
+        let report = EytzingerTree::<u32>::new(3).balance_report();
+
+        assert_eq!(report.average_depth, 0.0);
+        assert_eq!(report.max_depth, None);
+        assert!(report.balance_factors.is_empty());
+    }
+
+    #[test]
+    fn chain_is_unbalanced_at_every_level() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(0)
+            .set_child_value(1, 1)
+            .set_child_value(1, 2);
+
+        let report = tree.balance_report();
+        assert_eq!(report.average_depth, 1.0);
+
+        let factors: Vec<_> = report
+            .balance_factors
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(factors, vec![3.0, 2.0, 1.0]);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{EytzingerTree, Node, NodePath};
+
+/// Chooses which subtree to evict when a `BoundedEytzingerTree` is full.
+pub trait EvictionPolicy<N> {
+    /// Chooses the root of the subtree to evict to make room for a node at `insert_path`.
+    ///
+    /// # Returns
+    ///
+    /// The path of the subtree to evict or `None` if the insert should fail instead. Choosing an
+    /// ancestor of `insert_path` also fails the insert, leaving the tree unchanged.
+    fn choose_eviction(
+        &mut self,
+        tree: &EytzingerTree<N>,
+        insert_path: &NodePath,
+    ) -> Option<NodePath>;
+
+    /// Called with each subtree after it has been evicted. By default the subtree is dropped.
+    fn evicted(&mut self, _subtree: EytzingerTree<N>) {}
+}
+
+impl<N, F> EvictionPolicy<N> for F
+where
+    F: FnMut(&EytzingerTree<N>, &NodePath) -> Option<NodePath>,
+{
+    fn choose_eviction(
+        &mut self,
+        tree: &EytzingerTree<N>,
+        insert_path: &NodePath,
+    ) -> Option<NodePath> {
+        self(tree, insert_path)
+    }
+}
+
+/// An eviction policy which never evicts, inserts into a full tree fail.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RejectInserts;
+
+impl<N> EvictionPolicy<N> for RejectInserts {
+    fn choose_eviction(&mut self, _: &EytzingerTree<N>, _: &NodePath) -> Option<NodePath> {
+        None
+    }
+}
+
+/// An eviction policy which evicts the deepest leaf, the last one in breadth-first order. The
+/// ancestors of the node being inserted are never evicted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EvictDeepest;
+
+impl<N> EvictionPolicy<N> for EvictDeepest {
+    fn choose_eviction(
+        &mut self,
+        tree: &EytzingerTree<N>,
+        insert_path: &NodePath,
+    ) -> Option<NodePath> {
+        let insert_index = tree.path_index(insert_path);
+
+        (0..tree.nodes.len())
+            .rev()
+            .filter(|&index| tree.node(index).is_some())
+            .find(|&index| match insert_index {
+                Some(insert_index) => !tree.index_calculator.is_ancestor(index, insert_index),
+                None => true,
+            })
+            .map(|index| tree.path(index))
+    }
+}
+
+/// An Eytzinger tree which never holds more than a maximum number of nodes.
+///
+/// When an insert would exceed the maximum the eviction policy is asked to choose subtrees to
+/// remove until there is room, if it declines the insert fails.
+#[derive(Debug, Clone)]
+pub struct BoundedEytzingerTree<N, P = RejectInserts> {
+    tree: EytzingerTree<N>,
+    max_len: usize,
+    policy: P,
+}
+
+impl<N> BoundedEytzingerTree<N> {
+    /// Creates a new bounded tree which rejects inserts once it holds `max_len` nodes.
+    pub fn new(max_children_per_node: usize, max_len: usize) -> Self {
+        Self::with_eviction_policy(max_children_per_node, max_len, RejectInserts)
+    }
+}
+
+impl<N, P> BoundedEytzingerTree<N, P>
+where
+    P: EvictionPolicy<N>,
+{
+    /// Creates a new bounded tree which uses the specified policy to make room once it holds
+    /// `max_len` nodes.
+    pub fn with_eviction_policy(max_children_per_node: usize, max_len: usize, policy: P) -> Self {
+        Self {
+            tree: EytzingerTree::new(max_children_per_node),
+            max_len,
+            policy,
+        }
+    }
+
+    /// Gets the maximum number of nodes.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Gets the number of nodes.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Gets whether there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Gets the underlying tree.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Gets the eviction policy.
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Gets the underlying tree, consuming the bounded tree.
+    pub fn into_tree(self) -> EytzingerTree<N> {
+        self.tree
+    }
+
+    /// Gets the root node, `None` if there was no root node.
+    pub fn root(&self) -> Option<Node<'_, N>> {
+        self.tree.root()
+    }
+
+    /// Sets the value of the node at the specified path. Replacing an existing value never
+    /// requires an eviction.
+    ///
+    /// # Returns
+    ///
+    /// The old value if there was one, otherwise `Err` with the value if the parent of the path
+    /// does not exist or no room could be made.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{bounded::{BoundedEytzingerTree, EvictDeepest}, NodePath};
+    ///
+    /// let mut tree = BoundedEytzingerTree::with_eviction_policy(2, 2, EvictDeepest);
+    /// tree.insert(&NodePath::root(), 1).unwrap();
+    /// tree.insert(&NodePath::from(vec![0]), 2).unwrap();
+    /// tree.insert(&NodePath::from(vec![1]), 3).unwrap();
+    ///
+    /// let values: Vec<_> = tree.tree().breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 3]);
+    /// ```
+    pub fn insert(&mut self, path: &NodePath, value: N) -> Result<Option<N>, N> {
+        let index = match self.tree.path_index(path) {
+            Some(index) => index,
+            None => return Err(value),
+        };
+
+        if self.tree.node(index).is_some() {
+            return Ok(self.tree.replace_value(index, value));
+        }
+
+        if !self.has_parent(index) {
+            return Err(value);
+        }
+
+        while self.tree.len() >= self.max_len {
+            if !self.evict(index, path) {
+                return Err(value);
+            }
+        }
+
+        self.tree.set_value(index, value);
+        Ok(None)
+    }
+
+    /// Removes the node at the specified path along with all of its children.
+    ///
+    /// # Returns
+    ///
+    /// The removed value if there was one.
+    pub fn remove(&mut self, path: &NodePath) -> Option<N> {
+        let index = self.tree.path_index(path)?;
+        self.tree.remove(index)
+    }
+
+    fn has_parent(&self, index: usize) -> bool {
+        match self.tree.parent_index(index) {
+            Some(parent_index) => self.tree.node(parent_index).is_some(),
+            None => true,
+        }
+    }
+
+    fn evict(&mut self, insert_index: usize, insert_path: &NodePath) -> bool {
+        // evicting an ancestor of the insert would remove the parent the node is inserted under,
+        // so it is treated the same as the policy declining
+        let evicted_index = self
+            .policy
+            .choose_eviction(&self.tree, insert_path)
+            .and_then(|evicted_path| self.tree.path_index(&evicted_path))
+            .filter(|&evicted_index| self.tree.node(evicted_index).is_some())
+            .filter(|&evicted_index| {
+                !self
+                    .tree
+                    .index_calculator
+                    .is_ancestor(evicted_index, insert_index)
+            });
+
+        match evicted_index {
+            Some(evicted_index) => {
+                let evicted = self.tree.split_off(evicted_index);
+                self.policy.evicted(evicted);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bounded::{BoundedEytzingerTree, EvictDeepest},
+        EytzingerTree, NodePath,
+    };
+
+    #[test]
+    fn insert_fails_when_full() {
+        let mut tree = BoundedEytzingerTree::new(2, 2);
+
+        assert_eq!(tree.insert(&NodePath::root(), 1), Ok(None));
+        assert_eq!(tree.insert(&NodePath::from(vec![0]), 2), Ok(None));
+        assert_eq!(tree.insert(&NodePath::from(vec![1]), 3), Err(3));
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn insert_replaces_when_full() {
+        let mut tree = BoundedEytzingerTree::new(2, 1);
+
+        tree.insert(&NodePath::root(), 1).unwrap();
+        assert_eq!(tree.insert(&NodePath::root(), 2), Ok(Some(1)));
+    }
+
+    #[test]
+    fn insert_fails_without_parent() {
+        let mut tree = BoundedEytzingerTree::new(2, 5);
+
+        assert_eq!(tree.insert(&NodePath::from(vec![0]), 1), Err(1));
+    }
+
+    #[test]
+    fn evict_deepest_keeps_ancestors() {
+        let mut tree = BoundedEytzingerTree::with_eviction_policy(2, 3, EvictDeepest);
+
+        tree.insert(&NodePath::root(), 1).unwrap();
+        tree.insert(&NodePath::from(vec![0]), 2).unwrap();
+        tree.insert(&NodePath::from(vec![1]), 3).unwrap();
+        tree.insert(&NodePath::from(vec![0, 0]), 4).unwrap();
+
+        let values: Vec<_> = tree
+            .tree()
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn evicted_subtrees_are_passed_to_the_policy() {
+        struct Collect(Vec<EytzingerTree<u32>>);
+
+        impl crate::bounded::EvictionPolicy<u32> for Collect {
+            fn choose_eviction(
+                &mut self,
+                _: &EytzingerTree<u32>,
+                _: &NodePath,
+            ) -> Option<NodePath> {
+                Some(NodePath::from(vec![0]))
+            }
+
+            fn evicted(&mut self, subtree: EytzingerTree<u32>) {
+                self.0.push(subtree);
+            }
+        }
+
+        let mut tree = BoundedEytzingerTree::with_eviction_policy(2, 3, Collect(vec![]));
+        tree.insert(&NodePath::root(), 1).unwrap();
+        tree.insert(&NodePath::from(vec![0]), 2).unwrap();
+        tree.insert(&NodePath::from(vec![0, 1]), 3).unwrap();
+        tree.insert(&NodePath::from(vec![1]), 4).unwrap();
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.policy().0.len(), 1);
+        assert_eq!(tree.policy().0[0].len(), 2);
+    }
+
+    #[test]
+    fn insert_fails_without_changes_when_an_ancestor_is_chosen() {
+        let evict_root = |_: &EytzingerTree<u32>, _: &NodePath| Some(NodePath::root());
+        let mut tree = BoundedEytzingerTree::with_eviction_policy(2, 3, evict_root);
+
+        tree.insert(&NodePath::root(), 1).unwrap();
+        tree.insert(&NodePath::from(vec![0]), 2).unwrap();
+        tree.insert(&NodePath::from(vec![1]), 3).unwrap();
+
+        assert_eq!(tree.insert(&NodePath::from(vec![0, 0]), 4), Err(4));
+        assert_eq!(tree.len(), 3);
+
+        let values: Vec<_> = tree
+            .tree()
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::EytzingerTree;
+use std::hash::{Hash, Hasher};
+
+/// A node of a pointer-based tree, each child is boxed separately.
+///
+/// This is a simple structure to convert to and from when moving recursive data onto the
+/// Eytzinger layout. The child at offset `i` is `children[i]`, `None` children may be omitted from
+/// the end.
+///
+/// The conversions, cloning, comparing and hashing do not recurse. Dropping and formatting with
+/// `Debug` are the exceptions, as with any tree of boxes they recurse once per level so a very
+/// deep `BoxedNode` may overflow the stack. Dropping can not be made iterative without preventing
+/// the fields from being moved out of a node, converting it into an `EytzingerTree` first avoids
+/// the recursion.
+#[derive(Debug)]
+pub struct BoxedNode<N> {
+    /// The value of this node.
+    pub value: N,
+    /// The children of this node by child offset.
+    pub children: Vec<Option<Box<BoxedNode<N>>>>,
+}
+
+impl<N> BoxedNode<N> {
+    /// Creates a new node with no children.
+    pub fn new(value: N) -> Self {
+        Self {
+            value,
+            children: vec![],
+        }
+    }
+
+    /// Converts this node and all of its children into an Eytzinger tree with the specified
+    /// maximum number of child nodes per parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero or a node has more children than it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::boxed::BoxedNode;
+    ///
+    /// let root = BoxedNode {
+    ///     value: 1,
+    ///     children: vec![None, Some(Box::new(BoxedNode::new(2)))],
+    /// };
+    ///
+    /// let tree = root.into_tree(4);
+    /// assert_eq!(tree.max_children_per_node(), 4);
+    /// assert_eq!(tree.root().and_then(|n| n.child(1)).map(|n| *n.value()), Some(2));
+    /// ```
+    pub fn into_tree(self, max_children_per_node: usize) -> EytzingerTree<N> {
+        let mut tree = EytzingerTree::new(max_children_per_node);
+
+        let mut pending = vec![(self, 0)];
+        while let Some((node, index)) = pending.pop() {
+            tree.set_value(index, node.value);
+
+            for (child_offset, child) in node.children.into_iter().enumerate() {
+                if let Some(child) = child {
+                    pending.push((*child, tree.child_index(index, child_offset)));
+                }
+            }
+        }
+
+        tree
+    }
+
+    /// Gets the largest number of children of this node or any of its descendants, at least one.
+    fn max_children_per_node(&self) -> usize {
+        let mut max_children_per_node = 1;
+
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            max_children_per_node = max_children_per_node.max(node.children.len());
+            pending.extend(node.children.iter().flatten().map(|child| &**child));
+        }
+
+        max_children_per_node
+    }
+}
+
+impl<N> Clone for BoxedNode<N>
+where
+    N: Clone,
+{
+    fn clone(&self) -> Self {
+        // a post-order traversal, each node is built once all of its children have been
+        let mut pending = vec![(self, 0)];
+        let mut built: Vec<Option<Box<Self>>> = vec![];
+
+        loop {
+            let (node, next_child) = pending.last_mut().expect("the root should be pending");
+            let node: &Self = node;
+            let child = node.children.get(*next_child);
+            *next_child += 1;
+
+            match child {
+                Some(Some(child)) => pending.push((child, 0)),
+                Some(None) => built.push(None),
+                None => {
+                    pending.pop();
+                    let children = built.split_off(built.len() - node.children.len());
+                    let clone = BoxedNode {
+                        value: node.value.clone(),
+                        children,
+                    };
+
+                    if pending.is_empty() {
+                        return clone;
+                    }
+                    built.push(Some(Box::new(clone)));
+                }
+            }
+        }
+    }
+}
+
+impl<N> PartialEq for BoxedNode<N>
+where
+    N: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        let mut pending = vec![(self, other)];
+        while let Some((node, other)) = pending.pop() {
+            if node.value != other.value || node.children.len() != other.children.len() {
+                return false;
+            }
+
+            for children in node.children.iter().zip(&other.children) {
+                match children {
+                    (Some(child), Some(other)) => pending.push((child, other)),
+                    (None, None) => {}
+                    _ => return false,
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl<N> Eq for BoxedNode<N> where N: Eq {}
+
+impl<N> Hash for BoxedNode<N>
+where
+    N: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // a pre-order traversal, hashing which children are present keeps the shape unambiguous
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            node.value.hash(state);
+            node.children.len().hash(state);
+
+            for child in &node.children {
+                child.is_some().hash(state);
+            }
+            pending.extend(node.children.iter().rev().flatten().map(|child| &**child));
+        }
+    }
+}
+
+impl<N> From<BoxedNode<N>> for EytzingerTree<N> {
+    /// Converts the node into an Eytzinger tree whose maximum number of child nodes per parent is
+    /// the largest number of children of any node.
+    fn from(root: BoxedNode<N>) -> Self {
+        let max_children_per_node = root.max_children_per_node();
+        root.into_tree(max_children_per_node)
+    }
+}
+
+impl<N> From<EytzingerTree<N>> for Option<BoxedNode<N>> {
+    /// Converts the tree into boxed nodes, `None` if the tree was empty. Vacant children at the end
+    /// of each node are omitted.
+    fn from(tree: EytzingerTree<N>) -> Self {
+        let index_calculator = tree.index_calculator;
+        let mut built: Vec<Option<BoxedNode<N>>> = Vec::with_capacity(tree.nodes.len());
+        built.resize_with(tree.nodes.len(), || None);
+
+        // children always come after their parents so building from the back means every child is
+        // complete before its parent
+        for (index, value) in tree.nodes.into_iter().enumerate().rev() {
+            if let Some(value) = value {
+                let mut children: Vec<_> = (0..index_calculator.max_children_per_node())
+                    .map(|child_offset| {
+                        index_calculator
+                            .checked_child_index(index, child_offset)
+                            .and_then(|child_index| built.get_mut(child_index))
+                            .and_then(|child| child.take())
+                            .map(Box::new)
+                    })
+                    .collect();
+
+                while let Some(None) = children.last() {
+                    children.pop();
+                }
+
+                built[index] = Some(BoxedNode { value, children });
+            }
+        }
+
+        built.into_iter().next().and_then(|root| root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{boxed::BoxedNode, EytzingerTree};
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    fn boxed(value: u32, children: Vec<Option<BoxedNode<u32>>>) -> BoxedNode<u32> {
+        BoxedNode {
+            value,
+            children: children.into_iter().map(|c| c.map(Box::new)).collect(),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_tree() {
+        let root = boxed(
+            1,
+            vec![
+                Some(boxed(2, vec![None, None, Some(BoxedNode::new(4))])),
+                None,
+                Some(BoxedNode::new(3)),
+            ],
+        );
+
+        let tree = EytzingerTree::from(root.clone());
+        assert_eq!(tree.max_children_per_node(), 3);
+        assert_eq!(tree.len(), 4);
+
+        assert_eq!(Option::<BoxedNode<u32>>::from(tree), Some(root));
+    }
+
+    #[test]
+    fn clone_and_eq_do_not_recurse() {
+        let mut chain = BoxedNode::new(0);
+        for value in 1..100_000 {
+            chain = boxed(value, vec![Some(chain)]);
+        }
+
+        let clone = chain.clone();
+        assert!(clone == chain);
+
+        let hash = |node: &BoxedNode<u32>| {
+            let mut hasher = DefaultHasher::new();
+            node.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&clone), hash(&chain));
+
+        // dropping does recurse, so the chains are converted into trees to be dropped
+        assert_eq!(EytzingerTree::from(clone).len(), 100_000);
+        assert_eq!(EytzingerTree::from(chain).len(), 100_000);
+    }
+
+    #[test]
+    fn differently_shaped_nodes_are_not_equal() {
+        let left = boxed(1, vec![Some(BoxedNode::new(2))]);
+        let right = boxed(1, vec![None, Some(BoxedNode::new(2))]);
+
+        assert_ne!(left, right);
+        assert_ne!(left, boxed(1, vec![Some(BoxedNode::new(2)), None]));
+        assert_eq!(left, boxed(1, vec![Some(BoxedNode::new(2))]));
+    }
+
+    #[test]
+    fn empty_tree_has_no_root() {
+        let tree = EytzingerTree::<u32>::new(2);
+
+        assert_eq!(Option::<BoxedNode<u32>>::from(tree), None);
+    }
+
+    #[test]
+    fn leaf_is_a_single_node_tree() {
+        let tree = EytzingerTree::from(BoxedNode::new(7));
+
+        assert_eq!(tree.max_children_per_node(), 1);
+        assert_eq!(tree.root().map(|n| *n.value()), Some(7));
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn into_tree_panics_for_too_many_children() {
+        boxed(1, vec![None, None, Some(BoxedNode::new(2))]).into_tree(2);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{Depth, EytzingerIndexCalculator, EytzingerTree};
+use std::num::NonZeroUsize;
+
+/// Configures an Eytzinger tree before it is created.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{Depth, EytzingerTreeBuilder};
+///
+/// let tree = EytzingerTreeBuilder::new()
+///     .max_children_per_node(4)
+///     .depth_capacity(Depth(3))
+///     .build::<u32>();
+///
+/// assert_eq!(tree.max_children_per_node(), 4);
+/// assert!(tree.capacity() >= 1 + 4 + 16 + 64);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EytzingerTreeBuilder {
+    arity: NonZeroUsize,
+    capacity: usize,
+    pad_arity: bool,
+}
+
+impl EytzingerTreeBuilder {
+    /// Creates a new builder for binary trees without any reserved storage.
+    pub fn new() -> Self {
+        Self {
+            arity: NonZeroUsize::new(2).expect("2 is not zero"),
+            capacity: 0,
+            pad_arity: false,
+        }
+    }
+
+    /// Sets the maximum number of child nodes per parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero, use `arity` to avoid this.
+    pub fn max_children_per_node(self, max_children_per_node: usize) -> Self {
+        let arity = NonZeroUsize::new(max_children_per_node)
+            .expect("max_children_per_node should not be zero");
+
+        self.arity(arity)
+    }
+
+    /// Sets the maximum number of child nodes per parent.
+    pub fn arity(mut self, max_children_per_node: NonZeroUsize) -> Self {
+        self.arity = max_children_per_node;
+        self
+    }
+
+    /// Sets whether the children of each node are stored in a block padded to the next power of
+    /// two, while the tree keeps the same max children per node. Finding the parent of a node is
+    /// then a shift rather than a division and each block of children is a power of two in size,
+    /// at the cost of vacant padding in every level.
+    ///
+    /// Padded and unpadded trees are laid out differently so they are never equal, even with the
+    /// same nodes, use `EytzingerTree::normalized` to compare them. Grafting between them is
+    /// supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTreeBuilder};
+    ///
+    /// let mut tree = EytzingerTreeBuilder::new()
+    ///     .max_children_per_node(3)
+    ///     .pad_arity(true)
+    ///     .build::<u32>();
+    ///
+    /// tree.set_root_value(1).set_child_value(2, 2).set_child_value(2, 3);
+    ///
+    /// assert!(tree.is_arity_padded());
+    /// assert_eq!(tree.max_children_per_node(), 3);
+    /// assert_eq!(tree.values_at_depth(Depth(1)), &[None, None, Some(2), None]);
+    /// ```
+    pub fn pad_arity(mut self, pad_arity: bool) -> Self {
+        self.pad_arity = pad_arity;
+        self
+    }
+
+    /// Reserves storage for at least the specified number of nodes, when reserving by depth as
+    /// well the larger of the two is reserved.
+    ///
+    /// Nodes are stored by position so this is only the number of nodes which may be stored
+    /// without reallocating if they are the first nodes of a breadth-first traversal of a
+    /// complete tree.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = self.capacity.max(capacity);
+        self
+    }
+
+    /// Reserves storage for every node down to and including the specified depth, the root has a
+    /// depth of zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of nodes down to the depth overflows a `usize`.
+    pub fn depth_capacity(self, depth: Depth) -> Self {
+        let level = self
+            .index_calculator()
+            .level_indexes(depth.to_usize())
+            .expect("the depth should not overflow the index");
+
+        self.capacity(level.end)
+    }
+
+    /// Creates a new empty Eytzinger tree with this configuration.
+    pub fn build<N>(&self) -> EytzingerTree<N> {
+        let mut tree = EytzingerTree::with_index_calculator(self.index_calculator());
+        tree.nodes.reserve_exact(self.capacity);
+        tree
+    }
+
+    fn index_calculator(&self) -> EytzingerIndexCalculator {
+        let index_calculator = EytzingerIndexCalculator::with_arity(self.arity);
+        if self.pad_arity {
+            index_calculator.padded()
+        } else {
+            index_calculator
+        }
+    }
+}
+
+impl Default for EytzingerTreeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Depth, EytzingerTree, EytzingerTreeBuilder};
+
+    #[test]
+    fn depth_capacity_of_unary_trees() {
+        let tree = EytzingerTreeBuilder::new()
+            .max_children_per_node(1)
+            .depth_capacity(Depth(9))
+            .capacity(5)
+            .build::<u8>();
+
+        assert!(tree.capacity() >= 10);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn padded_trees_behave_like_unpadded_trees() {
+        let mut padded = EytzingerTreeBuilder::new()
+            .max_children_per_node(3)
+            .pad_arity(true)
+            .depth_capacity(Depth(2))
+            .build::<u32>();
+        let mut unpadded = EytzingerTree::<u32>::new(3);
+
+        for tree in [&mut padded, &mut unpadded] {
+            {
+                let mut root = tree.set_root_value(1);
+                root.set_child_value(2, 2).set_child_value(1, 3);
+                root.set_child_value(0, 4);
+            }
+            tree.root_mut().unwrap().extend_breadth_first(5..9);
+        }
+
+        assert!(padded.capacity() >= 1 + 4 + 16);
+        assert_ne!(padded, unpadded);
+        assert_eq!(padded.normalized(), unpadded.normalized());
+
+        let grafted = unpadded
+            .root_mut()
+            .unwrap()
+            .to_child(2)
+            .ok()
+            .unwrap()
+            .split_off();
+        padded.root_mut().unwrap().graft_child(1, grafted).unwrap();
+        let values: Vec<_> = padded
+            .root()
+            .and_then(|n| n.child(1))
+            .unwrap()
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![2, 3]);
+
+        let subtrees = padded.root_mut().unwrap().detach_children();
+        assert_eq!(subtrees.len(), 3);
+        assert!(subtrees.iter().all(|subtree| subtree.is_arity_padded()));
+        assert_eq!(padded.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn depth_capacity_panics_on_overflow() {
+        EytzingerTreeBuilder::new().depth_capacity(Depth(usize::BITS));
+    }
+}
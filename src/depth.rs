@@ -0,0 +1,71 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    num::TryFromIntError,
+};
+
+/// The depth of a node in an Eytzinger tree, the root has a depth of zero.
+///
+/// Depths are stored as a `u32` as no tree with more than one child per node can be deep enough
+/// to exceed it, see `EytzingerTree::max_representable_depth`. Arithmetic on depths is checked so
+/// it cannot silently wrap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Depth(pub u32);
+
+impl Depth {
+    /// The depth of the root node.
+    pub const ROOT: Depth = Depth(0);
+
+    /// Gets the depth as a number of levels below the root.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Gets the depth the specified number of levels below this, or `None` if it would overflow.
+    pub fn checked_add(self, levels: u32) -> Option<Depth> {
+        self.0.checked_add(levels).map(Depth)
+    }
+
+    /// Gets the depth the specified number of levels above this, or `None` if it would be above
+    /// the root.
+    pub fn checked_sub(self, levels: u32) -> Option<Depth> {
+        self.0.checked_sub(levels).map(Depth)
+    }
+
+    /// Gets the depth as a `usize`, saturating on platforms where it does not fit. No level that
+    /// deep can be stored there anyway.
+    pub(crate) fn to_usize(self) -> usize {
+        usize::try_from(self.0).unwrap_or(usize::MAX)
+    }
+
+    /// Gets the depth from a `usize`, saturating when it does not fit.
+    pub(crate) fn saturating_from_usize(depth: usize) -> Depth {
+        Depth(u32::try_from(depth).unwrap_or(u32::MAX))
+    }
+}
+
+impl From<u32> for Depth {
+    fn from(depth: u32) -> Self {
+        Depth(depth)
+    }
+}
+
+impl From<Depth> for u32 {
+    fn from(depth: Depth) -> Self {
+        depth.0
+    }
+}
+
+impl TryFrom<usize> for Depth {
+    type Error = TryFromIntError;
+
+    fn try_from(depth: usize) -> Result<Self, Self::Error> {
+        u32::try_from(depth).map(Depth)
+    }
+}
+
+impl Display for Depth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
@@ -1,75 +1,88 @@
 use crate::{
-    BreadthFirstIter, DepthFirstIter, DepthFirstOrder, EytzingerTree, Node, NodeChildIter, NodeMut,
+    BreadthFirstIter, DepthFirstIter, DepthFirstOrder, EytzingerTree, Node, NodeChildIter, NodePath,
+};
+use std::{
+    fmt::{self, Debug},
+    iter::FusedIterator,
 };
 
 /// An entry can be used to reference a node in an Eytzinger tree. The node may or may not have a
 /// value.
-#[derive(Debug)]
+///
+/// Entries only borrow the tree immutably so any number of them may exist at once, use
+/// `EntryMut` to insert or remove nodes.
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub enum Entry<'a, N>
 where
     N: 'a,
 {
     /// When the entry references a node which exists with a value.
-    Occupied(NodeMut<'a, N>),
+    Occupied(Node<'a, N>),
 
     /// When the entry references a non-existent node.
     Vacant(VacantEntry<'a, N>),
 }
 
+impl<'a, N> Copy for Entry<'a, N> {}
+
+impl<'a, N> Clone for Entry<'a, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
 /// For an entry where node does not exist.
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Hash)]
 pub struct VacantEntry<'a, N>
 where
     N: 'a,
 {
-    pub(crate) tree: &'a mut EytzingerTree<N>,
+    pub(crate) tree: &'a EytzingerTree<N>,
     pub(crate) index: usize,
 }
 
+impl<'a, N> Copy for VacantEntry<'a, N> {}
+
+impl<'a, N> Clone for VacantEntry<'a, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N> Debug for VacantEntry<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry")
+            .field("index", &self.index)
+            .field("path", &format_args!("{}", self.tree.path(self.index)))
+            .finish()
+    }
+}
+
 impl<'a, N> VacantEntry<'a, N> {
     /// Gets the Eytzinger tree this entry is for.
-    pub fn tree(&self) -> &EytzingerTree<N> {
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
         self.tree
     }
 
     /// Gets the parent of this entry or `None` is there was none (i.e. if this entry is for the root).
-    pub fn parent(&self) -> Option<Node<N>> {
+    pub fn parent(&self) -> Option<Node<'a, N>> {
         self.tree.parent(self.index)
     }
 
-    /// Gets the mutable parent of this entry or itself is there was none (i.e. if this entry is for the root).
-    pub fn to_parent(self) -> Result<NodeMut<'a, N>, Self> {
-        let index = self.index;
-        self.tree
-            .parent_mut(index)
-            .map_err(|tree| VacantEntry { tree, index })
-    }
-
-    /// Inserts a value at the referenced position.
-    ///
-    /// # Returns
-    ///
-    /// The new mutable node.
-    pub fn insert(self, value: N) -> NodeMut<'a, N> {
-        self.tree.set_value(self.index, value)
+    /// Gets the path from the root of the tree to this entry.
+    pub fn path(&self) -> NodePath {
+        self.tree.path(self.index)
     }
 
-    /// Inserts a value at the referenced position.
-    ///
-    /// # Returns
-    ///
-    /// The new mutable node.
-    pub fn insert_with<F>(self, value_factory: F) -> NodeMut<'a, N>
-    where
-        F: FnOnce() -> N,
-    {
-        self.tree.set_value(self.index, value_factory())
+    /// Gets the child offset of this entry within its parent, or `None` for the root.
+    pub fn child_offset(&self) -> Option<usize> {
+        self.tree.index_calculator.child_offset(self.index)
     }
 }
 
 impl<'a, N> Entry<'a, N> {
     /// Gets the Eytzinger tree this entry is for.
-    pub fn tree(&self) -> &EytzingerTree<N> {
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
         match self {
             Entry::Occupied(node) => node.tree(),
             Entry::Vacant(vacant_entry) => vacant_entry.tree(),
@@ -77,111 +90,50 @@ impl<'a, N> Entry<'a, N> {
     }
 
     /// Gets the parent of this entry or `None` is there was none (i.e. if this entry is for the root).
-    pub fn parent(&self) -> Option<Node<N>> {
+    pub fn parent(&self) -> Option<Node<'a, N>> {
         match self {
             Entry::Occupied(node) => node.parent(),
             Entry::Vacant(vacant_entry) => vacant_entry.parent(),
         }
     }
 
-    /// Gets the mutable parent of this entry or itself is there was none (i.e. if this entry is for the root).
-    pub fn to_parent(self) -> Result<NodeMut<'a, N>, Self> {
-        match self {
-            Entry::Occupied(node) => node.to_parent().map_err(|node| Entry::Occupied(node)),
-            Entry::Vacant(vacant_entry) => vacant_entry
-                .to_parent()
-                .map_err(|vacant_entry| Entry::Vacant(vacant_entry)),
-        }
-    }
-
-    /// Inserts a value at the referenced position if there is no node already there.
+    /// Gets the node this entry is for, if there is one.
     ///
     /// # Returns
     ///
-    /// The mutable node, this may be new or may have already existed.
-    pub fn or_insert(self, value: N) -> NodeMut<'a, N> {
-        match self {
-            Entry::Occupied(node) => node,
-            Entry::Vacant(vacant) => vacant.insert(value),
-        }
-    }
-
-    /// Inserts a value at the referenced position if there is no node already there.
+    /// The node if there was one, `None` otherwise.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// The mutable node, this may be new or may have already existed.
-    pub fn or_insert_with<F>(self, value_factory: F) -> NodeMut<'a, N>
-    where
-        F: FnOnce() -> N,
-    {
-        match self {
-            Entry::Occupied(node) => node,
-            Entry::Vacant(vacant) => vacant.insert_with(value_factory),
-        }
-    }
-
-    /// Modifies the value (if one exists).
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
     ///
-    /// # Returns
-    ///
-    /// The entry.
-    pub fn and_modify<F>(self, f: F) -> Self
-    where
-        F: FnOnce(&mut N),
-    {
-        match self {
-            Entry::Occupied(mut node) => {
-                f(node.value_mut());
-                Entry::Occupied(node)
-            }
-            entry @ Entry::Vacant(_) => entry,
-        }
-    }
-
-    /// Removes the node if one existed.
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(5).set_child_value(1, 3);
     ///
-    /// # Returns
+    /// let root = tree.root().unwrap();
+    /// let left = root.child_entry(0);
+    /// let right = root.child_entry(1);
     ///
-    /// The removed value if there was a node and the now vacant entry.
-    pub fn remove(self) -> (Option<N>, VacantEntry<'a, N>) {
+    /// assert!(left.node().is_none());
+    /// assert_eq!(right.node().map(|n| *n.value()), Some(3));
+    /// assert_eq!(left.parent(), right.parent());
+    /// ```
+    pub fn node(&self) -> Option<Node<'a, N>> {
         match self {
-            Entry::Occupied(node) => {
-                let (removed_value, vacant_entry) = node.remove();
-
-                (Some(removed_value), vacant_entry)
-            }
-            Entry::Vacant(vacant_entry) => (None, vacant_entry),
-        }
-    }
-
-    /// Gets the node this entry is for, if there is one.
-    ///
-    /// # Returns
-    ///
-    /// The node if there was one, `None` otherwise.
-    pub fn node(&self) -> Option<Node<N>> {
-        match self {
-            Entry::Occupied(node) => Some(node.as_node()),
+            Entry::Occupied(node) => Some(*node),
             Entry::Vacant(_) => None,
         }
     }
 
-    /// Gets the mutable node this entry is for, if there is one.
-    ///
-    /// # Returns
-    ///
-    /// The mutable node if there was one, `None` otherwise.
-    pub fn node_mut(self) -> Option<NodeMut<'a, N>> {
-        match self {
-            Entry::Occupied(node) => Some(node),
-            Entry::Vacant(_) => None,
-        }
+    /// Gets the number of nodes in the subtree at this entry, which is zero for a vacant entry.
+    pub fn subtree_len(&self) -> usize {
+        self.node().map_or(0, |node| node.subtree_len())
     }
 
     /// Gets an iterator over the immediate children of this node. This only includes children
     /// for which there is a node.
-    pub fn child_iter(&self) -> EntryIter<NodeChildIter<N>> {
+    pub fn child_iter(&self) -> EntryIter<NodeChildIter<'a, N>> {
         match self {
             Entry::Occupied(node) => EntryIter::Occupied(node.child_iter()),
             Entry::Vacant(_) => EntryIter::Vacant,
@@ -189,7 +141,7 @@ impl<'a, N> Entry<'a, N> {
     }
 
     /// Gets a depth-first iterator over this and all child nodes.
-    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> EntryIter<DepthFirstIter<N>> {
+    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> EntryIter<DepthFirstIter<'a, N>> {
         match self {
             Entry::Occupied(node) => EntryIter::Occupied(node.depth_first_iter(order)),
             Entry::Vacant(_) => EntryIter::Vacant,
@@ -197,7 +149,7 @@ impl<'a, N> Entry<'a, N> {
     }
 
     /// Gets a breadth-first iterator over this and all child nodes.
-    pub fn breadth_first_iter(&self) -> EntryIter<BreadthFirstIter<N>> {
+    pub fn breadth_first_iter(&self) -> EntryIter<BreadthFirstIter<'a, N>> {
         match self {
             Entry::Occupied(node) => EntryIter::Occupied(node.breadth_first_iter()),
             Entry::Vacant(_) => EntryIter::Vacant,
@@ -205,12 +157,58 @@ impl<'a, N> Entry<'a, N> {
     }
 }
 
+/// An iterator over the nodes of an entry, which yields nothing when the entry is vacant.
+///
+/// This behaves like an `Option` of the underlying iterator and forwards any of the iterator
+/// traits the underlying iterator implements.
 #[derive(Debug, Clone)]
 pub enum EntryIter<I> {
+    /// When the entry references a node which exists, iteration is delegated to `I`.
     Occupied(I),
+
+    /// When the entry references a non-existent node, nothing is yielded.
     Vacant,
 }
 
+impl<I> EntryIter<I> {
+    /// Returns whether this iterator was created from a vacant entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(5).set_child_value(1, 3);
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert!(root.child_entry(0).child_iter().is_vacant());
+    /// assert!(!root.child_entry(1).child_iter().is_vacant());
+    /// ```
+    pub fn is_vacant(&self) -> bool {
+        matches!(self, EntryIter::Vacant)
+    }
+
+    /// Returns whether this iterator was created from an occupied entry.
+    pub fn is_occupied(&self) -> bool {
+        !self.is_vacant()
+    }
+
+    /// Converts this into the underlying iterator, or `None` if the entry was vacant.
+    pub fn into_inner(self) -> Option<I> {
+        match self {
+            EntryIter::Occupied(iterator) => Some(iterator),
+            EntryIter::Vacant => None,
+        }
+    }
+}
+
+impl<I> From<Option<I>> for EntryIter<I> {
+    fn from(iterator: Option<I>) -> Self {
+        iterator.map_or(EntryIter::Vacant, EntryIter::Occupied)
+    }
+}
+
 impl<I> Iterator for EntryIter<I>
 where
     I: Iterator,
@@ -223,4 +221,76 @@ where
             EntryIter::Vacant => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            EntryIter::Occupied(iterator) => iterator.size_hint(),
+            EntryIter::Vacant => (0, Some(0)),
+        }
+    }
+}
+
+impl<I> DoubleEndedIterator for EntryIter<I>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            EntryIter::Occupied(iterator) => iterator.next_back(),
+            EntryIter::Vacant => None,
+        }
+    }
+}
+
+impl<I> ExactSizeIterator for EntryIter<I> where I: ExactSizeIterator {}
+
+impl<I> FusedIterator for EntryIter<I> where I: FusedIterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_iter_vacant_is_empty() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(5).set_child_value(1, 3);
+
+        let root = tree.root().unwrap();
+        let vacant = root
+            .child_entry(0)
+            .depth_first_iter(DepthFirstOrder::PreOrder);
+        assert!(vacant.is_vacant());
+        assert_eq!(vacant.size_hint(), (0, Some(0)));
+        assert_eq!(vacant.count(), 0);
+
+        let occupied = root.child_entry(1).breadth_first_iter();
+        assert!(occupied.is_occupied());
+        assert_eq!(occupied.map(|n| *n.value()).collect::<Vec<_>>(), [3]);
+    }
+
+    #[test]
+    fn entry_iter_delegates_to_inner() {
+        let mut iter = EntryIter::from(Some(vec![1, 2, 3].into_iter()));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.into_inner().map(|i| i.len()), Some(1));
+
+        let mut vacant = EntryIter::<std::vec::IntoIter<u32>>::from(None);
+        assert_eq!(vacant.len(), 0);
+        assert_eq!(vacant.next_back(), None);
+    }
+
+    #[test]
+    fn entry_mut_iters_match_entry() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1)
+            .set_child_value(0, 2)
+            .set_child_value(1, 3);
+
+        let expected: Vec<_> = tree.root_entry().child_iter().map(|n| *n.value()).collect();
+        let entry = tree.root_entry_mut();
+        let actual: Vec<_> = entry.child_iter().map(|n| *n.value()).collect();
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,250 @@
+use crate::{
+    entry::{Entry, EntryIter, VacantEntry},
+    BreadthFirstIter, DepthFirstIter, DepthFirstOrder, EytzingerTree, Node, NodeChildIter, NodeMut,
+    NodePath,
+};
+use std::fmt::{self, Debug};
+
+/// A mutable entry can be used to reference a node in an Eytzinger tree and to insert or remove
+/// it. The node may or may not have a value.
+#[derive(Debug)]
+pub enum EntryMut<'a, N>
+where
+    N: 'a,
+{
+    /// When the entry references a node which exists with a value.
+    Occupied(NodeMut<'a, N>),
+
+    /// When the entry references a non-existent node.
+    Vacant(VacantEntryMut<'a, N>),
+}
+
+/// For a mutable entry where node does not exist.
+pub struct VacantEntryMut<'a, N>
+where
+    N: 'a,
+{
+    pub(crate) tree: &'a mut EytzingerTree<N>,
+    pub(crate) index: usize,
+}
+
+impl<'a, N> Debug for VacantEntryMut<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_entry().fmt(f)
+    }
+}
+
+impl<'a, N> VacantEntryMut<'a, N> {
+    /// Gets the Eytzinger tree this entry is for.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        self.tree
+    }
+
+    /// Gets the parent of this entry or `None` is there was none (i.e. if this entry is for the root).
+    pub fn parent(&self) -> Option<Node<'_, N>> {
+        self.tree.parent(self.index)
+    }
+
+    /// Gets the mutable parent of this entry or itself is there was none (i.e. if this entry is for the root).
+    pub fn to_parent(self) -> Result<NodeMut<'a, N>, Self> {
+        let index = self.index;
+        self.tree
+            .parent_mut(index)
+            .map_err(|tree| VacantEntryMut { tree, index })
+    }
+
+    /// Gets the path from the root of the tree to this entry.
+    pub fn path(&self) -> NodePath {
+        self.as_entry().path()
+    }
+
+    /// Gets the child offset of this entry within its parent, or `None` for the root.
+    pub fn child_offset(&self) -> Option<usize> {
+        self.as_entry().child_offset()
+    }
+
+    /// Gets a view of this mutable entry as an immutable entry.
+    pub fn as_entry(&self) -> VacantEntry<'_, N> {
+        VacantEntry {
+            tree: self.tree,
+            index: self.index,
+        }
+    }
+
+    /// Inserts a value at the referenced position.
+    ///
+    /// # Returns
+    ///
+    /// The new mutable node.
+    pub fn insert(self, value: N) -> NodeMut<'a, N> {
+        self.tree.set_value(self.index, value)
+    }
+
+    /// Inserts a value at the referenced position.
+    ///
+    /// # Returns
+    ///
+    /// The new mutable node.
+    pub fn insert_with<F>(self, value_factory: F) -> NodeMut<'a, N>
+    where
+        F: FnOnce() -> N,
+    {
+        self.tree.set_value(self.index, value_factory())
+    }
+}
+
+impl<'a, N> EntryMut<'a, N> {
+    /// Gets the Eytzinger tree this entry is for.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        match self {
+            EntryMut::Occupied(node) => node.tree(),
+            EntryMut::Vacant(vacant_entry) => vacant_entry.tree(),
+        }
+    }
+
+    /// Gets the parent of this entry or `None` is there was none (i.e. if this entry is for the root).
+    pub fn parent(&self) -> Option<Node<'_, N>> {
+        match self {
+            EntryMut::Occupied(node) => node.parent(),
+            EntryMut::Vacant(vacant_entry) => vacant_entry.parent(),
+        }
+    }
+
+    /// Gets the mutable parent of this entry or itself is there was none (i.e. if this entry is for the root).
+    pub fn to_parent(self) -> Result<NodeMut<'a, N>, Self> {
+        match self {
+            EntryMut::Occupied(node) => node.to_parent().map_err(|node| EntryMut::Occupied(node)),
+            EntryMut::Vacant(vacant_entry) => vacant_entry
+                .to_parent()
+                .map_err(|vacant_entry| EntryMut::Vacant(vacant_entry)),
+        }
+    }
+
+    /// Gets a view of this mutable entry as an immutable entry.
+    pub fn as_entry(&self) -> Entry<'_, N> {
+        match self {
+            EntryMut::Occupied(node) => Entry::Occupied(node.as_node()),
+            EntryMut::Vacant(vacant_entry) => Entry::Vacant(vacant_entry.as_entry()),
+        }
+    }
+
+    /// Inserts a value at the referenced position if there is no node already there.
+    ///
+    /// # Returns
+    ///
+    /// The mutable node, this may be new or may have already existed.
+    pub fn or_insert(self, value: N) -> NodeMut<'a, N> {
+        match self {
+            EntryMut::Occupied(node) => node,
+            EntryMut::Vacant(vacant) => vacant.insert(value),
+        }
+    }
+
+    /// Inserts a value at the referenced position if there is no node already there.
+    ///
+    /// # Returns
+    ///
+    /// The mutable node, this may be new or may have already existed.
+    pub fn or_insert_with<F>(self, value_factory: F) -> NodeMut<'a, N>
+    where
+        F: FnOnce() -> N,
+    {
+        match self {
+            EntryMut::Occupied(node) => node,
+            EntryMut::Vacant(vacant) => vacant.insert_with(value_factory),
+        }
+    }
+
+    /// Modifies the value (if one exists).
+    ///
+    /// # Returns
+    ///
+    /// The entry.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut N),
+    {
+        match self {
+            EntryMut::Occupied(mut node) => {
+                f(node.value_mut());
+                EntryMut::Occupied(node)
+            }
+            entry @ EntryMut::Vacant(_) => entry,
+        }
+    }
+
+    /// Removes the node if one existed.
+    ///
+    /// # Returns
+    ///
+    /// The removed value if there was a node and the now vacant entry.
+    pub fn remove(self) -> (Option<N>, VacantEntryMut<'a, N>) {
+        match self {
+            EntryMut::Occupied(node) => {
+                let (removed_value, vacant_entry) = node.remove();
+
+                (Some(removed_value), vacant_entry)
+            }
+            EntryMut::Vacant(vacant_entry) => (None, vacant_entry),
+        }
+    }
+
+    /// Gets the node this entry is for, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// The node if there was one, `None` otherwise.
+    pub fn node(&self) -> Option<Node<'_, N>> {
+        match self {
+            EntryMut::Occupied(node) => Some(node.as_node()),
+            EntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Gets the mutable node this entry is for, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// The mutable node if there was one, `None` otherwise.
+    pub fn node_mut(self) -> Option<NodeMut<'a, N>> {
+        match self {
+            EntryMut::Occupied(node) => Some(node),
+            EntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Gets the number of nodes in the subtree at this entry, which is zero for a vacant entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// assert_eq!(tree.root_entry_mut().subtree_len(), 2);
+    ///
+    /// let mut root = tree.root_mut().unwrap();
+    /// assert_eq!(root.child_entry_mut(0).subtree_len(), 0);
+    /// ```
+    pub fn subtree_len(&self) -> usize {
+        self.as_entry().subtree_len()
+    }
+
+    /// Gets an iterator over the immediate children of this node. This only includes children
+    /// for which there is a node.
+    pub fn child_iter(&self) -> EntryIter<NodeChildIter<'_, N>> {
+        self.as_entry().child_iter()
+    }
+
+    /// Gets a depth-first iterator over this and all child nodes.
+    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> EntryIter<DepthFirstIter<'_, N>> {
+        self.as_entry().depth_first_iter(order)
+    }
+
+    /// Gets a breadth-first iterator over this and all child nodes.
+    pub fn breadth_first_iter(&self) -> EntryIter<BreadthFirstIter<'_, N>> {
+        self.as_entry().breadth_first_iter()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::NodePath;
+use std::{
+    error,
+    fmt::{self, Display},
+};
+
+/// The errors of the fallible operations on an Eytzinger tree.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Two trees with different max children per node were combined.
+    ArityMismatch {
+        /// The max children per node of the tree being changed.
+        expected: usize,
+        /// The max children per node of the other tree.
+        actual: usize,
+    },
+
+    /// A child offset was not less than the max children per node.
+    ChildIndexOutOfRange {
+        /// The child offset which was used.
+        child_offset: usize,
+        /// The max children per node of the tree.
+        max_children_per_node: usize,
+    },
+
+    /// The child offsets given for reordering children were not a permutation of every child
+    /// offset.
+    InvalidPermutation,
+
+    /// A node would be so deep its index does not fit in a `usize`.
+    DepthOverflow {
+        /// The depth of the node.
+        depth: usize,
+    },
+
+    /// A node would be inserted where its parent does not exist.
+    OrphanInsert {
+        /// The path of the node which would be inserted.
+        path: NodePath,
+    },
+
+    /// A key could not be parsed as the path of a node.
+    InvalidKey {
+        /// The key which could not be parsed.
+        key: String,
+    },
+
+    /// A reference to a node no longer resolves as the tree has changed since it was taken.
+    StaleNodeId,
+
+    /// A stream of node events did not describe a single tree, a node was ended without being
+    /// started, a second root was started or a node was never ended.
+    UnbalancedEvents,
+
+    /// Text could not be parsed as an s-expression describing a tree.
+    InvalidSexpr {
+        /// The byte offset in the text of the first invalid token.
+        position: usize,
+    },
+
+    /// A split node of a decision tree did not have both of its branches.
+    MissingBranch {
+        /// The path of the split node.
+        path: NodePath,
+    },
+
+    /// Two children of a node would be moved to the same child offset when changing the arity of
+    /// a tree.
+    SlotConflict {
+        /// The path of the node whose children conflict, in the tree being projected.
+        path: NodePath,
+        /// The child offset both children would be moved to.
+        child_offset: usize,
+    },
+
+    /// There was no node at a path which should have one.
+    MissingNode {
+        /// The path without a node.
+        path: NodePath,
+    },
+
+    /// An entry of a list of parent pointers did not refer to an earlier entry as its parent, or
+    /// was a second root.
+    InvalidParentPointer {
+        /// The position of the entry in the list.
+        position: usize,
+    },
+
+    /// A subtree would be moved to within itself or to a position above itself.
+    OverlappingSubtrees {
+        /// The path of the subtree being moved.
+        source: NodePath,
+        /// The path it would be moved to.
+        destination: NodePath,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ArityMismatch { expected, actual } => write!(
+                f,
+                "expected a tree with {} max children per node but it had {}",
+                expected, actual
+            ),
+            Error::ChildIndexOutOfRange {
+                child_offset,
+                max_children_per_node,
+            } => write!(
+                f,
+                "the child index {} should be less than the max children per node of {}",
+                child_offset, max_children_per_node
+            ),
+            Error::InvalidPermutation => {
+                f.write_str("the child offsets should be a permutation of every child offset")
+            }
+            Error::DepthOverflow { depth } => {
+                write!(f, "a node at depth {} would overflow the index", depth)
+            }
+            Error::OrphanInsert { path } => {
+                write!(f, "the parent of {} should exist to insert it", path)
+            }
+            Error::InvalidKey { key } => write!(f, "the key {:?} is not a valid path", key),
+            Error::StaleNodeId => f.write_str("the node no longer exists in the tree"),
+            Error::UnbalancedEvents => {
+                f.write_str("every started node should be ended within a single root")
+            }
+            Error::InvalidSexpr { position } => {
+                write!(f, "the s-expression is invalid at position {}", position)
+            }
+            Error::MissingBranch { path } => {
+                write!(f, "the split at {} should have both of its branches", path)
+            }
+            Error::SlotConflict { path, child_offset } => write!(
+                f,
+                "two children of {} would both be moved to child offset {}",
+                path, child_offset
+            ),
+            Error::MissingNode { path } => write!(f, "there should be a node at {}", path),
+            Error::InvalidParentPointer { position } => write!(
+                f,
+                "the parent of entry {} should be an earlier entry",
+                position
+            ),
+            Error::OverlappingSubtrees {
+                source,
+                destination,
+            } => write!(
+                f,
+                "the subtree at {} cannot be moved to {} as they overlap",
+                source, destination
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
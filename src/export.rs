@@ -0,0 +1,294 @@
+//! Writing Eytzinger trees out in other formats.
+//!
+//! `EytzingerTree::export` traverses a tree in pre-order and drives an `Exporter`, which writes
+//! each node in its own format. DOT, JSON and indented text exporters are provided and others may
+//! be implemented in the same way.
+
+use std::fmt::{self, Display, Write};
+
+/// Receives the nodes of a tree in pre-order from `EytzingerTree::export`.
+///
+/// Each node is reported by a call to `begin_node`, then `emit_value` with its value, then the
+/// calls for each of its children and finally `end_node`.
+pub trait Exporter<N> {
+    /// Called once before any nodes.
+    fn begin_tree(&mut self) -> fmt::Result {
+        Ok(())
+    }
+
+    /// Called when a node is entered. The root has a depth of zero and no child offset, every
+    /// other node has the child offset it has within its parent.
+    fn begin_node(&mut self, depth: usize, child_offset: Option<usize>) -> fmt::Result;
+
+    /// Called with the value of the node which was just entered.
+    fn emit_value(&mut self, value: &N) -> fmt::Result;
+
+    /// Called when a node is left, after all of its children.
+    fn end_node(&mut self, depth: usize) -> fmt::Result;
+
+    /// Called once after all nodes.
+    fn end_tree(&mut self) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Writes a tree as a Graphviz DOT digraph, each node is labelled with its value and each edge
+/// with the child offset.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{export::DotExporter, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(2);
+/// tree.set_root_value(1).set_child_value(1, 2);
+///
+/// let mut dot = String::new();
+/// tree.export(&mut DotExporter::new(&mut dot)).unwrap();
+///
+/// assert_eq!(
+///     dot,
+///     "digraph {\n    n0 [label=\"1\"];\n    n1 [label=\"2\"];\n    n0 -> n1 [label=\"1\"];\n}\n"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct DotExporter<W> {
+    writer: W,
+    next_id: usize,
+    ancestors: Vec<usize>,
+    child_offset: Option<usize>,
+}
+
+impl<W> DotExporter<W>
+where
+    W: Write,
+{
+    /// Creates a new exporter writing to the writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            next_id: 0,
+            ancestors: vec![],
+            child_offset: None,
+        }
+    }
+
+    /// Gets the writer back from the exporter.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<N, W> Exporter<N> for DotExporter<W>
+where
+    N: Display,
+    W: Write,
+{
+    fn begin_tree(&mut self) -> fmt::Result {
+        self.writer.write_str("digraph {\n")
+    }
+
+    fn begin_node(&mut self, _depth: usize, child_offset: Option<usize>) -> fmt::Result {
+        self.ancestors.push(self.next_id);
+        self.next_id += 1;
+        self.child_offset = child_offset;
+        Ok(())
+    }
+
+    fn emit_value(&mut self, value: &N) -> fmt::Result {
+        let id = *self.ancestors.last().expect("a node should have begun");
+
+        write!(self.writer, "    n{} [label=\"", id)?;
+        write_escaped(&mut self.writer, value)?;
+        self.writer.write_str("\"];\n")?;
+
+        if let (Some(child_offset), [.., parent, _]) = (self.child_offset, &self.ancestors[..]) {
+            writeln!(
+                self.writer,
+                "    n{} -> n{} [label=\"{}\"];",
+                parent, id, child_offset
+            )?;
+        }
+        Ok(())
+    }
+
+    fn end_node(&mut self, _depth: usize) -> fmt::Result {
+        self.ancestors.pop();
+        Ok(())
+    }
+
+    fn end_tree(&mut self) -> fmt::Result {
+        self.writer.write_str("}\n")
+    }
+}
+
+/// Writes a tree as JSON, each node is an object with its value as a string, the child offset if
+/// it is not the root and its children. An empty tree is written as `null`.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{export::JsonExporter, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(2);
+/// tree.set_root_value(1).set_child_value(1, 2);
+///
+/// let mut json = String::new();
+/// tree.export(&mut JsonExporter::new(&mut json)).unwrap();
+///
+/// assert_eq!(
+///     json,
+///     r#"{"value":"1","children":[{"offset":1,"value":"2","children":[]}]}"#
+/// );
+/// ```
+#[derive(Debug)]
+pub struct JsonExporter<W> {
+    writer: W,
+    child_counts: Vec<usize>,
+    wrote_root: bool,
+}
+
+impl<W> JsonExporter<W>
+where
+    W: Write,
+{
+    /// Creates a new exporter writing to the writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            child_counts: vec![],
+            wrote_root: false,
+        }
+    }
+
+    /// Gets the writer back from the exporter.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<N, W> Exporter<N> for JsonExporter<W>
+where
+    N: Display,
+    W: Write,
+{
+    fn begin_node(&mut self, _depth: usize, child_offset: Option<usize>) -> fmt::Result {
+        match self.child_counts.last_mut() {
+            Some(siblings) => {
+                if *siblings > 0 {
+                    self.writer.write_char(',')?;
+                }
+                *siblings += 1;
+            }
+            None => self.wrote_root = true,
+        }
+        self.child_counts.push(0);
+
+        self.writer.write_char('{')?;
+        if let Some(child_offset) = child_offset {
+            write!(self.writer, "\"offset\":{},", child_offset)?;
+        }
+        Ok(())
+    }
+
+    fn emit_value(&mut self, value: &N) -> fmt::Result {
+        self.writer.write_str("\"value\":\"")?;
+        write_escaped(&mut self.writer, value)?;
+        self.writer.write_str("\",\"children\":[")
+    }
+
+    fn end_node(&mut self, _depth: usize) -> fmt::Result {
+        self.child_counts.pop();
+        self.writer.write_str("]}")
+    }
+
+    fn end_tree(&mut self) -> fmt::Result {
+        if self.wrote_root {
+            Ok(())
+        } else {
+            self.writer.write_str("null")
+        }
+    }
+}
+
+/// Writes a tree as indented text, one node per line with two spaces of indentation per level.
+/// Every node other than the root is prefixed by its child offset.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{export::TextExporter, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(3);
+/// {
+///     let mut root = tree.set_root_value(1);
+///     root.set_child_value(2, 2).set_child_value(0, 3);
+///     root.set_child_value(0, 4);
+/// }
+///
+/// let mut text = String::new();
+/// tree.export(&mut TextExporter::new(&mut text)).unwrap();
+///
+/// assert_eq!(text, "1\n  0: 4\n  2: 2\n    0: 3\n");
+/// ```
+#[derive(Debug)]
+pub struct TextExporter<W> {
+    writer: W,
+}
+
+impl<W> TextExporter<W>
+where
+    W: Write,
+{
+    /// Creates a new exporter writing to the writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Gets the writer back from the exporter.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<N, W> Exporter<N> for TextExporter<W>
+where
+    N: Display,
+    W: Write,
+{
+    fn begin_node(&mut self, depth: usize, child_offset: Option<usize>) -> fmt::Result {
+        for _ in 0..depth {
+            self.writer.write_str("  ")?;
+        }
+        if let Some(child_offset) = child_offset {
+            write!(self.writer, "{}: ", child_offset)?;
+        }
+        Ok(())
+    }
+
+    fn emit_value(&mut self, value: &N) -> fmt::Result {
+        writeln!(self.writer, "{}", value)
+    }
+
+    fn end_node(&mut self, _depth: usize) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Writes the displayed value with `"` and `\` escaped, along with control characters.
+fn write_escaped<W, N>(writer: &mut W, value: &N) -> fmt::Result
+where
+    W: Write,
+    N: Display + ?Sized,
+{
+    for c in value.to_string().chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
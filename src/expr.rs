@@ -0,0 +1,254 @@
+//! Evaluation of expression trees stored in Eytzinger trees.
+
+use crate::{DepthFirstOrder, EytzingerTree};
+
+/// Evaluates the nodes of an expression tree.
+pub trait Evaluator<N> {
+    /// The result of evaluating a node.
+    type Value;
+
+    /// Evaluates a node from the results of its children, the operands are in child offset order
+    /// and only include the children which exist so leaves have no operands.
+    ///
+    /// # Returns
+    ///
+    /// The result of the node, or `None` if it cannot be evaluated such as when it refers to a
+    /// variable.
+    fn evaluate(&self, node: &N, operands: &[Self::Value]) -> Option<Self::Value>;
+}
+
+/// Evaluates the tree bottom-up without recursion, so the depth of the tree is not limited by the
+/// call stack.
+///
+/// # Returns
+///
+/// The result of the root, or `None` if the tree is empty or any node could not be evaluated.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{
+///     expr::{self, Evaluator},
+///     EytzingerTree,
+/// };
+///
+/// enum Expr {
+///     Sum,
+///     Product,
+///     Literal(i64),
+/// }
+///
+/// struct Arithmetic;
+///
+/// impl Evaluator<Expr> for Arithmetic {
+///     type Value = i64;
+///
+///     fn evaluate(&self, node: &Expr, operands: &[i64]) -> Option<i64> {
+///         match node {
+///             Expr::Sum => Some(operands.iter().sum()),
+///             Expr::Product => Some(operands.iter().product()),
+///             Expr::Literal(value) => Some(*value),
+///         }
+///     }
+/// }
+///
+/// let mut tree = EytzingerTree::new(3);
+/// {
+///     let mut root = tree.set_root_value(Expr::Sum);
+///     root.set_child_value(0, Expr::Literal(1));
+///     let mut product = root.set_child_value(2, Expr::Product);
+///     product.set_child_value(0, Expr::Literal(2));
+///     product.set_child_value(1, Expr::Literal(3));
+///     product.set_child_value(2, Expr::Literal(4));
+/// }
+///
+/// assert_eq!(expr::eval(&tree, &Arithmetic), Some(25));
+/// ```
+pub fn eval<N, E>(tree: &EytzingerTree<N>, evaluator: &E) -> Option<E::Value>
+where
+    E: Evaluator<N> + ?Sized,
+{
+    let mut values = Vec::new();
+    for node in tree.depth_first_iter(DepthFirstOrder::PostOrder) {
+        let operands = values.split_off(values.len() - node.child_iter().count());
+        values.push(evaluator.evaluate(node.value(), &operands)?);
+    }
+
+    values.pop()
+}
+
+/// Replaces each largest subtree which can be evaluated with a leaf holding its result. Leaves
+/// are left as they are, as are the nodes above any node which could not be evaluated. The
+/// results of children are cloned as operands so they can still be folded if their parent could
+/// not be evaluated.
+///
+/// # Returns
+///
+/// The number of subtrees which were replaced.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{
+///     expr::{self, Evaluator},
+///     EytzingerTree,
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Expr {
+///     Sum,
+///     Variable,
+///     Literal(i64),
+/// }
+///
+/// struct Arithmetic;
+///
+/// impl Evaluator<Expr> for Arithmetic {
+///     type Value = i64;
+///
+///     fn evaluate(&self, node: &Expr, operands: &[i64]) -> Option<i64> {
+///         match node {
+///             Expr::Sum => Some(operands.iter().sum()),
+///             Expr::Variable => None,
+///             Expr::Literal(value) => Some(*value),
+///         }
+///     }
+/// }
+///
+/// let mut tree = EytzingerTree::new(2);
+/// {
+///     let mut root = tree.set_root_value(Expr::Sum);
+///     root.set_child_value(0, Expr::Variable);
+///     let mut sum = root.set_child_value(1, Expr::Sum);
+///     sum.set_child_value(0, Expr::Literal(2));
+///     sum.set_child_value(1, Expr::Literal(3));
+/// }
+///
+/// assert_eq!(expr::const_fold(&mut tree, &Arithmetic, Expr::Literal), 1);
+/// assert_eq!(tree.len(), 3);
+///
+/// let root = tree.root().unwrap();
+/// assert_eq!(root.child(1).map(|n| n.value()), Some(&Expr::Literal(5)));
+/// ```
+pub fn const_fold<N, E, F>(tree: &mut EytzingerTree<N>, evaluator: &E, mut to_node: F) -> usize
+where
+    E: Evaluator<N> + ?Sized,
+    E::Value: Clone,
+    F: FnMut(E::Value) -> N,
+{
+    // the index of each evaluated node along with its result and whether it has children
+    let mut values: Vec<(usize, Option<E::Value>, bool)> = Vec::new();
+    let mut folds = Vec::new();
+    for node in tree.depth_first_iter(DepthFirstOrder::PostOrder) {
+        let child_count = node.child_iter().count();
+        let children = values.split_off(values.len() - child_count);
+
+        let value = children
+            .iter()
+            .map(|(_, value, _)| value.clone())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|operands| evaluator.evaluate(node.value(), &operands));
+
+        if value.is_none() {
+            folds.extend(children.into_iter().filter_map(
+                |(index, value, has_children)| match value {
+                    Some(value) if has_children => Some((index, value)),
+                    _ => None,
+                },
+            ));
+        }
+
+        values.push((node.index, value, child_count > 0));
+    }
+
+    if let Some((index, Some(value), true)) = values.pop() {
+        folds.push((index, value));
+    }
+
+    for &(index, _) in &folds {
+        for child_index in tree
+            .index_calculator
+            .stored_child_indexes(index, tree.nodes.len())
+        {
+            tree.remove(child_index);
+        }
+    }
+
+    let fold_count = folds.len();
+    for (index, value) in folds {
+        tree.replace_value(index, to_node(value));
+    }
+    fold_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Evaluator<Option<i32>> for Sum {
+        type Value = i32;
+
+        fn evaluate(&self, node: &Option<i32>, operands: &[i32]) -> Option<i32> {
+            node.map(|value| value + operands.iter().sum::<i32>())
+        }
+    }
+
+    #[test]
+    fn eval_fails_on_any_unevaluable_node() {
+        let mut tree = EytzingerTree::new(2);
+        assert_eq!(eval(&tree, &Sum), None);
+
+        tree.set_root_value(Some(1))
+            .set_child_value(1, Some(2))
+            .set_child_value(0, None);
+        assert_eq!(eval(&tree, &Sum), None);
+
+        tree.root_mut()
+            .unwrap()
+            .child_mut(1)
+            .unwrap()
+            .remove_child_value(0);
+        assert_eq!(eval(&tree, &Sum), Some(3));
+    }
+
+    #[test]
+    fn const_fold_replaces_largest_evaluable_subtrees() {
+        let mut tree = EytzingerTree::new(2);
+        {
+            let mut root = tree.set_root_value(Some(1));
+            {
+                let mut left = root.set_child_value(0, None);
+                left.set_child_value(0, Some(2)).set_child_value(0, Some(3));
+                left.set_child_value(1, Some(4));
+            }
+            root.set_child_value(1, Some(5)).set_child_value(1, Some(6));
+        }
+
+        assert_eq!(const_fold(&mut tree, &Sum, Some), 2);
+
+        let mut expected = EytzingerTree::new(2);
+        {
+            let mut root = expected.set_root_value(Some(1));
+            {
+                let mut left = root.set_child_value(0, None);
+                left.set_child_value(0, Some(5));
+                left.set_child_value(1, Some(4));
+            }
+            root.set_child_value(1, Some(11));
+        }
+        assert_eq!(tree, expected);
+
+        tree.root_mut()
+            .unwrap()
+            .child_mut(0)
+            .unwrap()
+            .into_value_mut()
+            .replace(0);
+        assert_eq!(const_fold(&mut tree, &Sum, Some), 1);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.root().map(|n| *n.value()), Some(Some(21)));
+        assert_eq!(const_fold(&mut tree, &Sum, Some), 0);
+    }
+}
@@ -1,43 +1,212 @@
-use std::ops::Range;
+use crate::EytzingerLayout;
+use std::{iter, num::NonZeroUsize, ops::Range};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct EytzingerIndexCalculator {
-    max_children_per_node: usize,
+    layout: EytzingerLayout,
 }
 
 impl EytzingerIndexCalculator {
     pub fn new(max_children_per_node: usize) -> Self {
-        assert!(max_children_per_node > 0);
+        Self {
+            layout: EytzingerLayout::new(max_children_per_node),
+        }
+    }
+
+    pub fn with_arity(max_children_per_node: NonZeroUsize) -> Self {
+        Self {
+            layout: EytzingerLayout::with_arity(max_children_per_node),
+        }
+    }
 
+    pub fn with_layout(layout: EytzingerLayout) -> Self {
+        Self { layout }
+    }
+
+    /// Gets a calculator with the same max children per node whose children are stored in blocks
+    /// padded to a power of two.
+    pub fn padded(self) -> Self {
         Self {
-            max_children_per_node,
+            layout: self.layout.padded(),
         }
     }
 
+    /// Gets a calculator with the max children per node which is padded if this one is.
+    pub fn with_max_children_per_node(&self, max_children_per_node: NonZeroUsize) -> Self {
+        let calculator = Self::with_arity(max_children_per_node);
+        if self.layout.is_padded() {
+            calculator.padded()
+        } else {
+            calculator
+        }
+    }
+
+    pub fn layout(&self) -> EytzingerLayout {
+        self.layout
+    }
+
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.layout.stride()
+    }
+
+    #[inline]
     pub fn max_children_per_node(&self) -> usize {
-        self.max_children_per_node
+        self.layout.max_children_per_node()
     }
 
+    #[inline]
     pub fn child_index(&self, parent_index: usize, child_offset: usize) -> usize {
         assert!(
-            child_offset < self.max_children_per_node,
+            child_offset < self.max_children_per_node(),
             "the child index should be less than max_children_per_node"
         );
 
-        (parent_index * self.max_children_per_node) + child_offset + 1
+        (parent_index * self.stride()) + child_offset + 1
     }
 
+    #[inline]
+    pub fn checked_child_index(&self, parent_index: usize, child_offset: usize) -> Option<usize> {
+        self.layout.child_index(parent_index, child_offset)
+    }
+
+    #[inline]
     pub fn parent_index(&self, child_index: usize) -> Option<usize> {
-        if child_index == 0 {
-            None
-        } else {
-            Some((child_index - 1) / self.max_children_per_node)
+        self.layout.parent_index(child_index)
+    }
+
+    /// Gets the offset of the index within the children of its parent, `None` for the root.
+    #[inline]
+    pub fn child_offset(&self, index: usize) -> Option<usize> {
+        let parent_index = self.parent_index(index)?;
+        Some(index - self.child_index(parent_index, 0))
+    }
+
+    pub fn depth(&self, mut index: usize) -> usize {
+        let mut depth = 0;
+        while let Some(parent_index) = self.parent_index(index) {
+            index = parent_index;
+            depth += 1;
+        }
+        depth
+    }
+
+    pub fn child_offsets(&self, index: usize) -> Vec<usize> {
+        let mut child_offsets = vec![];
+
+        let mut current = index;
+        while let Some(parent) = self.parent_index(current) {
+            child_offsets.push(current - self.child_index(parent, 0));
+            current = parent;
+        }
+        child_offsets.reverse();
+
+        child_offsets
+    }
+
+    pub fn is_ancestor(&self, ancestor_index: usize, index: usize) -> bool {
+        let mut current = index;
+        while current > ancestor_index {
+            current = match self.parent_index(current) {
+                Some(parent_index) => parent_index,
+                None => return false,
+            };
+        }
+        current == ancestor_index
+    }
+
+    /// Gets the deepest depth for which `level_indexes` does not overflow.
+    pub fn max_depth(&self) -> usize {
+        if self.stride() == 1 {
+            return usize::MAX - 1;
+        }
+
+        let mut depth = 0;
+        let mut first_index: usize = 0;
+        let mut level_len: usize = 1;
+        loop {
+            let next_level = first_index
+                .checked_add(level_len)
+                .and_then(|next_first| Some((next_first, level_len.checked_mul(self.stride())?)))
+                .filter(|&(next_first, next_len)| next_first.checked_add(next_len).is_some());
+
+            match next_level {
+                Some((next_first, next_len)) => {
+                    first_index = next_first;
+                    level_len = next_len;
+                    depth += 1;
+                }
+                None => return depth,
+            }
+        }
+    }
+
+    pub fn level_indexes(&self, depth: usize) -> Option<Range<usize>> {
+        if self.stride() == 1 {
+            return Some(depth..depth.checked_add(1)?);
+        }
+
+        let mut first_index: usize = 0;
+        let mut level_len: usize = 1;
+
+        for _ in 0..depth {
+            first_index = first_index.checked_add(level_len)?;
+            level_len = level_len.checked_mul(self.stride())?;
+        }
+
+        Some(first_index..first_index.checked_add(level_len)?)
+    }
+
+    pub fn descendant_level_ranges(
+        &self,
+        roots: Range<usize>,
+        storage_len: usize,
+    ) -> impl Iterator<Item = Range<usize>> {
+        let index_calculator = *self;
+        let mut next_level = Some(roots);
+
+        iter::from_fn(move || {
+            let level = next_level.take()?;
+            if level.is_empty() || level.start >= storage_len {
+                return None;
+            }
+
+            next_level = index_calculator
+                .checked_child_index(level.start, 0)
+                .map(|next_start| {
+                    // the whole block of the last node, including any padding
+                    let next_end = (level.end - 1)
+                        .checked_mul(index_calculator.stride())
+                        .and_then(|last_block_start| {
+                            last_block_start.checked_add(index_calculator.stride() + 1)
+                        })
+                        .unwrap_or(usize::MAX);
+                    next_start..next_end
+                });
+
+            Some(level.start..level.end.min(storage_len))
+        })
+    }
+
+    pub fn level_ranges(&self) -> impl Iterator<Item = Range<usize>> {
+        self.descendant_level_ranges(0..1, usize::MAX)
+    }
+
+    pub fn stored_child_indexes(&self, parent_index: usize, storage_len: usize) -> Range<usize> {
+        match self.checked_child_index(parent_index, 0) {
+            Some(first_child_index) => {
+                first_child_index.min(storage_len)
+                    ..first_child_index
+                        .saturating_add(self.max_children_per_node())
+                        .min(storage_len)
+            }
+            None => 0..0,
         }
     }
 
     pub fn child_indexes(&self, parent_index: usize) -> Range<usize> {
         let first_child_index = self.child_index(parent_index, 0);
 
-        first_child_index..(first_child_index + self.max_children_per_node)
+        first_child_index..(first_child_index + self.max_children_per_node())
     }
 }
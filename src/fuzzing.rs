@@ -0,0 +1,80 @@
+use crate::EytzingerTree;
+use arbitrary::{Arbitrary, Unstructured};
+
+/// The greatest max children per node of an arbitrary tree.
+const MAX_ARBITRARY_ARITY: usize = 8;
+
+/// The greatest depth of an arbitrary tree, along with `MAX_ARBITRARY_ARITY` this bounds the
+/// storage of a tree to a few hundred thousand nodes however sparse it is.
+const MAX_ARBITRARY_DEPTH: usize = 6;
+
+impl<'a, N> Arbitrary<'a> for EytzingerTree<N>
+where
+    N: Arbitrary<'a>,
+{
+    /// Creates a tree whose arity, depth and shape are all drawn from the input, one level at a
+    /// time. Each node takes at least one byte of the input so the number of nodes is bounded by
+    /// its length as well as by the depth and arity.
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let max_children_per_node = u.int_in_range(1..=MAX_ARBITRARY_ARITY)?;
+        let max_depth = u.int_in_range(0..=MAX_ARBITRARY_DEPTH)?;
+
+        let mut tree = EytzingerTree::new(max_children_per_node);
+        if !u.arbitrary::<bool>()? {
+            return Ok(tree);
+        }
+        tree.set_root_value(u.arbitrary()?);
+
+        let mut level = vec![0];
+        for _ in 0..max_depth {
+            let mut next_level = vec![];
+            for &parent_index in &level {
+                for child_offset in 0..max_children_per_node {
+                    if u.is_empty() {
+                        return Ok(tree);
+                    }
+                    if u.arbitrary::<bool>()? {
+                        let child_index = tree.child_index(parent_index, child_offset);
+                        tree.replace_value(child_index, u.arbitrary()?);
+                        next_level.push(child_index);
+                    }
+                }
+            }
+            level = next_level;
+        }
+
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_trees_stay_within_bounds() {
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+
+        let mut largest = 0;
+        for start in 0..64 {
+            let mut u = Unstructured::new(&data[start..]);
+            let tree = EytzingerTree::<u8>::arbitrary(&mut u).unwrap();
+
+            assert!(tree.max_children_per_node() <= MAX_ARBITRARY_ARITY);
+            assert!(tree
+                .breadth_first_iter()
+                .all(|node| node.path().depth() <= MAX_ARBITRARY_DEPTH));
+            assert_eq!(tree.breadth_first_iter().count(), tree.len());
+            largest = largest.max(tree.len());
+        }
+        assert!(largest > 1);
+    }
+
+    #[test]
+    fn empty_input_creates_an_empty_tree() {
+        let mut u = Unstructured::new(&[]);
+        let tree = EytzingerTree::<u32>::arbitrary(&mut u).unwrap();
+
+        assert!(tree.is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{DepthFirstOrder, Error, EytzingerTree, NodePath, NodeRef};
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash, Hasher},
+};
+
+/// An Eytzinger tree along with an index from values to the nodes holding them, so a node can be
+/// found by its value without visiting every node.
+///
+/// The tree can only be changed through this wrapper, which keeps the index up to date. The
+/// index stores the hash of each value rather than the value itself, so values need not be
+/// `Clone`.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{indexed::IndexedTree, NodePath};
+///
+/// let mut tree = IndexedTree::new(2);
+/// tree.set_value_at_path(&NodePath::root(), "root".to_string())
+///     .unwrap();
+/// tree.set_value_at_path(&NodePath::from(vec![1]), "leaf".to_string())
+///     .unwrap();
+///
+/// let node_ref = tree.node_of_value("leaf").unwrap();
+/// let node = tree.tree().resolve(node_ref).unwrap();
+/// assert_eq!(node.path(), NodePath::from(vec![1]));
+///
+/// assert_eq!(tree.node_of_value("branch"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedTree<N> {
+    tree: EytzingerTree<N>,
+    indexes_by_hash: HashMap<u64, Vec<usize>>,
+    hash_builder: RandomState,
+}
+
+impl<N> IndexedTree<N>
+where
+    N: Hash + Eq,
+{
+    /// Creates a new empty tree with the specified max children per node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    pub fn new(max_children_per_node: usize) -> Self {
+        Self::from(EytzingerTree::new(max_children_per_node))
+    }
+
+    /// Gets the underlying Eytzinger tree.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Converts this into the underlying Eytzinger tree, discarding the index.
+    pub fn into_tree(self) -> EytzingerTree<N> {
+        self.tree
+    }
+
+    /// Gets the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Gets whether the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Gets a reference to the node holding the value in `O(1)`, or `None` if no node holds it.
+    /// When several nodes hold equal values the one nearest the start of the storage is found.
+    pub fn node_of_value<Q>(&self, value: &Q) -> Option<NodeRef>
+    where
+        N: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.indexes_by_hash
+            .get(&self.hash(value))?
+            .iter()
+            .copied()
+            .filter(|&index| {
+                self.tree.nodes[index]
+                    .as_ref()
+                    .map_or(false, |node_value| node_value.borrow() == value)
+            })
+            .min()
+            .map(|index| self.tree.node_ref(index))
+    }
+
+    /// Sets the value of the node at the path, the parent of the node must already exist.
+    ///
+    /// # Returns
+    ///
+    /// The old value of the node, if there was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `EytzingerTree::set_value_at_path`.
+    pub fn set_value_at_path(&mut self, path: &NodePath, new_value: N) -> Result<Option<N>, Error> {
+        let index = self.tree.checked_insert_index(path)?;
+
+        self.insert_index(index, &new_value);
+        let old_value = self.tree.replace_value(index, new_value);
+        if let Some(old_value) = &old_value {
+            self.remove_index(index, old_value);
+        }
+
+        Ok(old_value)
+    }
+
+    /// Removes the node at the path along with all of its descendants.
+    ///
+    /// # Returns
+    ///
+    /// The old value of the node, if there was one.
+    pub fn remove_at_path(&mut self, path: &NodePath) -> Option<N> {
+        let index = self.tree.path_index(path)?;
+        let removed: Vec<_> = self
+            .tree
+            .node(index)?
+            .depth_first_iter(DepthFirstOrder::PreOrder)
+            .map(|node| (node.index, self.hash(node.value())))
+            .collect();
+        for (index, hash) in removed {
+            self.remove_hashed_index(index, hash);
+        }
+
+        self.tree.remove(index)
+    }
+
+    /// Removes all of the nodes.
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.indexes_by_hash.clear();
+    }
+
+    fn hash<Q>(&self, value: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hasher = self.hash_builder.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn insert_index(&mut self, index: usize, value: &N) {
+        let hash = self.hash(value);
+        self.indexes_by_hash.entry(hash).or_default().push(index);
+    }
+
+    fn remove_index(&mut self, index: usize, value: &N) {
+        let hash = self.hash(value);
+        self.remove_hashed_index(index, hash);
+    }
+
+    fn remove_hashed_index(&mut self, index: usize, hash: u64) {
+        if let Some(indexes) = self.indexes_by_hash.get_mut(&hash) {
+            if let Some(position) = indexes.iter().position(|&other| other == index) {
+                indexes.swap_remove(position);
+            }
+            if indexes.is_empty() {
+                self.indexes_by_hash.remove(&hash);
+            }
+        }
+    }
+}
+
+impl<N> From<EytzingerTree<N>> for IndexedTree<N>
+where
+    N: Hash + Eq,
+{
+    fn from(tree: EytzingerTree<N>) -> Self {
+        let mut indexed = Self {
+            tree,
+            indexes_by_hash: HashMap::new(),
+            hash_builder: RandomState::new(),
+        };
+
+        let hashes: Vec<_> = indexed
+            .tree
+            .enumerate_values()
+            .map(|(index, value)| (index, indexed.hash(value)))
+            .collect();
+        for (index, hash) in hashes {
+            indexed.indexes_by_hash.entry(hash).or_default().push(index);
+        }
+
+        indexed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_follows_replacements_and_removals() {
+        let mut source = EytzingerTree::<u32>::new(2);
+        source
+            .set_root_value(1)
+            .set_child_value(0, 2)
+            .set_child_value(1, 3);
+        let mut tree = IndexedTree::from(source);
+
+        let grandchild = NodePath::from(vec![0, 1]);
+        assert!(tree.node_of_value(&3).is_some());
+        assert_eq!(tree.set_value_at_path(&grandchild, 4), Ok(Some(3)));
+        assert_eq!(tree.node_of_value(&3), None);
+
+        let child = tree.node_of_value(&2).unwrap();
+        assert_eq!(
+            tree.tree().resolve(child).unwrap().path(),
+            NodePath::from(vec![0])
+        );
+
+        assert_eq!(tree.remove_at_path(&NodePath::from(vec![0])), Some(2));
+        assert_eq!(tree.node_of_value(&2), None);
+        assert_eq!(tree.node_of_value(&4), None);
+        assert!(tree.node_of_value(&1).is_some());
+        assert_eq!(tree.indexes_by_hash.len(), 1);
+    }
+
+    #[test]
+    fn equal_values_find_the_first_node() {
+        let mut tree = IndexedTree::new(2);
+        tree.set_value_at_path(&NodePath::root(), 7).unwrap();
+        tree.set_value_at_path(&NodePath::from(vec![1]), 7).unwrap();
+        tree.set_value_at_path(&NodePath::from(vec![0]), 7).unwrap();
+
+        let root = tree.node_of_value(&7).unwrap();
+        assert!(tree.tree().resolve(root).unwrap().parent().is_none());
+
+        tree.set_value_at_path(&NodePath::root(), 8).unwrap();
+        let left = tree.node_of_value(&7).unwrap();
+        assert_eq!(
+            tree.tree().resolve(left).unwrap().path(),
+            NodePath::from(vec![0])
+        );
+
+        tree.clear();
+        assert_eq!(tree.node_of_value(&8), None);
+    }
+
+    #[test]
+    fn orphans_are_not_indexed() {
+        let mut tree = IndexedTree::new(2);
+        assert!(tree.set_value_at_path(&NodePath::from(vec![0]), 1).is_err());
+        assert_eq!(tree.node_of_value(&1), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Conversions between Eytzinger trees and flat formats used by other systems.
+
+use crate::{AncestorPolicy, Error, EytzingerTree, NodePath};
+use std::collections::HashMap;
+
+/// Creates a new Eytzinger tree from values keyed by delimited paths such as `"0/3/1"`, each part
+/// of a key is converted into a child offset by `index_parser`. The root is keyed by an empty
+/// string and a single leading delimiter is allowed, so `"/0/3/1"` and `"/"` are also accepted.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidKey` if `index_parser` fails for a part of a key, `Error::OrphanInsert`
+/// if the parent of a node has no key and the errors of `EytzingerTree::from_path_values` for
+/// paths which cannot be stored.
+///
+/// # Panics
+///
+/// Panics if `max_children_per_node` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::interop::from_delimited_keys;
+///
+/// let keys = vec![("", 1), ("0", 2), ("0/3", 3), ("1", 4)];
+///
+/// let tree = from_delimited_keys(4, keys, '/', |part| part.parse().ok()).unwrap();
+///
+/// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+/// assert_eq!(values, vec![1, 2, 4, 3]);
+/// ```
+pub fn from_delimited_keys<'k, N, I, P>(
+    max_children_per_node: usize,
+    keyed_values: I,
+    delimiter: char,
+    mut index_parser: P,
+) -> Result<EytzingerTree<N>, Error>
+where
+    I: IntoIterator<Item = (&'k str, N)>,
+    P: FnMut(&str) -> Option<usize>,
+{
+    let path_values = keyed_values
+        .into_iter()
+        .map(|(key, value)| {
+            parse_key(key, delimiter, &mut index_parser)
+                .map(|path| (path, value))
+                .ok_or_else(|| Error::InvalidKey {
+                    key: key.to_string(),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    EytzingerTree::from_path_values(max_children_per_node, path_values, AncestorPolicy::Require)
+}
+
+/// Gets the delimited key of every node with its value in breadth-first order, the inverse of
+/// `from_delimited_keys`. Each child offset is converted into a part of the key by
+/// `index_formatter` and the root is keyed by an empty string.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{interop::to_delimited_keys, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(4);
+/// tree.set_root_value(1).set_child_value(0, 2).set_child_value(3, 3);
+///
+/// let keys = to_delimited_keys(&tree, '.', |offset| offset.to_string());
+/// assert_eq!(
+///     keys,
+///     vec![
+///         (String::new(), &1),
+///         ("0".to_string(), &2),
+///         ("0.3".to_string(), &3)
+///     ]
+/// );
+/// ```
+pub fn to_delimited_keys<N, F>(
+    tree: &EytzingerTree<N>,
+    delimiter: char,
+    mut index_formatter: F,
+) -> Vec<(String, &N)>
+where
+    F: FnMut(usize) -> String,
+{
+    tree.breadth_first_iter()
+        .map(|node| {
+            let mut key = String::new();
+            for (depth, &child_offset) in node.path().child_offsets().iter().enumerate() {
+                if depth > 0 {
+                    key.push(delimiter);
+                }
+                key.push_str(&index_formatter(child_offset));
+            }
+
+            (key, node.value())
+        })
+        .collect()
+}
+
+/// Which vacant child of its parent each node is placed at when building a tree from parent
+/// pointers, see `from_parent_pointers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotPolicy {
+    /// Children take the lowest vacant child offsets in the order they are given, this is the
+    /// inverse of `to_parent_pointers` for trees whose children are packed to the left.
+    Leftmost,
+    /// Children take the highest vacant child offsets in the order they are given.
+    Rightmost,
+}
+
+/// Creates a new Eytzinger tree from values paired with the position of their parent in the same
+/// list, the root has no parent. Every parent must come before its children, such as in
+/// breadth-first or depth-first order, so the root is always first. The child offset of each
+/// node is chosen by `slot_policy` as the list does not record it.
+///
+/// # Errors
+///
+/// * `Error::InvalidParentPointer` if a parent is not an earlier entry or an entry other than
+///   the first has no parent.
+/// * `Error::ChildIndexOutOfRange` if a node has more children than the max children per node,
+///   the child offset is the max children per node.
+/// * `Error::DepthOverflow` if a node would be too deep to be indexed.
+///
+/// # Panics
+///
+/// Panics if `max_children_per_node` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::interop::{from_parent_pointers, SlotPolicy};
+///
+/// let rows = vec![(None, "root"), (Some(0), "a"), (Some(1), "b"), (Some(0), "c")];
+///
+/// let tree = from_parent_pointers(2, rows.clone(), SlotPolicy::Leftmost).unwrap();
+/// let paths: Vec<_> = tree
+///     .breadth_first_iter()
+///     .map(|n| (n.path().to_string(), *n.value()))
+///     .collect();
+/// assert_eq!(
+///     paths,
+///     vec![
+///         ("/".to_string(), "root"),
+///         ("/0".to_string(), "a"),
+///         ("/1".to_string(), "c"),
+///         ("/0/0".to_string(), "b")
+///     ]
+/// );
+///
+/// let tree = from_parent_pointers(2, rows, SlotPolicy::Rightmost).unwrap();
+/// let root = tree.root().unwrap();
+/// assert_eq!(root.child(1).map(|n| *n.value()), Some("a"));
+/// assert_eq!(root.child(0).map(|n| *n.value()), Some("c"));
+/// ```
+pub fn from_parent_pointers<N, I>(
+    max_children_per_node: usize,
+    parented_values: I,
+    slot_policy: SlotPolicy,
+) -> Result<EytzingerTree<N>, Error>
+where
+    I: IntoIterator<Item = (Option<usize>, N)>,
+{
+    let mut tree = EytzingerTree::new(max_children_per_node);
+    // the index in the tree of each entry
+    let mut indexes: Vec<usize> = vec![];
+
+    for (position, (parent, value)) in parented_values.into_iter().enumerate() {
+        let parent_index = match parent {
+            None if position == 0 => None,
+            Some(parent) if parent < position => Some(indexes[parent]),
+            _ => return Err(Error::InvalidParentPointer { position }),
+        };
+
+        let index = match parent_index {
+            Some(parent_index) => vacant_child_index(&tree, parent_index, slot_policy)?,
+            None => 0,
+        };
+        tree.replace_value(index, value);
+        indexes.push(index);
+    }
+
+    Ok(tree)
+}
+
+/// Gets the value of every node in breadth-first order, paired with the position of its parent
+/// in the same list. The root has no parent and the children of each node are in child offset
+/// order, see `from_parent_pointers` for the inverse.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{interop::to_parent_pointers, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(3);
+/// {
+///     let mut root = tree.set_root_value(1);
+///     root.set_child_value(2, 2).set_child_value(0, 3);
+///     root.set_child_value(1, 4);
+/// }
+///
+/// assert_eq!(
+///     to_parent_pointers(&tree),
+///     vec![(None, &1), (Some(0), &4), (Some(0), &2), (Some(2), &3)]
+/// );
+/// ```
+pub fn to_parent_pointers<N>(tree: &EytzingerTree<N>) -> Vec<(Option<usize>, &N)> {
+    // the position in the list of each node by its index in the tree
+    let mut positions = HashMap::new();
+
+    tree.breadth_first_iter()
+        .enumerate()
+        .map(|(position, node)| {
+            positions.insert(node.index(), position);
+            let parent = node.parent().map(|parent| positions[&parent.index()]);
+
+            (parent, node.value())
+        })
+        .collect()
+}
+
+fn vacant_child_index<N>(
+    tree: &EytzingerTree<N>,
+    parent_index: usize,
+    slot_policy: SlotPolicy,
+) -> Result<usize, Error> {
+    let max_children_per_node = tree.max_children_per_node();
+    let checked_child_index = |child_offset| {
+        tree.index_calculator
+            .checked_child_index(parent_index, child_offset)
+            .ok_or_else(|| Error::DepthOverflow {
+                depth: tree.index_calculator.depth(parent_index) + 1,
+            })
+    };
+
+    // the children of a node are stored next to each other
+    let mut child_indexes =
+        checked_child_index(0)?..=checked_child_index(max_children_per_node - 1)?;
+    let is_vacant = |child_index: &usize| !matches!(tree.nodes.get(*child_index), Some(Some(_)));
+    let child_index = match slot_policy {
+        SlotPolicy::Leftmost => child_indexes.find(is_vacant),
+        SlotPolicy::Rightmost => child_indexes.rfind(is_vacant),
+    };
+
+    child_index.ok_or(Error::ChildIndexOutOfRange {
+        child_offset: max_children_per_node,
+        max_children_per_node,
+    })
+}
+
+fn parse_key<P>(key: &str, delimiter: char, index_parser: &mut P) -> Option<NodePath>
+where
+    P: FnMut(&str) -> Option<usize>,
+{
+    let key = key.strip_prefix(delimiter).unwrap_or(key);
+    if key.is_empty() {
+        return Some(NodePath::root());
+    }
+
+    key.split(delimiter).map(index_parser).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interop::{
+            from_delimited_keys, from_parent_pointers, to_delimited_keys, to_parent_pointers,
+            SlotPolicy,
+        },
+        Error, EytzingerTree, NodePath,
+    };
+
+    #[test]
+    fn delimited_keys_round_trip() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(2, 2).set_child_value(1, 3);
+            root.set_child_value(0, 4);
+        }
+
+        let keys = to_delimited_keys(&tree, ':', |offset| format!("c{}", offset));
+        assert_eq!(keys[2].0, "c2");
+        assert_eq!(keys[3].0, "c2:c1");
+
+        let rebuilt = from_delimited_keys(
+            3,
+            keys.iter().map(|(key, value)| (key.as_str(), **value)),
+            ':',
+            |part| part.strip_prefix('c')?.parse().ok(),
+        )
+        .unwrap();
+        assert_eq!(rebuilt, tree);
+    }
+
+    #[test]
+    fn from_delimited_keys_reports_invalid_keys() {
+        let parse = |part: &str| part.parse().ok();
+
+        assert_eq!(
+            from_delimited_keys(2, vec![("/", 1), ("0//1", 2)], '/', parse).unwrap_err(),
+            Error::InvalidKey {
+                key: "0//1".to_string()
+            }
+        );
+        assert_eq!(
+            from_delimited_keys(2, vec![("/", 1), ("/1/0", 2)], '/', parse).unwrap_err(),
+            Error::OrphanInsert {
+                path: NodePath::from(vec![1, 0])
+            }
+        );
+    }
+
+    #[test]
+    fn parent_pointers_round_trip_for_left_packed_trees() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            {
+                let mut first = root.set_child_value(0, 2);
+                first.set_child_value(0, 3);
+                first.set_child_value(1, 4).set_child_value(0, 5);
+            }
+            root.set_child_value(1, 6);
+        }
+
+        let parented: Vec<_> = to_parent_pointers(&tree)
+            .into_iter()
+            .map(|(parent, value)| (parent, *value))
+            .collect();
+        let rebuilt = from_parent_pointers(3, parented, SlotPolicy::Leftmost).unwrap();
+        assert_eq!(rebuilt, tree);
+    }
+
+    #[test]
+    fn from_parent_pointers_reports_invalid_entries() {
+        assert_eq!(
+            from_parent_pointers(
+                2,
+                vec![(None, 1), (Some(2), 2), (Some(0), 3)],
+                SlotPolicy::Leftmost
+            ),
+            Err(Error::InvalidParentPointer { position: 1 })
+        );
+        assert_eq!(
+            from_parent_pointers(2, vec![(None, 1), (None, 2)], SlotPolicy::Leftmost),
+            Err(Error::InvalidParentPointer { position: 1 })
+        );
+        assert_eq!(
+            from_parent_pointers(2, vec![(Some(0), 1)], SlotPolicy::Leftmost),
+            Err(Error::InvalidParentPointer { position: 0 })
+        );
+        assert_eq!(
+            from_parent_pointers(
+                2,
+                vec![(None, 1), (Some(0), 2), (Some(0), 3), (Some(0), 4)],
+                SlotPolicy::Rightmost
+            ),
+            Err(Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2
+            })
+        );
+        assert!(
+            from_parent_pointers(2, Vec::<(_, u32)>::new(), SlotPolicy::Leftmost)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::Depth;
+use std::num::NonZeroUsize;
+
+/// The positions of the nodes of an Eytzinger tree in its storage, the root is stored at index
+/// `0` followed by each level from left to right.
+///
+/// Every function is a `const fn` so the positions can be computed at compile time, for example
+/// by code generating a tree as a `static` array. The layout of an existing tree is given by
+/// `EytzingerTree::layout`.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{Depth, EytzingerLayout};
+///
+/// const LAYOUT: EytzingerLayout = EytzingerLayout::new(3);
+/// const LAST_AT_DEPTH_1: Option<usize> = LAYOUT.child_index(0, 2);
+/// const FIRST_AT_DEPTH_2: Option<usize> = LAYOUT.first_index_at_depth(Depth(2));
+///
+/// assert_eq!(LAST_AT_DEPTH_1, Some(3));
+/// assert_eq!(FIRST_AT_DEPTH_2, Some(4));
+/// assert_eq!(LAYOUT.parent_index(4), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct EytzingerLayout {
+    max_children_per_node: NonZeroUsize,
+    // the number of slots in storage for the children of each node, at least the max children
+    stride: NonZeroUsize,
+}
+
+impl EytzingerLayout {
+    /// Creates the layout of a tree with the specified max children per node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    pub const fn new(max_children_per_node: usize) -> Self {
+        match NonZeroUsize::new(max_children_per_node) {
+            Some(max_children_per_node) => Self::with_arity(max_children_per_node),
+            None => panic!("max_children_per_node should be greater than zero"),
+        }
+    }
+
+    /// Creates the layout of a tree with the specified max children per node.
+    pub const fn with_arity(max_children_per_node: NonZeroUsize) -> Self {
+        Self {
+            max_children_per_node,
+            stride: max_children_per_node,
+        }
+    }
+
+    /// Gets the layout with the same max children per node whose children are stored in blocks
+    /// padded to a power of two, as used by trees built with `EytzingerTreeBuilder::pad_arity`.
+    pub const fn padded(self) -> Self {
+        match NonZeroUsize::new(self.max_children_per_node.get().next_power_of_two()) {
+            Some(stride) => Self { stride, ..self },
+            None => panic!("a power of two is not zero"),
+        }
+    }
+
+    /// Gets whether the children of each node are stored in blocks padded to a power of two.
+    pub const fn is_padded(&self) -> bool {
+        self.stride.get() != self.max_children_per_node.get()
+    }
+
+    /// Gets the max children per node.
+    pub const fn max_children_per_node(&self) -> usize {
+        self.max_children_per_node.get()
+    }
+
+    /// Gets the number of slots in storage for the children of each node.
+    pub const fn stride(&self) -> usize {
+        self.stride.get()
+    }
+
+    /// Gets the index of the child at the offset of the node at the parent index, or `None` if
+    /// the offset is not less than the max children per node or the index would overflow.
+    #[inline]
+    pub const fn child_index(&self, parent_index: usize, child_offset: usize) -> Option<usize> {
+        if child_offset >= self.max_children_per_node() {
+            return None;
+        }
+
+        match parent_index.checked_mul(self.stride()) {
+            Some(first_child_index) => first_child_index.checked_add(child_offset + 1),
+            None => None,
+        }
+    }
+
+    /// Gets the index of the parent of the node at the index, or `None` for the root.
+    #[inline]
+    pub const fn parent_index(&self, child_index: usize) -> Option<usize> {
+        if child_index == 0 {
+            return None;
+        }
+
+        let stride = self.stride();
+        if stride.is_power_of_two() {
+            Some((child_index - 1) >> stride.trailing_zeros())
+        } else {
+            Some((child_index - 1) / stride)
+        }
+    }
+
+    /// Gets the index of the leftmost position at the depth, or `None` if it would overflow.
+    pub const fn first_index_at_depth(&self, depth: Depth) -> Option<usize> {
+        let stride = self.stride();
+        let mut first_index: usize = 0;
+        let mut level_len: usize = 1;
+
+        let mut level = 0;
+        while level < depth.0 {
+            if level > 0 {
+                level_len = match level_len.checked_mul(stride) {
+                    Some(level_len) => level_len,
+                    None => return None,
+                };
+            }
+            first_index = match first_index.checked_add(level_len) {
+                Some(first_index) => first_index,
+                None => return None,
+            };
+            level += 1;
+        }
+
+        Some(first_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_index_at_depth_is_the_first_child_of_the_previous_level() {
+        for layout in [
+            EytzingerLayout::new(1),
+            EytzingerLayout::new(2),
+            EytzingerLayout::new(3),
+            EytzingerLayout::new(3).padded(),
+        ] {
+            let mut first_index = Some(0);
+            for depth in 0..200 {
+                assert_eq!(layout.first_index_at_depth(Depth(depth)), first_index);
+                first_index = first_index.and_then(|index| layout.child_index(index, 0));
+            }
+        }
+
+        assert_eq!(
+            EytzingerLayout::new(2).first_index_at_depth(Depth(usize::BITS - 1)),
+            Some(usize::MAX / 2)
+        );
+        assert_eq!(
+            EytzingerLayout::new(2).first_index_at_depth(Depth(usize::BITS)),
+            Some(usize::MAX)
+        );
+        assert_eq!(
+            EytzingerLayout::new(2).first_index_at_depth(Depth(usize::BITS + 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn parent_index_inverts_child_index() {
+        let layout = EytzingerLayout::new(5).padded();
+        assert_eq!(layout.stride(), 8);
+
+        for parent_index in 0..100 {
+            for child_offset in 0..5 {
+                let child_index = layout.child_index(parent_index, child_offset).unwrap();
+                assert_eq!(layout.parent_index(child_index), Some(parent_index));
+            }
+            assert_eq!(layout.child_index(parent_index, 5), None);
+        }
+        assert_eq!(layout.child_index(usize::MAX, 0), None);
+    }
+}
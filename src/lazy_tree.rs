@@ -0,0 +1,214 @@
+use crate::{Depth, EytzingerTree, Node, NodePath};
+
+/// An Eytzinger tree whose children are only created when they are first needed.
+///
+/// The expansion function is given the path and value of a node and returns the values of its
+/// children, these are placed in child slots `0`, `1`, and so on. A node is expanded at most once
+/// and nodes which already have children are never expanded. This allows trees which are too
+/// large (or infinite) to be built up front, such as game trees, to be navigated on demand.
+pub struct LazyTree<N, F> {
+    tree: EytzingerTree<N>,
+    expanded: Vec<bool>,
+    expand: F,
+}
+
+impl<N, F> LazyTree<N, F>
+where
+    F: FnMut(&NodePath, &N) -> Vec<N>,
+{
+    /// Creates a new lazy tree from the already materialized nodes and the expansion function.
+    pub fn new(tree: EytzingerTree<N>, expand: F) -> Self {
+        Self {
+            tree,
+            expanded: vec![],
+            expand,
+        }
+    }
+
+    /// Gets the materialized part of the tree.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Gets the materialized part of the tree, consuming the lazy tree.
+    pub fn into_tree(self) -> EytzingerTree<N> {
+        self.tree
+    }
+
+    /// Gets whether the node at the specified path has been expanded.
+    pub fn is_expanded(&self, path: &NodePath) -> bool {
+        self.tree
+            .path_index(path)
+            .and_then(|index| self.expanded.get(index))
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    /// Gets the node at the specified path, expanding every node along the way including the node
+    /// itself.
+    ///
+    /// # Returns
+    ///
+    /// The node or `None` if there is no node at the path after expansion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an expansion returns more values than `max_children_per_node`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, LazyTree, NodePath};
+    ///
+    /// let mut root = EytzingerTree::new(2);
+    /// root.set_root_value(1u64);
+    ///
+    /// let mut tree = LazyTree::new(root, |_: &NodePath, value: &u64| {
+    ///     vec![value * 2, value * 2 + 1]
+    /// });
+    ///
+    /// let node = tree.get(&NodePath::from(vec![1, 0, 1])).unwrap();
+    /// assert_eq!(node.value(), &13);
+    /// ```
+    pub fn get(&mut self, path: &NodePath) -> Option<Node<'_, N>> {
+        self.tree.root()?;
+
+        let mut index = 0;
+        for &child_offset in path.child_offsets() {
+            self.expand_index(index);
+
+            index = self
+                .tree
+                .index_calculator
+                .checked_child_index(index, child_offset)?;
+            self.tree.node(index)?;
+        }
+        self.expand_index(index);
+
+        self.tree.node(index)
+    }
+
+    /// Expands every node above the specified depth, so all nodes down to that depth exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an expansion returns more values than `max_children_per_node`.
+    pub fn expand_to_depth(&mut self, depth: Depth) {
+        let mut current_level: Vec<usize> = self.tree.root().map(|_| 0).into_iter().collect();
+
+        for _ in 0..depth.get() {
+            let mut next_level = vec![];
+
+            for index in current_level {
+                self.expand_index(index);
+
+                next_level.extend(
+                    self.tree
+                        .child_indexes(index)
+                        .filter(|&child_index| self.tree.node(child_index).is_some()),
+                );
+            }
+
+            current_level = next_level;
+        }
+    }
+
+    fn expand_index(&mut self, index: usize) {
+        if self.expanded.get(index).cloned().unwrap_or(false) {
+            return;
+        }
+
+        let has_children = self
+            .tree
+            .child_indexes(index)
+            .any(|child_index| self.tree.node(child_index).is_some());
+
+        if !has_children {
+            let path = self.tree.path(index);
+            let value = self
+                .tree
+                .node(index)
+                .expect("only existing nodes should be expanded")
+                .value();
+
+            let children = (self.expand)(&path, value);
+            assert!(
+                children.len() <= self.tree.max_children_per_node(),
+                "an expansion should not return more than max_children_per_node values"
+            );
+
+            for (child_offset, child) in children.into_iter().enumerate() {
+                self.tree.set_child_value(index, child_offset, child);
+            }
+        }
+
+        if self.expanded.len() <= index {
+            self.expanded.resize(index + 1, false);
+        }
+        self.expanded[index] = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Depth, EytzingerTree, LazyTree, NodePath};
+
+    fn counting_tree() -> LazyTree<u32, impl FnMut(&NodePath, &u32) -> Vec<u32>> {
+        let mut root = EytzingerTree::new(3);
+        root.set_root_value(0);
+
+        LazyTree::new(
+            root,
+            |path: &NodePath, _: &u32| {
+                if path.depth() < 4 {
+                    vec![1, 2]
+                } else {
+                    vec![]
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn get_only_expands_the_path() {
+        let mut tree = counting_tree();
+
+        let node = tree.get(&NodePath::from(vec![1, 0])).unwrap();
+        assert_eq!(node.value(), &1);
+
+        // root (1) + its children (2) + the second child's children (2) + the target's children (2)
+        assert_eq!(tree.tree().len(), 7);
+        assert!(tree.is_expanded(&NodePath::from(vec![1])));
+        assert!(!tree.is_expanded(&NodePath::from(vec![0])));
+    }
+
+    #[test]
+    fn get_returns_none_for_missing_nodes() {
+        let mut tree = counting_tree();
+
+        assert!(tree.get(&NodePath::from(vec![2])).is_none());
+        assert!(tree.get(&NodePath::from(vec![7])).is_none());
+        assert!(tree.get(&NodePath::from(vec![0, 0, 0, 0, 0])).is_none());
+    }
+
+    #[test]
+    fn expand_to_depth_materializes_every_level() {
+        let mut tree = counting_tree();
+
+        tree.expand_to_depth(Depth(3));
+
+        assert_eq!(tree.tree().len(), 1 + 2 + 4 + 8);
+    }
+
+    #[test]
+    fn existing_children_are_not_expanded() {
+        let mut root = EytzingerTree::new(2);
+        root.set_root_value(0).set_child_value(1, 5);
+
+        let mut tree = LazyTree::new(root, |_: &NodePath, _: &u32| vec![1, 1]);
+
+        let root = tree.get(&NodePath::root()).unwrap();
+        assert!(root.child(0).is_none());
+        assert_eq!(root.child(1).map(|n| *n.value()), Some(5));
+    }
+}
@@ -1,3 +1,5 @@
+#![forbid(unsafe_code)]
+
 mod eytzinger_index_calculator;
 pub(crate) use self::eytzinger_index_calculator::EytzingerIndexCalculator;
 
@@ -7,36 +9,266 @@ pub use self::node_mut::NodeMut;
 mod node;
 pub use self::node::Node;
 
+mod node_path;
+pub use self::node_path::NodePath;
+
+mod error;
+pub use self::error::Error;
+
+mod ancestor_policy;
+pub use self::ancestor_policy::AncestorPolicy;
+
+mod builder;
+pub use self::builder::EytzingerTreeBuilder;
+
+mod balance_report;
+pub use self::balance_report::BalanceReport;
+
+mod depth;
+pub use self::depth::Depth;
+
+mod layout;
+pub use self::layout::EytzingerLayout;
+
+mod tree_ref;
+pub use self::tree_ref::EytzingerTreeRef;
+
+mod normalized_tree;
+pub use self::normalized_tree::NormalizedTree;
+
+mod versions;
+use self::versions::Versions;
+
+#[cfg(feature = "sexpr")]
+mod sexpr;
+
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+
+mod node_ref;
+pub use self::node_ref::NodeRef;
+
+mod subtree_mut;
+pub use self::subtree_mut::SubtreeMut;
+
+mod lazy_tree;
+pub use self::lazy_tree::LazyTree;
+
+pub mod bounded;
+pub mod boxed;
 pub mod entry;
+pub mod entry_mut;
+pub mod export;
+pub mod expr;
+pub mod indexed;
+pub mod interop;
+pub mod ml;
+#[cfg(feature = "overflow")]
+pub mod overflow;
+pub mod query;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod scapegoat;
+pub mod segtree;
+pub mod set;
+pub mod sink;
+pub mod spatial;
 pub mod traversal;
+pub mod trie;
+pub mod zipper;
 
 use crate::{
     entry::{Entry, VacantEntry},
+    entry_mut::{EntryMut, VacantEntryMut},
+    export::Exporter,
+    query::Rule,
     traversal::{
-        BreadthFirstIter, BreadthFirstIterator, DepthFirstIter, DepthFirstIterator,
-        DepthFirstOrder, NodeChildIter,
+        BestFirstIter, BreadthFirstIter, BreadthFirstIterator, BreadthFirstRevIter,
+        BreadthFirstValuesMut, DepthFirstIter, DepthFirstIterator, DepthFirstOrder, InOrderIter,
+        IterSnapshot, Levels, NodeChildIter, SubtreeDrain,
     },
 };
 use std::{
-    cmp::PartialEq,
+    cmp::{self, Ordering, PartialEq},
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    fmt,
     hash::{Hash, Hasher},
-    mem,
-    ops::Range,
+    iter, mem,
+    num::NonZeroUsize,
+    ops::{ControlFlow, Range},
+    sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
+    thread,
 };
 
 /// An Eytzinger tree is an N-tree stored in an array structure.
-#[derive(Debug, Clone, Eq)]
+///
+/// No operation on the tree, its nodes or its iterators recurses, so the depth of a tree is only
+/// limited by memory and not by the size of the call stack. As the storage grows exponentially
+/// with depth, trees deeper than `usize::BITS` levels are only possible with a single child per
+/// node.
+///
+/// # Complexity
+///
+/// Where `n` is the number of nodes, `k` the max children per node and `s` the length of the
+/// storage, which is the index of the last node plus one:
+///
+/// - Getting a node's value, parent or child is `O(1)`.
+/// - Setting a value is `O(1)`, amortized when the storage grows.
+/// - The breadth-first and depth-first iterators are `O(n * k)` over a whole traversal.
+/// - Removing or splitting off a subtree is linear in the part of the storage taken by the
+///   subtree, which is at most `s`.
+/// - Comparing, hashing, cloning and mapping are `O(s)`.
+///
+/// `s` is between `n` and `k^d` where `d` is the depth of the tree, so the storage is compact for
+/// complete trees and grows quickly for sparse, deep trees.
+#[derive(Eq)]
 pub struct EytzingerTree<N> {
     nodes: Vec<Option<N>>,
     index_calculator: EytzingerIndexCalculator,
     len: usize,
+    tree_id: u64,
+    generation: u64,
+    versions: Option<Versions>,
 }
 
 impl<N: PartialEq> PartialEq for EytzingerTree<N> {
     fn eq(&self, other: &Self) -> bool {
-        self.index_calculator == other.index_calculator
-            && self.len == other.len
-            && self.enumerate_values().eq(other.enumerate_values())
+        self.eq_by(other, PartialEq::eq)
+    }
+}
+
+impl<N: fmt::Debug> fmt::Debug for EytzingerTree<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the id, generation and versions are bookkeeping which differs between equal trees
+        f.debug_struct("EytzingerTree")
+            .field("nodes", &self.nodes)
+            .field("max_children_per_node", &self.max_children_per_node())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<N: Clone> Clone for EytzingerTree<N> {
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            index_calculator: self.index_calculator,
+            len: self.len,
+            tree_id: next_tree_id(),
+            generation: self.generation,
+            versions: self.versions.clone(),
+        }
+    }
+
+    /// Clones the source into this tree, reusing the existing storage rather than allocating.
+    fn clone_from(&mut self, source: &Self) {
+        self.nodes.clone_from(&source.nodes);
+        self.index_calculator = source.index_calculator;
+        self.len = source.len;
+        self.tree_id = next_tree_id();
+        self.generation = source.generation;
+        self.versions.clone_from(&source.versions);
+    }
+}
+
+impl<N> From<(usize, N)> for EytzingerTree<N> {
+    /// Creates a new Eytzinger tree from the maximum number of child nodes per parent and the
+    /// value of the root, see `EytzingerTree::with_root`.
+    fn from((max_children_per_node, value): (usize, N)) -> Self {
+        Self::with_root(max_children_per_node, value)
+    }
+}
+
+impl<N> IntoIterator for EytzingerTree<N> {
+    type Item = N;
+    type IntoIter = BreadthFirstIterator<N>;
+
+    /// Gets a breadth-first iterator over the owned values, see `into_breadth_first_iterator`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_breadth_first_iterator()
+    }
+}
+
+impl<'a, N> IntoIterator for &'a EytzingerTree<N> {
+    type Item = Node<'a, N>;
+    type IntoIter = BreadthFirstIter<'a, N>;
+
+    /// Gets a breadth-first iterator over the nodes, see `breadth_first_iter`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.breadth_first_iter()
+    }
+}
+
+impl<'a, N> IntoIterator for &'a mut EytzingerTree<N> {
+    type Item = &'a mut N;
+    type IntoIter = BreadthFirstValuesMut<'a, N>;
+
+    /// Gets a breadth-first iterator over the mutable values, see `breadth_first_values_mut`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.breadth_first_values_mut()
+    }
+}
+
+impl<N> Extend<(usize, N)> for EytzingerTree<N> {
+    /// Sets the value of the node at each index, replacing any existing value. The pairs may be
+    /// given in any order, the value of the last pair for an index is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parent of any node would not exist or an index is not the position of a
+    /// child, in which case the nodes before it in index order have been set.
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (usize, N)>,
+    {
+        // parents are stored before their children
+        let mut index_values: Vec<_> = iter.into_iter().collect();
+        index_values.sort_by_key(|&(index, _)| index);
+
+        for (index, value) in index_values {
+            if let Some(parent_index) = self.parent_index(index) {
+                let child_offset = index - self.child_index(parent_index, 0);
+                assert!(
+                    child_offset < self.max_children_per_node(),
+                    "the index {} should be the position of a child",
+                    index
+                );
+                assert!(
+                    self.node(parent_index).is_some(),
+                    "the parent of the node at index {} should exist",
+                    index
+                );
+            }
+
+            self.replace_value(index, value);
+        }
+    }
+}
+
+impl<N> FromIterator<(usize, N)> for EytzingerTree<N> {
+    /// Creates a new binary Eytzinger tree from the indexes and values of its nodes, see
+    /// `Extend`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree: EytzingerTree<_> = vec![(2, "right"), (0, "root"), (5, "right-left")]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// let values: Vec<_> = tree.into_iter().collect();
+    /// assert_eq!(values, vec!["root", "right", "right-left"]);
+    /// ```
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, N)>,
+    {
+        let mut tree = Self::new(2);
+        tree.extend(iter);
+        tree
     }
 }
 
@@ -59,257 +291,3152 @@ impl<N> EytzingerTree<N> {
     /// # Returns
     ///
     /// The new Eytzinger tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero, use `with_arity` to avoid this.
     pub fn new(max_children_per_node: usize) -> Self {
+        Self::with_index_calculator(EytzingerIndexCalculator::new(max_children_per_node))
+    }
+
+    /// Creates a new Eytzinger tree with the specified maximum number of child nodes per parent.
+    ///
+    /// Unlike `new` this cannot panic as the arity is known to be non-zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let tree = NonZeroUsize::new(4).map(EytzingerTree::<u32>::with_arity).unwrap();
+    /// assert_eq!(tree.max_children_per_node(), 4);
+    ///
+    /// assert!(NonZeroUsize::new(0).map(EytzingerTree::<u32>::with_arity).is_none());
+    /// ```
+    pub fn with_arity(max_children_per_node: NonZeroUsize) -> Self {
+        Self::with_index_calculator(EytzingerIndexCalculator::with_arity(max_children_per_node))
+    }
+
+    /// Creates a new empty Eytzinger tree with the layout of the index calculator.
+    fn with_index_calculator(index_calculator: EytzingerIndexCalculator) -> Self {
         Self {
             nodes: vec![],
-            index_calculator: EytzingerIndexCalculator::new(max_children_per_node),
+            index_calculator,
             len: 0,
+            tree_id: next_tree_id(),
+            generation: 0,
+            versions: None,
         }
     }
 
-    /// Gets a depth-first iterator over all nodes.
-    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> DepthFirstIter<N> {
-        DepthFirstIter::new(self, self.root(), order)
+    /// Creates a new Eytzinger tree with the specified maximum number of child nodes per parent
+    /// and a root with the specified value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::with_root(3, 1);
+    /// tree.root_mut().unwrap().set_child_value(2, 2);
+    ///
+    /// assert_eq!(tree.max_children_per_node(), 3);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn with_root(max_children_per_node: usize, value: N) -> Self {
+        let mut tree = Self::new(max_children_per_node);
+        tree.set_root_value(value);
+        tree
     }
 
-    /// Gets a breadth-first iterator over all nodes.
-    pub fn breadth_first_iter(&self) -> BreadthFirstIter<N> {
-        BreadthFirstIter::new(self, self.root())
+    /// Creates a new binary Eytzinger tree with a root with the specified value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::singleton("root");
+    ///
+    /// assert_eq!(tree.max_children_per_node(), 2);
+    /// assert_eq!(tree.root().map(|n| *n.value()), Some("root"));
+    /// ```
+    pub fn singleton(value: N) -> Self {
+        Self::with_root(2, value)
     }
 
-    pub fn into_depth_first_iterator(self, order: DepthFirstOrder) -> DepthFirstIterator<N> {
-        DepthFirstIterator::new(self, order)
+    /// Creates a new complete binary Eytzinger tree from sorted values, laid out so an in-order
+    /// traversal yields them in order. This is the layout searched by a descent from the root,
+    /// the first levels are stored together so they stay in cache across searches.
+    ///
+    /// Every level but the last is full and the last is filled from the left, so the tree is
+    /// dense. The values are not checked to be sorted, see `is_search_tree`.
+    ///
+    /// The tree is always binary, there is no arity parameter. Each node holds a single value so
+    /// only its left and right children can be ordered around it, a node with more children has
+    /// no place for the values which would separate them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::from_sorted_slice(&[1, 2, 3, 4, 5, 6]);
+    /// assert!(tree.is_dense());
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![4, 2, 6, 1, 3, 5]);
+    ///
+    /// let in_order: Vec<_> = tree.in_order_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(in_order, vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn from_sorted_slice(sorted: &[N]) -> Self
+    where
+        N: Clone,
+    {
+        Self::from_sorted_iter(sorted.iter().cloned())
     }
 
-    pub fn into_breadth_first_iterator(self) -> BreadthFirstIterator<N> {
-        BreadthFirstIterator::new(self)
+    /// Creates a new complete binary Eytzinger tree from sorted values, see `from_sorted_slice`.
+    /// Like it, only binary trees can be built.
+    pub fn from_sorted_iter<I>(sorted: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut values = sorted.into_iter().collect::<Vec<_>>().into_iter();
+        let len = values.len();
+
+        let mut tree = Self::new(2);
+        tree.nodes.resize_with(len, || None);
+        tree.len = len;
+
+        // an iterative in-order traversal of the indexes, filling each with the next value
+        let mut pending = vec![];
+        let mut current = 0;
+        loop {
+            while current < len {
+                pending.push(current);
+                current = 2 * current + 1;
+            }
+
+            match pending.pop() {
+                Some(index) => {
+                    tree.nodes[index] = values.next();
+                    current = 2 * index + 2;
+                }
+                None => break,
+            }
+        }
+
+        tree
     }
 
-    /// Gets whether the Eytzinger tree is empty.
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    /// Creates a new Eytzinger tree from values and the paths of the nodes they belong to, in any
+    /// order. When a path is repeated the last value is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChildIndexOutOfRange` or `Error::DepthOverflow` if a path cannot be stored
+    /// and `Error::OrphanInsert` if the parent of a node was not given and the policy is
+    /// `AncestorPolicy::Require`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{AncestorPolicy, EytzingerTree, NodePath};
+    ///
+    /// let path_values = vec![
+    ///     (NodePath::from(vec![1, 0]), 3),
+    ///     (NodePath::root(), 1),
+    ///     (NodePath::from(vec![1]), 2),
+    ///     (NodePath::from(vec![0, 0]), 4),
+    /// ];
+    ///
+    /// let tree = EytzingerTree::from_path_values(2, path_values, AncestorPolicy::Discard).unwrap();
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn from_path_values<I>(
+        max_children_per_node: usize,
+        path_values: I,
+        policy: AncestorPolicy,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (NodePath, N)>,
+    {
+        let mut tree = Self::with_path_values(max_children_per_node, path_values)?;
+
+        // parents are stored before their children so discarding cascades to every descendant
+        for index in 1..tree.nodes.len() {
+            let parent_index = tree
+                .parent_index(index)
+                .expect("only the root has no parent");
+            if tree.nodes[index].is_none() || tree.nodes[parent_index].is_some() {
+                continue;
+            }
+
+            match policy {
+                AncestorPolicy::Require => {
+                    return Err(Error::OrphanInsert {
+                        path: tree.path(index),
+                    })
+                }
+                AncestorPolicy::Discard => {
+                    tree.nodes[index] = None;
+                    tree.len -= 1;
+                }
+            }
+        }
+
+        Ok(tree)
     }
 
-    /// Gets the number of nodes in the Eytzinger tree.
-    pub fn len(&self) -> usize {
-        self.len
+    /// Creates a new Eytzinger tree from values and the paths of the nodes they belong to, as with
+    /// `from_path_values`. Ancestors which were not given are created with the values returned by
+    /// `fill` for their paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChildIndexOutOfRange` or `Error::DepthOverflow` if a path cannot be stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, NodePath};
+    ///
+    /// let path_values = vec![(NodePath::from(vec![2, 1]), "leaf".to_string())];
+    ///
+    /// let tree = EytzingerTree::from_path_values_filling(3, path_values, |path| path.to_string())
+    ///     .unwrap();
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| n.value().as_str()).collect();
+    /// assert_eq!(values, vec!["/", "/2", "leaf"]);
+    /// ```
+    pub fn from_path_values_filling<I, F>(
+        max_children_per_node: usize,
+        path_values: I,
+        mut fill: F,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (NodePath, N)>,
+        F: FnMut(&NodePath) -> N,
+    {
+        let mut tree = Self::with_path_values(max_children_per_node, path_values)?;
+
+        // children are filled before their parents so each missing ancestor is only filled once
+        for index in (1..tree.nodes.len()).rev() {
+            if tree.nodes[index].is_none() {
+                continue;
+            }
+
+            let parent_index = tree
+                .parent_index(index)
+                .expect("only the root has no parent");
+            if tree.nodes[parent_index].is_none() {
+                let value = fill(&tree.path(parent_index));
+                tree.replace_value(parent_index, value);
+            }
+        }
+
+        Ok(tree)
     }
 
-    /// Gets the maximum number of children per parent node.
-    pub fn max_children_per_node(&self) -> usize {
-        self.index_calculator.max_children_per_node()
+    /// Creates a new Eytzinger tree with the values at their paths, without regard for whether
+    /// their parents exist.
+    fn with_path_values<I>(max_children_per_node: usize, path_values: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (NodePath, N)>,
+    {
+        let mut tree = Self::new(max_children_per_node);
+
+        for (path, value) in path_values {
+            let index = tree.checked_path_index(&path)?;
+            tree.replace_value(index, value);
+        }
+
+        Ok(tree)
     }
 
-    /// Clears the Eytzinger tree, removing all nodes.
-    pub fn clear(&mut self) {
-        self.remove_root_value();
+    /// Gets a depth-first iterator over all nodes.
+    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> DepthFirstIter<'_, N> {
+        DepthFirstIter::new(self, self.root(), order)
     }
 
-    /// Gets the root node, `None` if there was no root node.
-    ///
-    /// The root node may be set with `set_root_value`.
-    pub fn root(&self) -> Option<Node<N>> {
-        self.node(0)
+    /// Gets a breadth-first iterator over all nodes.
+    pub fn breadth_first_iter(&self) -> BreadthFirstIter<'_, N> {
+        BreadthFirstIter::new(self, self.root())
     }
 
-    /// Gets the mutable root node, `None` if there was no root node.
+    /// Gets a breadth-first iterator over the mutable values of all nodes.
     ///
-    /// The root node may be set with `set_root_value`.
-    pub fn root_mut(&mut self) -> Option<NodeMut<N>> {
-        self.node_mut(0).ok()
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// for value in tree.breadth_first_values_mut() {
+    ///     *value *= 10;
+    /// }
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![10, 20]);
+    /// ```
+    pub fn breadth_first_values_mut(&mut self) -> BreadthFirstValuesMut<'_, N> {
+        if let Some(versions) = &mut self.versions {
+            let nodes = &self.nodes;
+            versions.record((0..nodes.len()).filter(|&index| nodes[index].is_some()));
+        }
+
+        BreadthFirstValuesMut::new(&mut self.nodes, self.len)
     }
 
-    /// Sets the value of the root node. All child nodes will remain as they are.
+    /// Gets an iterator over the levels of the tree from the root down, each with its depth and
+    /// an iterator over its nodes from left to right, see `Levels`.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// The new root node.
-    pub fn set_root_value(&mut self, new_value: N) -> NodeMut<N> {
-        self.set_value(0, new_value)
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 4);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let rendered: Vec<_> = tree
+    ///     .levels()
+    ///     .map(|(depth, level)| {
+    ///         let values: Vec<_> = level.map(|n| n.value().to_string()).collect();
+    ///         format!("{}: {}", depth, values.join(" "))
+    ///     })
+    ///     .collect();
+    /// assert_eq!(rendered, vec!["0: 1", "1: 2 3", "2: 4"]);
+    /// ```
+    pub fn levels(&self) -> Levels<'_, N> {
+        Levels::new(self, self.root())
     }
 
-    /// Removes the root value. This will also remove all children.
+    /// Gets a reverse breadth-first iterator over all nodes, the deepest level is returned first
+    /// and the nodes within each level are returned from left to right. This is the order in
+    /// which every child is returned before its parent one level at a time.
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// The old root value if there was one.
-    pub fn remove_root_value(&mut self) -> (Option<N>, VacantEntry<N>) {
-        self.nodes.truncate(1);
-        self.len = 0;
-        let value = self.nodes[0].take();
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(0, 4);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let values: Vec<_> = tree.breadth_first_rev_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![4, 2, 3, 1]);
+    /// ```
+    pub fn breadth_first_rev_iter(&self) -> BreadthFirstRevIter<'_, N> {
+        BreadthFirstRevIter::new(self, self.root())
+    }
 
-        (
-            value,
-            VacantEntry {
-                tree: self,
-                index: 0,
-            },
-        )
+    /// Calls the closure on every node in breadth-first order, stopping at the first error.
+    ///
+    /// The storage is laid out in breadth-first order so this is a single scan of it, without
+    /// the iterator state kept by `breadth_first_iter`.
+    pub fn try_for_each_breadth_first<E, F>(&self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Node<'_, N>) -> Result<(), E>,
+    {
+        let flow =
+            self.try_fold_occupied(iter::once(0..self.nodes.len()), (), |(), index| {
+                match f(Node { tree: self, index }) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(error) => ControlFlow::Break(error),
+                }
+            });
+
+        match flow {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(error) => Err(error),
+        }
     }
 
-    /// Gets the entry for the root node.
+    /// Calls the closure on every node in breadth-first order, see `try_for_each_breadth_first`.
     ///
     /// # Examples
     ///
-    /// ```    
-    /// use lz_eytzinger_tree::{EytzingerTree, entry::Entry};
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
     ///
-    /// let tree = {
-    ///     let mut tree = EytzingerTree::<u32>::new(8);
-    ///     tree.root_entry().or_insert(5);
-    ///     tree
-    /// };
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(1, 2).set_child_value(0, 4);
+    ///     root.set_child_value(0, 3);
+    /// }
     ///
-    /// let root = tree.root().unwrap();
-    /// assert_eq!(root.value(), &5);
+    /// let mut values = vec![];
+    /// tree.for_each_breadth_first(|node| values.push(*node.value()));
+    /// assert_eq!(values, vec![1, 3, 2, 4]);
     /// ```
-    pub fn root_entry(&mut self) -> Entry<N> {
-        self.entry(0)
+    pub fn for_each_breadth_first<F>(&self, mut f: F)
+    where
+        F: FnMut(Node<'_, N>),
+    {
+        self.fold_breadth_first((), |(), node| f(node));
     }
 
-    /// Builds a new `EytzingerTree<N>` with the values mapped
-    /// using the specified selector.
-    pub fn map<U, F>(self, mut f: F) -> EytzingerTree<U>
+    /// Folds every node into an accumulator in breadth-first order, see
+    /// `try_for_each_breadth_first`.
+    pub fn fold_breadth_first<B, F>(&self, init: B, mut f: F) -> B
     where
-        F: FnMut(N) -> U,
+        F: FnMut(B, Node<'_, N>) -> B,
     {
-        let nodes = self.nodes.into_iter().map(|n| n.map(&mut f)).collect();
+        let flow = self.try_fold_occupied(
+            iter::once(0..self.nodes.len()),
+            init,
+            |accumulator, index| {
+                ControlFlow::<Infallible, _>::Continue(f(accumulator, Node { tree: self, index }))
+            },
+        );
 
-        EytzingerTree {
-            nodes: nodes,
-            index_calculator: self.index_calculator,
+        match flow {
+            ControlFlow::Continue(accumulator) => accumulator,
+            ControlFlow::Break(never) => match never {},
+        }
+    }
+
+    /// Gets an in-order iterator over all nodes of a binary tree, every node is returned after the
+    /// nodes of its left (`0`) child and before the nodes of its right (`1`) child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(4);
+    ///     root.set_child_value(0, 2).set_child_value(1, 3);
+    ///     root.set_child_value(1, 6);
+    /// }
+    ///
+    /// let values: Vec<_> = tree.in_order_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![2, 3, 4, 6]);
+    /// ```
+    pub fn in_order_iter(&self) -> InOrderIter<'_, N> {
+        InOrderIter::new(self, self.root())
+    }
+
+    /// Gets whether this binary tree is ordered as a search tree by the comparison function, that
+    /// is every value is not less than any value of its left (`0`) child's subtree and not greater
+    /// than any value of its right (`1`) child's subtree. An empty tree is a search tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(4);
+    ///     root.set_child_value(0, 2).set_child_value(1, 3);
+    /// }
+    /// assert!(tree.is_search_tree(Ord::cmp));
+    ///
+    /// tree.root_mut().unwrap().set_child_value(1, 1);
+    /// assert!(!tree.is_search_tree(Ord::cmp));
+    /// ```
+    pub fn is_search_tree<F>(&self, mut cmp: F) -> bool
+    where
+        F: FnMut(&N, &N) -> Ordering,
+    {
+        let mut values = self.in_order_iter().map(|n| n.value());
+
+        let mut previous = match values.next() {
+            Some(value) => value,
+            None => return true,
+        };
+        for value in values {
+            if cmp(previous, value) == Ordering::Greater {
+                return false;
+            }
+            previous = value;
+        }
+
+        true
+    }
+
+    /// Searches this binary search tree for a node matching the comparison function, which
+    /// returns the ordering of a node's value relative to the target. The search descends from
+    /// the root to the right (`1`) child of any node ordered `Less` than the target and to the
+    /// left (`0`) child of any node ordered `Greater`, so a tree built by `from_sorted_slice` is
+    /// searched in logarithmic time. Each step computes the next index without a branch on the
+    /// comparison and the first levels are stored together, so they stay in cache.
+    ///
+    /// If several nodes match any one of them may be returned. The result is unspecified if the
+    /// tree is not a search tree, see `is_search_tree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::from_sorted_slice(&[(1, "a"), (3, "b"), (5, "c")]);
+    ///
+    /// let found = tree.search_by(|(key, _)| key.cmp(&3));
+    /// assert_eq!(found.map(|n| n.value().1), Some("b"));
+    /// assert!(tree.search_by(|(key, _)| key.cmp(&4)).is_none());
+    /// ```
+    pub fn search_by<F>(&self, cmp: F) -> Option<Node<'_, N>>
+    where
+        F: FnMut(&N) -> Ordering,
+    {
+        let index = self.as_tree_ref().search_index_by(cmp)?;
+        Some(Node { tree: self, index })
+    }
+
+    /// Searches this binary search tree for a node with a value equal to `value`, see
+    /// `search_by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::from_sorted_iter(0..100);
+    ///
+    /// assert_eq!(tree.search(&42).map(|n| *n.value()), Some(42));
+    /// assert!(tree.search(&100).is_none());
+    /// ```
+    pub fn search(&self, value: &N) -> Option<Node<'_, N>>
+    where
+        N: Ord,
+    {
+        self.search_by(|current| current.cmp(value))
+    }
+
+    /// Gets an iterator over the nodes in ascending order of `key`, see `BestFirstIter`.
+    pub fn best_first_iter<'a, K, F>(&'a self, key: F) -> BestFirstIter<'a, N, K, F>
+    where
+        K: Ord,
+        F: FnMut(Node<'a, N>) -> K,
+    {
+        BestFirstIter::new(self, self.root(), key)
+    }
+
+    pub fn into_depth_first_iterator(self, order: DepthFirstOrder) -> DepthFirstIterator<N> {
+        DepthFirstIterator::new(self, order)
+    }
+
+    pub fn into_breadth_first_iterator(self) -> BreadthFirstIterator<N> {
+        BreadthFirstIterator::new(self)
+    }
+
+    /// Captures the current depth-first order of the nodes so they can be visited one by one
+    /// while the tree is changed, see `IterSnapshot`.
+    pub fn iter_snapshot(&self, order: DepthFirstOrder) -> IterSnapshot {
+        let indexes = self.depth_first_iter(order).map(|n| n.index).collect();
+        IterSnapshot::new(self, indexes)
+    }
+
+    /// Gets an iterator which takes the owned values of the subtree at the path out of the tree
+    /// in depth-first order, the rest of the tree is left intact. Unlike `split_off` followed by
+    /// an owning iterator no nodes are re-indexed.
+    ///
+    /// The whole subtree is removed even if the iterator is not exhausted, if there is no node at
+    /// the path the iterator is empty and the tree is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{traversal::DepthFirstOrder, EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 3);
+    ///     root.set_child_value(1, 4);
+    /// }
+    ///
+    /// let values: Vec<_> = tree
+    ///     .drain_subtree(&NodePath::from(vec![0]), DepthFirstOrder::PreOrder)
+    ///     .collect();
+    /// assert_eq!(values, vec![2, 3]);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn drain_subtree(
+        &mut self,
+        path: &NodePath,
+        order: DepthFirstOrder,
+    ) -> SubtreeDrain<'_, N> {
+        let root = self
+            .path_index(path)
+            .filter(|&index| matches!(self.nodes.get(index), Some(Some(_))));
+        SubtreeDrain::new(self, root, order)
+    }
+
+    /// Gets an iterator over the path of every node in breadth-first order, without the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let paths: Vec<_> = tree.occupied_paths().collect();
+    /// assert_eq!(
+    ///     paths,
+    ///     vec![
+    ///         NodePath::from(vec![]),
+    ///         NodePath::from(vec![1]),
+    ///         NodePath::from(vec![1, 0]),
+    ///     ]
+    /// );
+    /// ```
+    pub fn occupied_paths(&self) -> impl Iterator<Item = NodePath> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_some())
+            .map(move |(index, _)| self.path(index))
+    }
+
+    /// Gets whether there is a node at the path. Paths with a child offset out of range for the
+    /// tree or too deep to be stored are never occupied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// assert!(tree.contains_path(&NodePath::from(vec![1])));
+    /// assert!(!tree.contains_path(&NodePath::from(vec![0])));
+    /// assert!(!tree.contains_path(&NodePath::from(vec![2])));
+    /// ```
+    pub fn contains_path(&self, path: &NodePath) -> bool {
+        self.path_index(path)
+            .map_or(false, |index| self.node(index).is_some())
+    }
+
+    /// Gets whether the Eytzinger tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the number of nodes in the Eytzinger tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Drives the exporter with every node in pre-order, see the `export` module.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by the exporter, no more nodes are exported after it.
+    pub fn export<E>(&self, exporter: &mut E) -> fmt::Result
+    where
+        E: Exporter<N>,
+    {
+        exporter.begin_tree()?;
+
+        if let Some(root) = self.root() {
+            exporter.begin_node(0, None)?;
+            exporter.emit_value(root.value())?;
+
+            let mut pending = vec![root.child_iter()];
+            while let Some(children) = pending.last_mut() {
+                match children.next() {
+                    Some(child) => {
+                        let child_offset = self
+                            .index_calculator
+                            .child_offset(child.index())
+                            .expect("a child is not the root");
+
+                        exporter.begin_node(pending.len(), Some(child_offset))?;
+                        exporter.emit_value(child.value())?;
+                        pending.push(child.child_iter());
+                    }
+                    None => {
+                        pending.pop();
+                        exporter.end_node(pending.len())?;
+                    }
+                }
+            }
+        }
+
+        exporter.end_tree()
+    }
+
+    /// Gets statistics about the shape of the tree, such as how deep its nodes are on average and
+    /// how balanced each subtree is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(0, 3);
+    ///     root.set_child_value(1, 4);
+    /// }
+    ///
+    /// let report = tree.balance_report();
+    /// assert_eq!(report.average_depth, 1.0);
+    /// assert_eq!(report.max_depth, Some(Depth(2)));
+    ///
+    /// let factors: Vec<_> = report.balance_factors.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(factors, vec![1.5, 2.0, 1.0, 1.0]);
+    /// ```
+    pub fn balance_report(&self) -> BalanceReport {
+        BalanceReport::new(self)
+    }
+
+    /// Gets whether every position in the storage has a node, as it does for a complete tree
+    /// filled in breadth-first order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    /// assert!(tree.is_dense());
+    ///
+    /// tree.root_mut().unwrap().set_child_value(0, 2).set_child_value(1, 3);
+    /// assert!(!tree.is_dense());
+    /// ```
+    pub fn is_dense(&self) -> bool {
+        self.len == self.nodes.len()
+    }
+
+    /// Gets the number of nodes which may be stored without reallocating, see
+    /// `EytzingerTreeBuilder::capacity`.
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Gets an estimate of the number of bytes allocated by the tree, this is the storage for
+    /// every node including vacant nodes and spare capacity, any versioning ticks and the heap
+    /// allocations of each value as estimated by `value_heap_size`. The size of the tree itself
+    /// is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    /// use std::mem;
+    ///
+    /// let mut tree = EytzingerTree::<String>::new(2);
+    /// tree.set_root_value("root".to_string())
+    ///     .set_child_value(1, "leaf".to_string());
+    ///
+    /// let storage = tree.capacity() * mem::size_of::<Option<String>>();
+    /// assert_eq!(tree.heap_size(|_| 0), storage);
+    /// assert_eq!(tree.heap_size(String::capacity), storage + 8);
+    /// ```
+    pub fn heap_size<F>(&self, mut value_heap_size: F) -> usize
+    where
+        F: FnMut(&N) -> usize,
+    {
+        let storage = self.nodes.capacity() * mem::size_of::<Option<N>>();
+        let versions = self.versions.as_ref().map_or(0, Versions::heap_size);
+        let values: usize = self.nodes.iter().flatten().map(&mut value_heap_size).sum();
+
+        storage + versions + values
+    }
+
+    /// Starts recording when each node is modified. The tree keeps a tick which advances with
+    /// every mutation and each node remembers the tick it was last set, changed or had a child
+    /// removed at. The tick starts at zero, which is also the tick of every existing node.
+    ///
+    /// Getting a node's value mutably counts as modifying it, whether or not it is changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    /// tree.enable_versioning();
+    ///
+    /// let synced = tree.current_tick().unwrap();
+    /// tree.root_mut().unwrap().set_child_value(1, 3);
+    ///
+    /// let changed: Vec<_> = tree.nodes_modified_since(synced).map(|n| *n.value()).collect();
+    /// assert_eq!(changed, vec![3]);
+    /// ```
+    pub fn enable_versioning(&mut self) {
+        self.versions.get_or_insert_with(Versions::default);
+    }
+
+    /// Stops recording when each node is modified and forgets every recorded tick.
+    pub fn disable_versioning(&mut self) {
+        self.versions = None;
+    }
+
+    /// Gets the tick of the latest mutation or `None` if versioning is not enabled.
+    pub fn current_tick(&self) -> Option<u64> {
+        self.versions.as_ref().map(Versions::tick)
+    }
+
+    /// Gets the nodes last modified after the tick in breadth-first order. Every node is
+    /// returned if versioning is not enabled, as any of them may have changed.
+    pub fn nodes_modified_since(&self, tick: u64) -> impl Iterator<Item = Node<'_, N>> {
+        self.enumerate_values()
+            .filter(move |&(index, _)| {
+                self.versions
+                    .as_ref()
+                    .map_or(true, |versions| versions.last_modified(index) > tick)
+            })
+            .map(move |(index, _)| Node { tree: self, index })
+    }
+
+    /// Gets the fewest max children per node this tree could be stored with, the most children
+    /// any one of its nodes has. Comparing this with `max_children_per_node` shows how
+    /// over-provisioned the arity is, as storage grows exponentially with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(8);
+    /// assert_eq!(tree.min_arity(), 1);
+    ///
+    /// tree.set_root_value(1).set_child_value(7, 2).set_child_value(3, 3);
+    /// tree.root_mut().unwrap().set_child_value(5, 4);
+    /// assert_eq!(tree.min_arity(), 2);
+    /// ```
+    pub fn min_arity(&self) -> usize {
+        self.enumerate_values()
+            .map(|(index, _)| {
+                self.index_calculator
+                    .stored_child_indexes(index, self.nodes.len())
+                    .filter(|&child_index| self.nodes[child_index].is_some())
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Converts the tree to the arity given by `min_arity`, moving the children of each node to the
+    /// lowest child offsets while keeping their order. The paths of nodes change unless the
+    /// children of every node already started at the first child offset with no gaps.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes of storage which were freed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(8);
+    /// tree.set_root_value(1).set_child_value(7, 2).set_child_value(3, 3);
+    /// tree.root_mut().unwrap().set_child_value(5, 4);
+    ///
+    /// assert!(tree.shrink_arity_to_fit() > 0);
+    /// assert_eq!(tree.max_children_per_node(), 2);
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 4, 2, 3]);
+    /// ```
+    pub fn shrink_arity_to_fit(&mut self) -> usize {
+        let arity = self.min_arity();
+        if arity == self.max_children_per_node() {
+            return 0;
+        }
+
+        let node_size = mem::size_of::<Option<N>>();
+        let old_size = self.nodes.capacity() * node_size;
+
+        let old_index_calculator = self.index_calculator;
+        let mut old_nodes = mem::take(&mut self.nodes);
+        self.index_calculator = old_index_calculator.with_max_children_per_node(
+            NonZeroUsize::new(arity).expect("the min arity is not zero"),
+        );
+        self.len = 0;
+        self.bump_generation();
+        if let Some(versions) = &mut self.versions {
+            versions.forget_indexes();
+        }
+
+        // breadth-first, pairing each old index with its new index
+        let mut pending = VecDeque::new();
+        if matches!(old_nodes.first(), Some(Some(_))) {
+            pending.push_back((0, 0));
+        }
+        while let Some((old_index, new_index)) = pending.pop_front() {
+            let value = old_nodes[old_index].take().expect("the node should exist");
+
+            let children = old_index_calculator
+                .stored_child_indexes(old_index, old_nodes.len())
+                .filter(|&child_index| old_nodes[child_index].is_some());
+            for (child_offset, child_index) in children.enumerate() {
+                pending.push_back((child_index, self.child_index(new_index, child_offset)));
+            }
+
+            self.replace_value(new_index, value);
+        }
+
+        self.nodes.shrink_to_fit();
+        old_size.saturating_sub(self.nodes.capacity() * node_size)
+    }
+
+    /// Converts the tree to a new arity, moving each child to the child offset given by
+    /// `slot_map` for its old child offset. Children for which `slot_map` returns `None` are
+    /// dropped along with their descendants. When several children of a node map to the same
+    /// child offset, only the one with the lowest old child offset is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_arity` is zero, if `slot_map` returns a child offset which is not less
+    /// than `new_arity` or if a node would be too deep to be indexed with `new_arity`, which is
+    /// only possible when it is greater than the current max children per node. Every node is
+    /// checked before any storage is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(4);
+    /// tree.set_root_value(1)
+    ///     .set_child_value(0, 2)
+    ///     .set_child_value(2, 3)
+    ///     .set_child_value(3, 4);
+    ///
+    /// // keep the western quadrants
+    /// let tree = tree.project(2, |slot| if slot % 2 == 0 { Some(slot / 2) } else { None });
+    ///
+    /// assert_eq!(tree.max_children_per_node(), 2);
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn project<F>(mut self, new_arity: usize, mut slot_map: F) -> Self
+    where
+        F: FnMut(usize) -> Option<usize>,
+    {
+        let new_arity =
+            NonZeroUsize::new(new_arity).expect("new_arity should be greater than zero");
+        let mut projected = Self::with_index_calculator(
+            self.index_calculator.with_max_children_per_node(new_arity),
+        );
+
+        // breadth-first, pairing each old index with its new index, the values are only placed
+        // once every new index is known to fit in a usize
+        let mut pending = VecDeque::new();
+        let mut placed = Vec::with_capacity(self.len);
+        if matches!(self.nodes.first(), Some(Some(_))) {
+            pending.push_back((0, 0));
+        }
+        while let Some((index, new_index)) = pending.pop_front() {
+            placed.push((index, new_index));
+
+            let first_pending = pending.len();
+            for child_offset in 0..self.max_children_per_node() {
+                let child_index = match self
+                    .index_calculator
+                    .checked_child_index(index, child_offset)
+                {
+                    Some(child_index) if matches!(self.nodes.get(child_index), Some(Some(_))) => {
+                        child_index
+                    }
+                    _ => continue,
+                };
+                let new_child_index = match slot_map(child_offset) {
+                    Some(new_offset) => {
+                        assert!(
+                            new_offset < new_arity.get(),
+                            "the projected child offset should be less than new_arity"
+                        );
+                        projected
+                            .index_calculator
+                            .checked_child_index(new_index, new_offset)
+                            .expect("the projected node should not be too deep to index")
+                    }
+                    None => continue,
+                };
+
+                let already_mapped = pending
+                    .range(first_pending..)
+                    .any(|&(_, mapped_index)| mapped_index == new_child_index);
+                if !already_mapped {
+                    pending.push_back((child_index, new_child_index));
+                }
+            }
+        }
+
+        for (index, new_index) in placed {
+            let value = self.nodes[index].take().expect("the node should exist");
+            projected.replace_value(new_index, value);
+        }
+
+        projected
+    }
+
+    /// Replaces the nodes of this tree with clones of the nodes of the other tree, keeping the
+    /// layout of this tree. The existing storage is reused so repeatedly copying into the same
+    /// tree does not allocate once its storage is large enough.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChildIndexOutOfRange` if a node of the other tree has a child at a child
+    /// offset which is not less than the max children per node of this tree and
+    /// `Error::DepthOverflow` if a node is too deep to be stored in this tree. This tree is left
+    /// unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree};
+    ///
+    /// let mut source = EytzingerTree::<u32>::new(2);
+    /// source.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// let mut scratch = EytzingerTree::<u32>::new(4);
+    /// scratch.copy_from(&source).unwrap();
+    ///
+    /// assert_eq!(scratch.max_children_per_node(), 4);
+    /// let root = scratch.root().unwrap();
+    /// assert_eq!(root.child(1).map(|n| *n.value()), Some(2));
+    ///
+    /// let mut unary = EytzingerTree::<u32>::new(1);
+    /// assert_eq!(
+    ///     unary.copy_from(&source),
+    ///     Err(Error::ChildIndexOutOfRange {
+    ///         child_offset: 1,
+    ///         max_children_per_node: 1
+    ///     })
+    /// );
+    /// assert!(unary.is_empty());
+    /// ```
+    pub fn copy_from(&mut self, other: &Self) -> Result<(), Error>
+    where
+        N: Clone,
+    {
+        if self.index_calculator == other.index_calculator {
+            self.nodes.clone_from(&other.nodes);
+        } else {
+            other.for_each_reindexed(self.index_calculator, |_, _| {})?;
+
+            let nodes = &mut self.nodes;
+            nodes.clear();
+            other
+                .for_each_reindexed(self.index_calculator, |index, value| {
+                    if index >= nodes.len() {
+                        nodes.resize_with(index + 1, || None);
+                    }
+                    nodes[index] = Some(value.clone());
+                })
+                .expect("the other tree should fit in this layout");
+        }
+
+        self.len = other.len;
+        self.bump_generation();
+        if let Some(versions) = &mut self.versions {
+            versions.forget_indexes();
+        }
+        self.record_modified(0..self.nodes.len());
+
+        Ok(())
+    }
+
+    /// Gets the depth of the deepest level whose nodes all have indexes which fit in a `usize`.
+    /// Nodes can never be stored below this depth, whatever the capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let binary = EytzingerTree::<u32>::new(2);
+    /// assert_eq!(binary.max_representable_depth(), Depth(usize::BITS - 1));
+    ///
+    /// let unary = EytzingerTree::<u32>::new(1);
+    /// assert_eq!(unary.max_representable_depth(), Depth(u32::MAX));
+    /// ```
+    pub fn max_representable_depth(&self) -> Depth {
+        Depth::saturating_from_usize(self.index_calculator.max_depth())
+    }
+
+    /// Gets whether the children of each node are stored in blocks padded to a power of two, see
+    /// `EytzingerTreeBuilder::pad_arity`.
+    pub fn is_arity_padded(&self) -> bool {
+        self.layout().is_padded()
+    }
+
+    /// Gets a read-only view of the tree, over the same storage, see `EytzingerTreeRef`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::from_sorted_slice(&[1, 2, 3]);
+    /// let tree_ref = tree.as_tree_ref();
+    ///
+    /// assert_eq!(tree_ref.as_slice(), &[Some(2), Some(1), Some(3)]);
+    /// assert_eq!(tree_ref.search(&3), Some(&3));
+    /// ```
+    pub fn as_tree_ref(&self) -> EytzingerTreeRef<'_, N> {
+        EytzingerTreeRef::from_tree(self)
+    }
+
+    /// Gets the layout of the nodes of the tree in its storage, so the positions of nodes can be
+    /// computed without a tree, see `EytzingerLayout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerLayout, EytzingerTree};
+    ///
+    /// let tree = EytzingerTree::<u32>::new(3);
+    /// assert_eq!(tree.layout(), EytzingerLayout::new(3));
+    /// ```
+    pub fn layout(&self) -> EytzingerLayout {
+        self.index_calculator.layout()
+    }
+
+    /// Gets the maximum number of children per parent node.
+    pub fn max_children_per_node(&self) -> usize {
+        self.index_calculator.max_children_per_node()
+    }
+
+    /// Clears the Eytzinger tree, removing all nodes.
+    pub fn clear(&mut self) {
+        self.remove_root_value();
+    }
+
+    /// Gets the root node, `None` if there was no root node.
+    ///
+    /// The root node may be set with `set_root_value`.
+    pub fn root(&self) -> Option<Node<'_, N>> {
+        self.node(0)
+    }
+
+    /// Gets the mutable root node, `None` if there was no root node.
+    ///
+    /// The root node may be set with `set_root_value`.
+    pub fn root_mut(&mut self) -> Option<NodeMut<'_, N>> {
+        self.node_mut(0).ok()
+    }
+
+    /// Sets the value of the root node. All child nodes will remain as they are.
+    ///
+    /// # Returns
+    ///
+    /// The new root node.
+    pub fn set_root_value(&mut self, new_value: N) -> NodeMut<'_, N> {
+        self.set_value(0, new_value)
+    }
+
+    /// Sets a new root value, moving the existing tree down a level to become the child of the new
+    /// root at the specified index.
+    ///
+    /// # Returns
+    ///
+    /// The new root node.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChildIndexOutOfRange` if the index is not less than the max children per
+    /// node, the tree is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(2).set_child_value(0, 3);
+    ///
+    /// tree.insert_root_pushing_down(1, 1).unwrap();
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.value(), &1);
+    /// assert_eq!(root.child(0), None);
+    /// assert_eq!(root.child(1).and_then(|n| n.child(0)).map(|n| *n.value()), Some(3));
+    /// ```
+    pub fn insert_root_pushing_down(
+        &mut self,
+        new_value: N,
+        index: usize,
+    ) -> Result<NodeMut<'_, N>, Error> {
+        let max_children_per_node = self.max_children_per_node();
+        if index >= max_children_per_node {
+            return Err(Error::ChildIndexOutOfRange {
+                child_offset: index,
+                max_children_per_node,
+            });
+        }
+
+        let old_tree = self.split_off(0);
+        self.replace_value(0, new_value);
+        self.graft(self.child_index(0, index), old_tree);
+
+        Ok(NodeMut {
+            tree: self,
+            index: 0,
+        })
+    }
+
+    /// Removes the root value. This will also remove all children.
+    ///
+    /// # Returns
+    ///
+    /// The old root value if there was one.
+    pub fn remove_root_value(&mut self) -> (Option<N>, VacantEntryMut<'_, N>) {
+        self.bump_generation();
+        self.record_modified(None);
+        self.nodes.truncate(1);
+        self.len = 0;
+        let value = self.nodes.first_mut().and_then(Option::take);
+
+        (
+            value,
+            VacantEntryMut {
+                tree: self,
+                index: 0,
+            },
+        )
+    }
+
+    /// Gets the entry for the root node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(8);
+    /// assert!(tree.root_entry().node().is_none());
+    ///
+    /// tree.set_root_value(5);
+    /// assert_eq!(tree.root_entry().node().map(|n| *n.value()), Some(5));
+    /// ```
+    pub fn root_entry(&self) -> Entry<'_, N> {
+        self.entry(0)
+    }
+
+    /// Gets the mutable entry for the root node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = {
+    ///     let mut tree = EytzingerTree::<u32>::new(8);
+    ///     tree.root_entry_mut().or_insert(5);
+    ///     tree
+    /// };
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.value(), &5);
+    /// ```
+    pub fn root_entry_mut(&mut self) -> EntryMut<'_, N> {
+        self.entry_mut(0)
+    }
+
+    /// Gets the mutable root node, inserting the value if there was no root node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.root_or_insert(5).set_child_value(0, 3);
+    ///
+    /// assert_eq!(tree.root_or_insert(7).value(), &5);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn root_or_insert(&mut self, value: N) -> NodeMut<'_, N> {
+        self.root_entry_mut().or_insert(value)
+    }
+
+    /// Gets the mutable root node, inserting the value returned by `value_factory` if there was
+    /// no root node.
+    pub fn root_or_insert_with<F>(&mut self, value_factory: F) -> NodeMut<'_, N>
+    where
+        F: FnOnce() -> N,
+    {
+        self.root_entry_mut().or_insert_with(value_factory)
+    }
+
+    /// Gets the mutable root node, inserting the default value if there was no root node.
+    pub fn root_or_default(&mut self) -> NodeMut<'_, N>
+    where
+        N: Default,
+    {
+        self.root_or_insert_with(N::default)
+    }
+
+    /// Sets the value of the node at the path, the parent of the node must already exist.
+    ///
+    /// # Returns
+    ///
+    /// The new mutable node.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChildIndexOutOfRange` if a child offset of the path is not less than the max
+    /// children per node, `Error::DepthOverflow` if the path is too deep to be stored and
+    /// `Error::OrphanInsert` if the parent of the node does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_value_at_path(&NodePath::root(), 1).unwrap();
+    /// tree.set_value_at_path(&NodePath::from(vec![1]), 2).unwrap();
+    ///
+    /// let orphan = NodePath::from(vec![0, 0]);
+    /// assert_eq!(
+    ///     tree.set_value_at_path(&orphan, 3).unwrap_err(),
+    ///     Error::OrphanInsert { path: orphan }
+    /// );
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn set_value_at_path(
+        &mut self,
+        path: &NodePath,
+        new_value: N,
+    ) -> Result<NodeMut<'_, N>, Error> {
+        let index = self.checked_insert_index(path)?;
+
+        Ok(self.set_value(index, new_value))
+    }
+
+    /// Moves the node at the source path, along with all of its descendants, to the destination
+    /// path. The subtree is moved directly within the storage one level at a time, no other tree
+    /// is built.
+    ///
+    /// # Returns
+    ///
+    /// The subtree which was replaced at the destination, this is empty if there was no node.
+    ///
+    /// # Errors
+    ///
+    /// The tree is left unchanged on error.
+    ///
+    /// * `Error::MissingNode` if there is no node at the source path.
+    /// * `Error::OverlappingSubtrees` if either path is below the other, moving a subtree to its
+    ///   own path does nothing.
+    /// * `Error::OrphanInsert` if the parent of the destination does not exist.
+    /// * `Error::ChildIndexOutOfRange` if a child offset of either path is not less than the max
+    ///   children per node.
+    /// * `Error::DepthOverflow` if a node of the subtree would be too deep to be stored at the
+    ///   destination.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 3);
+    ///     root.set_child_value(1, 4);
+    /// }
+    ///
+    /// let source = NodePath::from(vec![0]);
+    /// let destination = NodePath::from(vec![1, 0]);
+    /// assert!(tree.relocate(&source, &destination).unwrap().is_empty());
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 4, 2, 3]);
+    ///
+    /// assert_eq!(
+    ///     tree.relocate(&NodePath::from(vec![1]), &NodePath::from(vec![1, 0, 0])),
+    ///     Err(Error::OverlappingSubtrees {
+    ///         source: NodePath::from(vec![1]),
+    ///         destination: NodePath::from(vec![1, 0, 0]),
+    ///     })
+    /// );
+    /// ```
+    pub fn relocate(
+        &mut self,
+        source: &NodePath,
+        destination: &NodePath,
+    ) -> Result<EytzingerTree<N>, Error> {
+        let source_index = self.checked_path_index(source)?;
+        if self.node(source_index).is_none() {
+            return Err(Error::MissingNode {
+                path: source.clone(),
+            });
+        }
+        let destination_index = self.checked_path_index(destination)?;
+        if source_index == destination_index {
+            return Ok(Self::with_index_calculator(self.index_calculator));
+        }
+        if self
+            .index_calculator
+            .is_ancestor(source_index, destination_index)
+            || self
+                .index_calculator
+                .is_ancestor(destination_index, source_index)
+        {
+            return Err(Error::OverlappingSubtrees {
+                source: source.clone(),
+                destination: destination.clone(),
+            });
+        }
+        self.checked_insert_index(destination)?;
+
+        // pair each level of the subtree with the same level below the destination, checking
+        // every node will fit before anything is moved
+        let mut levels = vec![];
+        let mut destination_levels = self
+            .index_calculator
+            .descendant_level_ranges(destination_index..destination_index + 1, usize::MAX);
+        for (depth, source_level) in self
+            .index_calculator
+            .descendant_level_ranges(source_index..source_index + 1, self.nodes.len())
+            .enumerate()
+        {
+            let destination_level = destination_levels.next();
+            // there are no orphans so nothing is stored below an empty level
+            let last_offset = match self.nodes[source_level.clone()]
+                .iter()
+                .rposition(Option::is_some)
+            {
+                Some(last_offset) => last_offset,
+                None => break,
+            };
+
+            let destination_start = destination_level
+                .filter(|level| level.start.checked_add(last_offset).is_some())
+                .ok_or(Error::DepthOverflow {
+                    depth: destination.depth() + depth,
+                })?
+                .start;
+            levels.push((source_level, destination_start));
+        }
+
+        let replaced = self.split_off(destination_index);
+        self.record_modified(self.parent_index(source_index));
+        for (source_level, destination_start) in levels {
+            for offset in 0..source_level.len() {
+                if let Some(value) = self.nodes[source_level.start + offset].take() {
+                    self.len -= 1;
+                    self.replace_value(destination_start + offset, value);
+                }
+            }
+        }
+
+        Ok(replaced)
+    }
+
+    /// Descends from the root, repeatedly asking `f` which child of the current node to move to.
+    /// The descent stops when `f` returns `None` or the chosen child does not exist.
+    ///
+    /// # Returns
+    ///
+    /// The node the descent stopped at and its path, or `None` if the tree is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a child offset which is not less than the max children per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(5);
+    ///     root.set_child_value(0, 3).set_child_value(1, 4);
+    ///     root.set_child_value(1, 8);
+    /// }
+    ///
+    /// let (node, path) = tree
+    ///     .descend_while(|value| match 4.cmp(value) {
+    ///         std::cmp::Ordering::Less => Some(0),
+    ///         std::cmp::Ordering::Equal => None,
+    ///         std::cmp::Ordering::Greater => Some(1),
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(node.value(), &4);
+    /// assert_eq!(path, NodePath::from(vec![0, 1]));
+    /// ```
+    pub fn descend_while<F>(&self, mut f: F) -> Option<(Node<'_, N>, NodePath)>
+    where
+        F: FnMut(&N) -> Option<usize>,
+    {
+        let mut node = self.root()?;
+        let mut child_offsets = vec![];
+
+        while let Some(child_offset) = f(node.value()) {
+            match node.child(child_offset) {
+                Some(child) => {
+                    node = child;
+                    child_offsets.push(child_offset);
+                }
+                None => break,
+            }
+        }
+
+        Some((node, NodePath::from(child_offsets)))
+    }
+
+    /// Descends from the root, repeatedly asking `f` which child of the current node to move to,
+    /// as with `descend_while`. The descent stops when `f` returns `None`, resulting in an
+    /// occupied entry, or when the chosen child does not exist, resulting in a vacant entry which
+    /// may be inserted into.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a child offset which is not less than the max children per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{entry_mut::EntryMut, EytzingerTree};
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// for value in vec![5, 3, 8, 4, 3] {
+    ///     let entry = tree.descend_while_mut(|current| match value.cmp(current) {
+    ///         Ordering::Less => Some(0),
+    ///         Ordering::Equal => None,
+    ///         Ordering::Greater => Some(1),
+    ///     });
+    ///
+    ///     if let EntryMut::Vacant(entry) = entry {
+    ///         entry.insert(value);
+    ///     }
+    /// }
+    ///
+    /// let values: Vec<_> = tree.in_order_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![3, 4, 5, 8]);
+    /// ```
+    pub fn descend_while_mut<F>(&mut self, mut f: F) -> EntryMut<'_, N>
+    where
+        F: FnMut(&N) -> Option<usize>,
+    {
+        let mut index = 0;
+
+        while let Some(Some(value)) = self.nodes.get(index) {
+            match f(value) {
+                Some(child_offset) => index = self.child_index(index, child_offset),
+                None => break,
+            }
+        }
+
+        self.entry_mut(index)
+    }
+
+    /// Resolves a reference taken with `Node::node_ref` back to its node.
+    ///
+    /// # Returns
+    ///
+    /// The node or `None` if any node has been set, replaced or removed since the reference was
+    /// taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// let child_ref = tree.set_root_value(5).set_child_value(1, 3).node_ref();
+    ///
+    /// assert_eq!(tree.resolve(child_ref).map(|n| *n.value()), Some(3));
+    ///
+    /// tree.root_mut().unwrap().set_child_value(0, 4);
+    /// assert!(tree.resolve(child_ref).is_none());
+    /// ```
+    pub fn resolve(&self, node_ref: NodeRef) -> Option<Node<'_, N>> {
+        if !self.is_current(node_ref) {
+            return None;
+        }
+
+        self.node(node_ref.id)
+    }
+
+    /// Resolves a reference taken with `Node::node_ref` back to its mutable node.
+    ///
+    /// # Returns
+    ///
+    /// The node or `None` if any node has been set, replaced or removed since the reference was
+    /// taken.
+    pub fn resolve_mut(&mut self, node_ref: NodeRef) -> Option<NodeMut<'_, N>> {
+        if !self.is_current(node_ref) {
+            return None;
+        }
+
+        self.node_mut(node_ref.id).ok()
+    }
+
+    /// Resolves a reference taken with `Node::node_ref` back to its node, see `resolve`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::StaleNodeId` if any node has been set, replaced or removed since the
+    /// reference was taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// let root_ref = tree.set_root_value(5).node_ref();
+    ///
+    /// assert_eq!(tree.try_resolve(root_ref).map(|n| *n.value()), Ok(5));
+    ///
+    /// tree.set_root_value(6);
+    /// assert_eq!(tree.try_resolve(root_ref).map(|n| *n.value()), Err(Error::StaleNodeId));
+    /// ```
+    pub fn try_resolve(&self, node_ref: NodeRef) -> Result<Node<'_, N>, Error> {
+        self.resolve(node_ref).ok_or(Error::StaleNodeId)
+    }
+
+    /// Resolves a reference taken with `Node::node_ref` back to its mutable node, see
+    /// `resolve_mut`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::StaleNodeId` if any node has been set, replaced or removed since the
+    /// reference was taken.
+    pub fn try_resolve_mut(&mut self, node_ref: NodeRef) -> Result<NodeMut<'_, N>, Error> {
+        self.resolve_mut(node_ref).ok_or(Error::StaleNodeId)
+    }
+
+    /// Gets the storage for every node at the specified depth, from left to right. Vacant nodes
+    /// are `None`.
+    ///
+    /// The slice is empty if no storage exists at the depth and may be shorter than the full
+    /// level, the missing nodes at the end of the level are all vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(5);
+    ///     root.set_child_value(0, 2).set_child_value(1, 4);
+    ///     root.set_child_value(1, 7);
+    /// }
+    ///
+    /// assert_eq!(tree.values_at_depth(Depth(0)), &[Some(5)]);
+    /// assert_eq!(tree.values_at_depth(Depth(1)), &[Some(2), Some(7)]);
+    /// assert_eq!(tree.values_at_depth(Depth(2)), &[None, Some(4)]);
+    /// assert!(tree.values_at_depth(Depth(3)).is_empty());
+    /// ```
+    pub fn values_at_depth(&self, depth: Depth) -> &[Option<N>] {
+        let storage_len = self.nodes.len();
+
+        match self.index_calculator.level_indexes(depth.to_usize()) {
+            Some(level) => &self.nodes[level.start.min(storage_len)..level.end.min(storage_len)],
+            None => &[],
+        }
+    }
+
+    /// Gets the values of every level of the tree from the root down, see `values_at_depth`. Each
+    /// level is a contiguous slice of the storage, so it may be processed without visiting nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).extend_breadth_first(2..6);
+    ///
+    /// let sums: Vec<u32> = tree
+    ///     .level_slices()
+    ///     .map(|level| level.iter().flatten().sum())
+    ///     .collect();
+    /// assert_eq!(sums, vec![1, 5, 9]);
+    /// ```
+    pub fn level_slices(&self) -> impl Iterator<Item = &[Option<N>]> {
+        self.index_calculator
+            .level_ranges()
+            .map_while(move |level| self.nodes.get(level.start..level.end.min(self.nodes.len())))
+            .take_while(|level| !level.is_empty())
+    }
+
+    /// Gets an iterator over the entries of every position where a node may be inserted, these
+    /// are the vacant children of every node along with the root of an empty tree. The entries
+    /// are in breadth-first order, children too deep to be stored are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// assert_eq!(tree.vacant_frontier_iter().count(), 1);
+    ///
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// let frontier: Vec<_> = tree
+    ///     .vacant_frontier_iter()
+    ///     .map(|entry| entry.path().to_string())
+    ///     .collect();
+    /// assert_eq!(frontier, vec!["/0", "/1/0", "/1/1"]);
+    /// ```
+    pub fn vacant_frontier_iter(&self) -> impl Iterator<Item = VacantEntry<'_, N>> {
+        let max_children_per_node = self.max_children_per_node();
+        let index_calculator = self.index_calculator;
+        let vacant_root = self.root().is_none().then_some(0);
+
+        let vacant_children = self.enumerate_values().flat_map(move |(index, _)| {
+            (0..max_children_per_node)
+                .map_while(move |child_offset| {
+                    index_calculator.checked_child_index(index, child_offset)
+                })
+                .filter(move |&child_index| !matches!(self.nodes.get(child_index), Some(Some(_))))
+        });
+
+        vacant_root
+            .into_iter()
+            .chain(vacant_children)
+            .map(move |index| VacantEntry { tree: self, index })
+    }
+
+    /// Gets the children of every node which has any, grouped by their parent. The parents are
+    /// in breadth-first order and the children of each are in child offset order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(2, 2).set_child_value(1, 4);
+    ///     root.set_child_value(0, 3);
+    /// }
+    ///
+    /// let groups: Vec<(u32, Vec<u32>)> = tree
+    ///     .sibling_groups()
+    ///     .map(|(parent, children)| (*parent.value(), children.map(|n| *n.value()).collect()))
+    ///     .collect();
+    /// assert_eq!(groups, vec![(1, vec![3, 2]), (2, vec![4])]);
+    /// ```
+    pub fn sibling_groups(&self) -> impl Iterator<Item = (Node<'_, N>, NodeChildIter<'_, N>)> {
+        self.breadth_first_iter().filter_map(|parent| {
+            let children = parent.child_iter();
+            children.clone().next().map(|_| (parent, children))
+        })
+    }
+
+    /// Gets mutable views of the subtrees rooted at each node at the specified depth, from left
+    /// to right. The subtrees never overlap, so they may all be used at once.
+    pub fn subtrees_at_depth_mut(&mut self, depth: Depth) -> Vec<SubtreeMut<'_, N>> {
+        let storage_len = self.nodes.len();
+        let roots = match self.index_calculator.level_indexes(depth.to_usize()) {
+            Some(roots) if roots.start < storage_len => roots.start..roots.end.min(storage_len),
+            _ => return vec![],
+        };
+
+        self.record_subtree_modified(roots.clone());
+        SubtreeMut::split(&mut self.nodes, self.index_calculator, roots)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Calls `f` with each of the subtrees rooted at the specified depth, the subtrees are
+    /// processed in parallel on scoped threads. Nodes above the depth are not visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 3);
+    ///     root.set_child_value(1, 4);
+    /// }
+    ///
+    /// tree.par_for_each_subtree_mut(Depth(1), |mut subtree| {
+    ///     for value in subtree.values_mut() {
+    ///         *value *= 10;
+    ///     }
+    /// });
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 20, 40, 30]);
+    /// ```
+    pub fn par_for_each_subtree_mut<F>(&mut self, depth: Depth, f: F)
+    where
+        N: Send,
+        F: Fn(SubtreeMut<'_, N>) + Sync,
+    {
+        let subtrees = self.subtrees_at_depth_mut(depth);
+
+        let thread_count = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1)
+            .min(subtrees.len());
+
+        if thread_count <= 1 {
+            subtrees.into_iter().for_each(f);
+            return;
+        }
+
+        let mut batches: Vec<Vec<_>> = (0..thread_count).map(|_| vec![]).collect();
+        for (i, subtree) in subtrees.into_iter().enumerate() {
+            batches[i % thread_count].push(subtree);
+        }
+
+        let f = &f;
+        thread::scope(|scope| {
+            for batch in batches {
+                scope.spawn(move || batch.into_iter().for_each(f));
+            }
+        });
+    }
+
+    /// Gets a reference to every node whose value matches the predicate, in breadth-first order.
+    /// The storage is scanned directly rather than traversing the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 4);
+    ///
+    /// let even: Vec<_> = tree
+    ///     .find_all(|value| value % 2 == 0)
+    ///     .into_iter()
+    ///     .map(|node_ref| *tree.resolve(node_ref).unwrap().value())
+    ///     .collect();
+    /// assert_eq!(even, vec![2, 4]);
+    /// ```
+    pub fn find_all<P>(&self, mut pred: P) -> Vec<NodeRef>
+    where
+        P: FnMut(&N) -> bool,
+    {
+        self.enumerate_values()
+            .filter(|(_, value)| pred(value))
+            .map(|(index, _)| self.node_ref(index))
+            .collect()
+    }
+
+    /// Gets the same nodes as `find_all`, scanning the storage in parallel on scoped threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 4);
+    ///
+    /// assert_eq!(
+    ///     tree.par_find_all(|value| value % 2 == 0),
+    ///     tree.find_all(|value| value % 2 == 0)
+    /// );
+    /// ```
+    pub fn par_find_all<P>(&self, pred: P) -> Vec<NodeRef>
+    where
+        N: Sync,
+        P: Fn(&N) -> bool + Sync,
+    {
+        let thread_count = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1)
+            .min(self.nodes.len());
+
+        if thread_count <= 1 {
+            return self.find_all(pred);
+        }
+
+        let chunk_len = (self.nodes.len() + thread_count - 1) / thread_count;
+        let pred = &pred;
+        let indexes: Vec<usize> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .nodes
+                .chunks(chunk_len)
+                .enumerate()
+                .map(|(chunk, values)| {
+                    scope.spawn(move || {
+                        values
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, value)| value.as_ref().map_or(false, pred))
+                            .map(|(offset, _)| chunk * chunk_len + offset)
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("the predicate should not panic"))
+                .collect()
+        });
+
+        indexes
+            .into_iter()
+            .map(|index| self.node_ref(index))
+            .collect()
+    }
+
+    /// Cuts the tree at the specified depth.
+    ///
+    /// # Returns
+    ///
+    /// The nodes above the depth and the subtrees rooted at the depth, from left to right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 4);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let (top, subtrees) = tree.split_at_depth(Depth(1));
+    /// assert_eq!(top.len(), 1);
+    ///
+    /// let roots: Vec<_> = subtrees.iter().map(|t| *t.root().unwrap().value()).collect();
+    /// assert_eq!(roots, vec![2, 3]);
+    /// assert_eq!(subtrees[0].len(), 2);
+    /// ```
+    pub fn split_at_depth(mut self, depth: Depth) -> (Self, Vec<Self>) {
+        let roots = match self.index_calculator.level_indexes(depth.to_usize()) {
+            Some(roots) if roots.start < self.nodes.len() => roots,
+            _ => return (self, vec![]),
+        };
+
+        let mut subtrees = vec![];
+        for root in roots.clone() {
+            if self.node(root).is_some() {
+                subtrees.push(self.split_off(root));
+            }
+        }
+
+        self.nodes.truncate(roots.start);
+
+        (self, subtrees)
+    }
+
+    /// Consumes the tree, returning the subtree rooted at every node at the specified depth as a
+    /// detached tree from left to right. The nodes above the depth are dropped.
+    ///
+    /// The subtrees are all split off in a single pass over the levels below the depth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 4);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let forest: Vec<_> = tree.into_forest_at_depth(Depth(1)).collect();
+    ///
+    /// let roots: Vec<_> = forest.iter().map(|t| *t.root().unwrap().value()).collect();
+    /// assert_eq!(roots, vec![2, 3]);
+    /// assert_eq!(forest[0].root().unwrap().child(1).map(|n| *n.value()), Some(4));
+    /// ```
+    pub fn into_forest_at_depth(mut self, depth: Depth) -> impl ExactSizeIterator<Item = Self> {
+        let storage_len = self.nodes.len();
+        let mut subtrees = match self.index_calculator.level_indexes(depth.to_usize()) {
+            Some(roots) if roots.start < storage_len => {
+                self.detach_subtrees(roots.start..roots.end.min(storage_len))
+            }
+            _ => vec![],
+        };
+
+        subtrees.retain(|subtree| !subtree.is_empty());
+        subtrees.into_iter()
+    }
+
+    /// Splits the tree by the predicate, each node which does not satisfy it is removed along
+    /// with all of its descendants. The predicate is not called for descendants of removed nodes.
+    ///
+    /// # Returns
+    ///
+    /// The tree of the remaining nodes, which keep their positions, and the removed subtrees in
+    /// breadth-first order of their roots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 20).set_child_value(1, 3);
+    ///     root.set_child_value(1, 4).set_child_value(0, 50);
+    /// }
+    ///
+    /// let (kept, removed) = tree.partition(|value| *value < 10);
+    ///
+    /// let kept_values: Vec<_> = kept.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(kept_values, vec![1, 4]);
+    /// assert_eq!(kept.root().unwrap().child(1).map(|n| *n.value()), Some(4));
+    ///
+    /// let removed_roots: Vec<_> = removed.iter().map(|t| *t.root().unwrap().value()).collect();
+    /// assert_eq!(removed_roots, vec![20, 50]);
+    /// assert_eq!(removed[0].len(), 2);
+    /// ```
+    pub fn partition<F>(mut self, mut pred: F) -> (Self, Vec<Self>)
+    where
+        F: FnMut(&N) -> bool,
+    {
+        let mut removed = vec![];
+
+        // parents are stored before their children so removed subtrees are never visited again
+        for index in 0..self.nodes.len() {
+            if let Some(value) = &self.nodes[index] {
+                if !pred(value) {
+                    removed.push(self.split_off(index));
+                }
+            }
+        }
+
+        (self, removed)
+    }
+
+    /// Builds a new `EytzingerTree<N>` with the values mapped
+    /// using the specified selector.
+    ///
+    /// The storage keeps its capacity and is reused when `U` has the same size and alignment as
+    /// `N`, so only `map_in_place` avoids building a new tree.
+    pub fn map<U, F>(self, mut f: F) -> EytzingerTree<U>
+    where
+        F: FnMut(N) -> U,
+    {
+        let capacity = self.nodes.capacity();
+        let mut nodes: Vec<_> = self.nodes.into_iter().map(|n| n.map(&mut f)).collect();
+        nodes.reserve_exact(capacity.saturating_sub(nodes.len()));
+
+        EytzingerTree {
+            nodes,
+            index_calculator: self.index_calculator,
+            len: self.len,
+            tree_id: self.tree_id,
+            generation: self.generation,
+            versions: None,
+        }
+    }
+
+    /// Changes every value in place, without building a new tree. The values are visited in
+    /// breadth-first order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// tree.map_in_place(|value| *value *= 10);
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![10, 20]);
+    /// ```
+    pub fn map_in_place<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut N),
+    {
+        self.record_subtree_modified(0..1);
+        self.nodes.iter_mut().flatten().for_each(f);
+    }
+
+    /// Builds a new `EytzingerTree<U>` with the same shape where each value is mapped from a
+    /// reference to the value in this tree. The values are visited in breadth-first order.
+    pub fn clone_map_ref<U, F>(&self, mut f: F) -> EytzingerTree<U>
+    where
+        F: FnMut(&N) -> U,
+    {
+        let nodes = self.nodes.iter().map(|n| n.as_ref().map(&mut f)).collect();
+
+        EytzingerTree {
+            nodes,
+            index_calculator: self.index_calculator,
+            len: self.len,
+            tree_id: next_tree_id(),
+            generation: self.generation,
+            versions: None,
+        }
+    }
+
+    /// Duplicates the tree creating each value from a reference to the existing one, this does
+    /// not require the values to be `Clone`. The values are visited in breadth-first order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// struct Handle(u32);
+    ///
+    /// let mut tree = EytzingerTree::new(2);
+    /// tree.set_root_value(Handle(1)).set_child_value(1, Handle(2));
+    ///
+    /// let copy = tree.clone_with(|handle| Handle(handle.0 + 10));
+    /// let values: Vec<_> = copy.breadth_first_iter().map(|n| n.value().0).collect();
+    /// assert_eq!(values, vec![11, 12]);
+    /// ```
+    pub fn clone_with<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&N) -> N,
+    {
+        self.clone_map_ref(f)
+    }
+
+    /// Gets whether this tree has the same shape as `other`, with the same maximum number of
+    /// children per node, and `f` returns `true` for every pair of values at the same position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(4).set_child_value(1, 9);
+    ///
+    /// let mut expected = EytzingerTree::new(2);
+    /// expected.set_root_value("even").set_child_value(1, "odd");
+    ///
+    /// assert!(tree.eq_by(&expected, |value, parity| match *parity {
+    ///     "even" => value % 2 == 0,
+    ///     _ => value % 2 == 1,
+    /// }));
+    /// ```
+    pub fn eq_by<M, F>(&self, other: &EytzingerTree<M>, mut f: F) -> bool
+    where
+        F: FnMut(&N, &M) -> bool,
+    {
+        if self.index_calculator != other.index_calculator || self.len != other.len {
+            return false;
+        }
+
+        let mut other_values = other.enumerate_values();
+        self.enumerate_values()
+            .all(|(index, value)| match other_values.next() {
+                Some((other_index, other_value)) => index == other_index && f(value, other_value),
+                None => false,
+            })
+    }
+
+    /// Gets whether this tree has a node at the same path as every node of `other` with an equal
+    /// value and no other nodes. Unlike `==` the max children per node of the trees may differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// let mut wider = EytzingerTree::<u32>::new(3);
+    /// wider.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// assert!(tree.shape_value_eq(&wider));
+    /// ```
+    pub fn shape_value_eq<M>(&self, other: &EytzingerTree<M>) -> bool
+    where
+        N: PartialEq<M>,
+    {
+        // breadth-first order is the same for the same paths whatever the arity
+        self.len == other.len
+            && self
+                .breadth_first_iter()
+                .zip(other.breadth_first_iter())
+                .all(|(node, other_node)| {
+                    node.value() == other_node.value() && node.path() == other_node.path()
+                })
+    }
+
+    /// Creates a new tree with a node at each path which has a node in both this and the other
+    /// tree, holding clones of both values. The new tree has the max children per node of this
+    /// tree, the other tree may have any max children per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(1, 3).set_child_value(0, 4);
+    /// }
+    ///
+    /// let mut mask = EytzingerTree::<bool>::new(3);
+    /// {
+    ///     let mut root = mask.set_root_value(true);
+    ///     root.set_child_value(1, false).set_child_value(0, true);
+    ///     root.set_child_value(2, true);
+    /// }
+    ///
+    /// let intersection = tree.intersect(&mask);
+    /// let values: Vec<_> = intersection.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![(1, true), (3, false), (4, true)]);
+    /// ```
+    pub fn intersect<M>(&self, other: &EytzingerTree<M>) -> EytzingerTree<(N, M)>
+    where
+        N: Clone,
+        M: Clone,
+    {
+        let mut intersection = EytzingerTree::with_index_calculator(self.index_calculator);
+        let max_children_per_node =
+            cmp::min(self.max_children_per_node(), other.max_children_per_node());
+
+        // each pending position is the same path in both trees, so also in the intersection
+        let mut pending = VecDeque::new();
+        if let (Some(Some(_)), Some(Some(_))) = (self.nodes.first(), other.nodes.first()) {
+            pending.push_back((0, 0));
+        }
+        while let Some((index, other_index)) = pending.pop_front() {
+            let (node, other_node) = match (self.node(index), other.node(other_index)) {
+                (Some(node), Some(other_node)) => (node, other_node),
+                _ => continue,
+            };
+
+            for child_offset in 0..max_children_per_node {
+                if let (Some(child), Some(other_child)) =
+                    (node.child(child_offset), other_node.child(child_offset))
+                {
+                    pending.push_back((child.index, other_child.index));
+                }
+            }
+
+            intersection.replace_value(index, (node.value().clone(), other_node.value().clone()));
+        }
+
+        intersection
+    }
+
+    /// Combines this tree with the other, the new tree has a node at each path which has a node
+    /// in either tree. The values of paths with a node in both trees are combined with `f`.
+    ///
+    /// The new tree has the larger max children per node of the two trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node would be too deep to be stored with the larger max children per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    ///
+    /// let mut other = EytzingerTree::<u32>::new(3);
+    /// other.set_root_value(10).set_child_value(2, 30);
+    ///
+    /// let union = tree.union_with(other, |value, other_value| value + other_value);
+    /// assert_eq!(union.max_children_per_node(), 3);
+    ///
+    /// let values: Vec<_> = union.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![11, 2, 30]);
+    /// ```
+    pub fn union_with<F>(mut self, mut other: Self, mut f: F) -> Self
+    where
+        F: FnMut(N, N) -> N,
+    {
+        let index_calculator = if self.max_children_per_node() >= other.max_children_per_node() {
+            self.index_calculator
+        } else {
+            other.index_calculator
+        };
+        let mut union = Self::with_index_calculator(index_calculator);
+
+        // the position of a path in this tree, the other tree and the union
+        let mut pending = VecDeque::new();
+        let roots = (
+            matches!(self.nodes.first(), Some(Some(_))).then_some(0),
+            matches!(other.nodes.first(), Some(Some(_))).then_some(0),
+        );
+        if roots.0.is_some() || roots.1.is_some() {
+            pending.push_back((roots.0, roots.1, 0));
+        }
+        while let Some((index, other_index, union_index)) = pending.pop_front() {
+            let value = index.and_then(|index| self.nodes[index].take());
+            let other_value = other_index.and_then(|index| other.nodes[index].take());
+
+            for child_offset in 0..union.max_children_per_node() {
+                let child = index.and_then(|index| self.occupied_child_index(index, child_offset));
+                let other_child =
+                    other_index.and_then(|index| other.occupied_child_index(index, child_offset));
+
+                if child.is_some() || other_child.is_some() {
+                    let union_child = union
+                        .index_calculator
+                        .checked_child_index(union_index, child_offset)
+                        .expect("index overflow");
+                    pending.push_back((child, other_child, union_child));
+                }
+            }
+
+            let value = match (value, other_value) {
+                (Some(value), Some(other_value)) => f(value, other_value),
+                (Some(value), None) | (None, Some(value)) => value,
+                (None, None) => unreachable!("a pending position should have a node"),
+            };
+            union.replace_value(union_index, value);
+        }
+
+        union
+    }
+
+    /// Gets a view of this tree which is compared and hashed independently of the max children
+    /// per node, see `NormalizedTree`.
+    pub fn normalized(&self) -> NormalizedTree<'_, N> {
+        NormalizedTree::new(self)
+    }
+
+    /// Finds the subtrees which are identical to at least one other subtree, with equal values at
+    /// the same paths. Each group holds the roots of identical subtrees in breadth-first order and
+    /// the groups are ordered by their first root. The subtrees of duplicates are duplicates too,
+    /// so they are also reported.
+    ///
+    /// Each distinct subtree is given an id from its value and the ids of its children, so every
+    /// subtree is hashed and compared once regardless of its size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<char>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value('+');
+    ///     let mut left = root.set_child_value(0, '*');
+    ///     left.set_child_value(0, 'a');
+    ///     left.set_child_value(1, 'b');
+    ///     let mut right = root.set_child_value(1, '*');
+    ///     right.set_child_value(0, 'a');
+    ///     right.set_child_value(1, 'b');
+    /// }
+    ///
+    /// let values: Vec<Vec<String>> = tree
+    ///     .find_duplicate_subtrees()
+    ///     .into_iter()
+    ///     .map(|group| {
+    ///         group
+    ///             .into_iter()
+    ///             .map(|node_ref| tree.resolve(node_ref).unwrap().path().to_string())
+    ///             .collect()
+    ///     })
+    ///     .collect();
+    /// assert_eq!(
+    ///     values,
+    ///     vec![vec!["/0", "/1"], vec!["/0/0", "/1/0"], vec!["/0/1", "/1/1"]]
+    /// );
+    /// ```
+    pub fn find_duplicate_subtrees(&self) -> Vec<Vec<NodeRef>>
+    where
+        N: Hash + Eq,
+    {
+        let mut ids = vec![0; self.nodes.len()];
+        let mut canonical = HashMap::new();
+        for node in self.depth_first_iter(DepthFirstOrder::PostOrder) {
+            let children: Vec<(usize, usize)> = node
+                .child_iter()
+                .map(|child| {
+                    let child_offset = self
+                        .index_calculator
+                        .child_offset(child.index)
+                        .expect("a child has a parent");
+                    (child_offset, ids[child.index])
+                })
+                .collect();
+
+            let next_id = canonical.len();
+            ids[node.index] = *canonical.entry((node.value(), children)).or_insert(next_id);
+        }
+
+        let mut counts = vec![0; canonical.len()];
+        for (index, _) in self.enumerate_values() {
+            counts[ids[index]] += 1;
+        }
+
+        let mut group_of_id = vec![None; canonical.len()];
+        let mut groups: Vec<Vec<NodeRef>> = Vec::new();
+        for node in self.breadth_first_iter() {
+            let id = ids[node.index];
+            if counts[id] < 2 {
+                continue;
+            }
+
+            let group = *group_of_id[id].get_or_insert_with(|| {
+                groups.push(Vec::with_capacity(counts[id]));
+                groups.len() - 1
+            });
+            groups[group].push(self.node_ref(node.index));
+        }
+
+        groups
+    }
+
+    /// Compares this tree with `other` using `f` to compare values.
+    ///
+    /// Trees are first ordered by their maximum number of children per node, then the nodes are
+    /// compared pairwise in breadth-first order. Each pair is ordered by position in storage and
+    /// then by value and a tree which runs out of nodes first sorts before the other.
+    pub fn cmp_by<M, F>(&self, other: &EytzingerTree<M>, mut f: F) -> Ordering
+    where
+        F: FnMut(&N, &M) -> Ordering,
+    {
+        let arity_ordering = self
+            .max_children_per_node()
+            .cmp(&other.max_children_per_node());
+        if arity_ordering != Ordering::Equal {
+            return arity_ordering;
+        }
+
+        let mut values = self.enumerate_values();
+        let mut other_values = other.enumerate_values();
+        loop {
+            let ordering = match (values.next(), other_values.next()) {
+                (Some((index, value)), Some((other_index, other_value))) => {
+                    index.cmp(&other_index).then_with(|| f(value, other_value))
+                }
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => return Ordering::Equal,
+            };
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+
+    /// Builds a new tree with the same shape where each value is derived from the value of the
+    /// node and the new value of its parent, `init` is used in place of the parent of the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// // path lengths from edge weights
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(0);
+    ///     root.set_child_value(0, 3).set_child_value(1, 4);
+    ///     root.set_child_value(1, 1);
+    /// }
+    ///
+    /// let distances = tree.scan_down(0, |parent, weight| parent + weight);
+    /// let values: Vec<_> = distances.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![0, 3, 1, 7]);
+    /// ```
+    pub fn scan_down<A, F>(&self, init: A, mut f: F) -> EytzingerTree<A>
+    where
+        F: FnMut(&A, &N) -> A,
+    {
+        let mut nodes: Vec<Option<A>> = Vec::with_capacity(self.nodes.len());
+
+        // parents are always stored before their children so their new values already exist
+        for (index, value) in self.nodes.iter().enumerate() {
+            let new_value = value.as_ref().map(|value| {
+                let parent = match self.index_calculator.parent_index(index) {
+                    Some(parent_index) => nodes[parent_index]
+                        .as_ref()
+                        .expect("the parent of a node should exist"),
+                    None => &init,
+                };
+
+                f(parent, value)
+            });
+            nodes.push(new_value);
+        }
+
+        EytzingerTree {
+            nodes,
+            index_calculator: self.index_calculator,
+            len: self.len,
+            tree_id: next_tree_id(),
+            generation: self.generation,
+            versions: None,
+        }
+    }
+
+    /// Builds a new tree with the same shape where each value is derived from the value of the
+    /// node and the new values of its children, in child offset order with vacant children
+    /// skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(2, 3).set_child_value(1, 4);
+    /// }
+    ///
+    /// let sums = tree.fold_up(|value, children: &[u32]| value + children.iter().sum::<u32>());
+    /// let values: Vec<_> = sums.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![10, 2, 7, 4]);
+    /// ```
+    pub fn fold_up<U, F>(&self, mut f: F) -> EytzingerTree<U>
+    where
+        F: FnMut(&N, &[U]) -> U,
+    {
+        let mut nodes: Vec<Option<U>> = Vec::with_capacity(self.nodes.len());
+        nodes.resize_with(self.nodes.len(), || None);
+
+        let mut child_indexes = vec![];
+        let mut child_values = vec![];
+
+        // children are always stored after their parents so their new values already exist
+        for (index, value) in self.nodes.iter().enumerate().rev() {
+            if let Some(value) = value {
+                let children = self
+                    .index_calculator
+                    .stored_child_indexes(index, self.nodes.len());
+
+                child_indexes.clear();
+                child_indexes.extend(children.filter(|&child_index| nodes[child_index].is_some()));
+                child_values.extend(
+                    child_indexes
+                        .iter()
+                        .map(|&child_index| nodes[child_index].take().unwrap()),
+                );
+
+                nodes[index] = Some(f(value, &child_values));
+
+                for (&child_index, child_value) in child_indexes.iter().zip(child_values.drain(..))
+                {
+                    nodes[child_index] = Some(child_value);
+                }
+            }
+        }
+
+        EytzingerTree {
+            nodes,
+            index_calculator: self.index_calculator,
+            len: self.len,
+            tree_id: next_tree_id(),
+            generation: self.generation,
+            versions: None,
+        }
+    }
+
+    /// Builds a new tree with the same shape where each value is the pair of the results of
+    /// `scan_down` and `fold_up` for the node.
+    pub fn annotate<D, U, FD, FU>(&self, init: D, down: FD, up: FU) -> EytzingerTree<(D, U)>
+    where
+        FD: FnMut(&D, &N) -> D,
+        FU: FnMut(&N, &[U]) -> U,
+    {
+        let down_tree = self.scan_down(init, down);
+        let up_tree = self.fold_up(up);
+
+        let nodes = down_tree
+            .nodes
+            .into_iter()
+            .zip(up_tree.nodes)
+            .map(|(down_value, up_value)| down_value.zip(up_value))
+            .collect();
+
+        EytzingerTree {
+            nodes,
+            index_calculator: self.index_calculator,
             len: self.len,
+            tree_id: next_tree_id(),
+            generation: self.generation,
+            versions: None,
+        }
+    }
+
+    /// Calls `f` with each value in depth-first order along with the value of its parent, `None`
+    /// for the root. The value may be changed while reading the parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{traversal::DepthFirstOrder, EytzingerTree};
+    ///
+    /// // relative offsets to absolute positions
+    /// let mut tree = EytzingerTree::<i32>::new(2);
+    /// tree.set_root_value(10).set_child_value(0, 5).set_child_value(1, -2);
+    ///
+    /// tree.for_each_mut_with_parent(DepthFirstOrder::PreOrder, |parent, value| {
+    ///     *value += parent.cloned().unwrap_or(0);
+    /// });
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![10, 15, 13]);
+    /// ```
+    pub fn for_each_mut_with_parent<F>(&mut self, order: DepthFirstOrder, mut f: F)
+    where
+        F: FnMut(Option<&N>, &mut N),
+    {
+        let indexes: Vec<_> = self
+            .depth_first_iter(order)
+            .map(|node| node.index())
+            .collect();
+        self.record_modified(indexes.iter().copied());
+
+        for index in indexes {
+            // parents are always stored before their children
+            let (before, from_index) = self.nodes.split_at_mut(index);
+            let parent = self
+                .index_calculator
+                .parent_index(index)
+                .and_then(|parent_index| before[parent_index].as_ref());
+            let value = from_index[0]
+                .as_mut()
+                .expect("there should be a value at the index returned by the iterator");
+
+            f(parent, value);
+        }
+    }
+
+    /// Repeatedly applies the rewrite rules until none of them match anywhere in the tree.
+    ///
+    /// Each pass visits the nodes bottom-up, so the children of a node are rewritten before the
+    /// node itself, and the first matching rule replaces the node and its descendants. Rules which
+    /// always match again after being applied never finish.
+    ///
+    /// # Returns
+    ///
+    /// The number of rewrites applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rule builds a tree with a different `max_children_per_node`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{
+    ///     query::{Pattern, Rule},
+    ///     EytzingerTree,
+    /// };
+    ///
+    /// // (x + 0) + 0
+    /// let mut tree = EytzingerTree::new(2);
+    /// {
+    ///     let mut outer = tree.set_root_value("+");
+    ///     outer.set_child_value(1, "0");
+    ///     let mut inner = outer.set_child_value(0, "+");
+    ///     inner.set_child_value(0, "x");
+    ///     inner.set_child_value(1, "0");
+    /// }
+    ///
+    /// let add_zero = Rule::new(
+    ///     Pattern::value("+")
+    ///         .child(0, Pattern::any_value().bind("x"))
+    ///         .child(1, Pattern::value("0")),
+    ///     |m| m.get("x").unwrap().to_tree(),
+    /// );
+    ///
+    /// assert_eq!(tree.rewrite(&[add_zero]), 2);
+    /// assert_eq!(tree.root().map(|n| *n.value()), Some("x"));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn rewrite(&mut self, rules: &[Rule<N>]) -> usize
+    where
+        N: PartialEq,
+    {
+        let mut rewrites = 0;
+
+        loop {
+            let previous_rewrites = rewrites;
+
+            // children are always stored after their parents
+            for index in (0..self.nodes.len()).rev() {
+                let replacement = self
+                    .node(index)
+                    .and_then(|node| rules.iter().find_map(|rule| rule.apply(node)));
+
+                if let Some(replacement) = replacement {
+                    self.graft(index, replacement);
+                    rewrites += 1;
+                }
+            }
+
+            if rewrites == previous_rewrites {
+                return rewrites;
+            }
+        }
+    }
+
+    /// Shrinks the inner storage of the tree to only take up
+    /// as much space as required.
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.truncate(self.len())
+    }
+
+    /// Gets an iterator over each value and its index in the tree.
+    fn enumerate_values(&self) -> impl Iterator<Item = (usize, &N)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, o)| o.as_ref().map(|v| (i, v)))
+    }
+
+    fn set_child_value(&mut self, parent: usize, child: usize, new_value: N) -> NodeMut<'_, N> {
+        let child_index = self.child_index(parent, child);
+        self.set_value(child_index, new_value)
+    }
+
+    fn ensure_size(&mut self, index: usize) {
+        let desired_len = index.checked_add(1).expect("index overflow");
+
+        if let Some(additional) = desired_len.checked_sub(self.nodes.len()) {
+            // TODO LH Use resize_default once stable
+            self.nodes.reserve(additional);
+
+            for _ in 0..additional {
+                self.nodes.push(None);
+            }
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> Option<N> {
+        let old_value = self.nodes.get_mut(index)?.take()?;
+
+        self.bump_generation();
+        self.record_modified(self.parent_index(index));
+        self.len -= 1;
+
+        if index == 0 {
+            // the whole storage is the subtree, truncating only visits each slot if N needs dropping
+            self.nodes.truncate(1);
+            self.len = 0;
+            return Some(old_value);
+        }
+
+        let descendant_levels = self
+            .index_calculator
+            .descendant_level_ranges(index..index + 1, self.nodes.len())
+            .skip(1);
+        for level in descendant_levels {
+            let values = &mut self.nodes[level];
+            if mem::needs_drop::<N>() {
+                for value in values {
+                    if value.take().is_some() {
+                        self.len -= 1;
+                    }
+                }
+            } else {
+                // nothing needs dropping, so the slots are counted and then overwritten in bulk
+                self.len -= values.iter().filter(|value| value.is_some()).count();
+                values.fill_with(|| None);
+            }
+        }
+
+        Some(old_value)
+    }
+
+    fn split_off(&mut self, index: usize) -> EytzingerTree<N> {
+        let mut new_tree = EytzingerTree::with_index_calculator(self.index_calculator);
+        if self.node(index).is_none() {
+            return new_tree;
+        }
+
+        self.bump_generation();
+        self.record_modified(self.parent_index(index));
+
+        let source_levels = self
+            .index_calculator
+            .descendant_level_ranges(index..index + 1, self.nodes.len());
+        let mut target_levels = self.index_calculator.level_ranges();
+
+        for source_level in source_levels {
+            let target_level = target_levels.next().expect("index overflow");
+
+            for (offset, value) in self.nodes[source_level].iter_mut().enumerate() {
+                if let Some(value) = value.take() {
+                    self.len -= 1;
+                    new_tree.replace_value(target_level.start + offset, value);
+                }
+            }
+        }
+
+        new_tree
+    }
+
+    /// Splits off the subtree of every occupied child of the node at the index in a single pass
+    /// over the descendants.
+    fn detach_children(&mut self, index: usize) -> Vec<EytzingerTree<N>> {
+        let children = self
+            .index_calculator
+            .stored_child_indexes(index, self.nodes.len());
+        let mut subtrees = self.detach_subtrees(children);
+
+        if !subtrees.is_empty() {
+            self.bump_generation();
+            self.record_modified(Some(index));
+        }
+
+        subtrees.retain(|subtree| !subtree.is_empty());
+        subtrees
+    }
+
+    /// Splits off the subtree rooted at each of the consecutive indexes of a single level in a
+    /// single pass over the descendants, an empty tree is returned for each vacant root.
+    fn detach_subtrees(&mut self, roots: Range<usize>) -> Vec<EytzingerTree<N>> {
+        let mut subtrees: Vec<_> = roots
+            .clone()
+            .map(|_| EytzingerTree::with_index_calculator(self.index_calculator))
+            .collect();
+
+        let source_levels = self
+            .index_calculator
+            .descendant_level_ranges(roots, self.nodes.len());
+        let mut target_levels = self.index_calculator.level_ranges();
+
+        for source_level in source_levels {
+            // each level holds a block of the same size for every root
+            let target_level = target_levels.next().expect("index overflow");
+            let block_len = target_level.len();
+
+            for (offset, value) in self.nodes[source_level].iter_mut().enumerate() {
+                if let Some(value) = value.take() {
+                    self.len -= 1;
+                    subtrees[offset / block_len]
+                        .replace_value(target_level.start + offset % block_len, value);
+                }
+            }
         }
-    }
 
-    /// Shrinks the inner storage of the tree to only take up
-    /// as much space as required.
-    pub fn shrink_to_fit(&mut self) {
-        self.nodes.truncate(self.len())
+        subtrees
     }
 
-    /// Gets an iterator over each value and its index in the tree.
-    fn enumerate_values(&self) -> impl Iterator<Item = (usize, &N)> {
-        self.nodes
-            .iter()
-            .enumerate()
-            .flat_map(|(i, o)| o.as_ref().map(|v| (i, v)))
-    }
+    /// Replaces the binary subtree at the index, which must exist, with a perfectly balanced
+    /// search tree of its values.
+    fn rebalance_by<F>(&mut self, index: usize, mut cmp: F)
+    where
+        F: FnMut(&N, &N) -> Ordering,
+    {
+        assert_eq!(
+            self.max_children_per_node(),
+            2,
+            "rebalancing requires a binary tree"
+        );
 
-    fn set_child_value(&mut self, parent: usize, child: usize, new_value: N) -> NodeMut<N> {
-        let child_index = self.child_index(parent, child);
-        self.set_value(child_index, new_value)
+        let mut subtree = self.split_off(index);
+        let in_order: Vec<_> = subtree.in_order_iter().map(|n| n.index()).collect();
+        let mut values: Vec<_> = in_order
+            .into_iter()
+            .map(|index| subtree.nodes[index].take())
+            .collect();
+        values.sort_by(|a, b| match (a, b) {
+            (Some(a), Some(b)) => cmp(a, b),
+            _ => unreachable!("every collected value should exist"),
+        });
+
+        self.build_balanced(index, values);
     }
 
-    fn ensure_size(&mut self, index: usize) {
-        let desired_len = index.checked_add(1).expect("index overflow");
+    /// Places sorted values as a perfectly balanced binary subtree at the vacant index, the
+    /// middle value of each range becomes the root of the subtree for that range.
+    fn build_balanced(&mut self, index: usize, mut values: Vec<Option<N>>) {
+        if values.is_empty() {
+            return;
+        }
 
-        if let Some(additional) = desired_len.checked_sub(self.nodes.len()) {
-            // TODO LH Use resize_default once stable
-            self.nodes.reserve(additional);
+        let mut pending = vec![(index, 0..values.len())];
+        while let Some((index, range)) = pending.pop() {
+            let middle = range.start + range.len() / 2;
+            let value = values[middle].take().expect("each value is placed once");
+            self.replace_value(index, value);
 
-            for _ in 0..additional {
-                self.nodes.push(None);
+            for (child_offset, child_range) in
+                [(0, range.start..middle), (1, middle + 1..range.end)]
+            {
+                if !child_range.is_empty() {
+                    pending.push((self.child_index(index, child_offset), child_range));
+                }
             }
         }
     }
 
-    fn remove(&mut self, index: usize) -> Option<N> {
-        if index >= self.nodes.len() {
-            return None;
+    /// Moves every node to its index in the layout of the index calculator, which must have the
+    /// same max children per node.
+    fn relayout(mut self, index_calculator: EytzingerIndexCalculator) -> Self {
+        if self.index_calculator == index_calculator {
+            return self;
         }
 
-        let indices_to_remove: Vec<_> = self
-            .node(index)?
-            .depth_first_iter(DepthFirstOrder::PostOrder)
-            .skip(1)
-            .map(|n| n.index())
-            .collect();
+        let mut relaid = Self::with_index_calculator(index_calculator);
+        let mut pending = vec![(0, 0)];
+        while let Some((index, new_index)) = pending.pop() {
+            let value = match self.nodes.get_mut(index).and_then(Option::take) {
+                Some(value) => value,
+                None => continue,
+            };
 
-        for index_to_remove in indices_to_remove {
-            let removed_child_value = self.nodes[index_to_remove].take();
-            if removed_child_value.is_some() {
-                self.len -= 1
+            for child_offset in 0..self.max_children_per_node() {
+                if let Some(child_index) = self
+                    .index_calculator
+                    .checked_child_index(index, child_offset)
+                {
+                    pending.push((child_index, relaid.child_index(new_index, child_offset)));
+                }
             }
+            relaid.replace_value(new_index, value);
         }
 
-        let old_value = self.nodes[index].take();
+        relaid
+    }
+
+    /// Calls `f` with the index each node of this tree would have in the layout of the index
+    /// calculator, along with its value, parents before children.
+    fn for_each_reindexed<F>(
+        &self,
+        index_calculator: EytzingerIndexCalculator,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(usize, &N),
+    {
+        let max_children_per_node = index_calculator.max_children_per_node();
 
-        if old_value.is_some() {
-            self.len -= 1;
+        let mut pending = Vec::new();
+        if self.node(0).is_some() {
+            pending.push((0, 0));
         }
+        while let Some((index, new_index)) = pending.pop() {
+            f(
+                new_index,
+                self.nodes[index].as_ref().expect("the node should exist"),
+            );
 
-        old_value
-    }
+            for child_offset in 0..self.max_children_per_node() {
+                let child_index = self.child_index(index, child_offset);
+                if self.node(child_index).is_none() {
+                    continue;
+                }
+                if child_offset >= max_children_per_node {
+                    return Err(Error::ChildIndexOutOfRange {
+                        child_offset,
+                        max_children_per_node,
+                    });
+                }
 
-    fn split_off(&mut self, index: usize) -> EytzingerTree<N> {
-        let mut new_tree = EytzingerTree::new(self.max_children_per_node());
+                let new_child_index = index_calculator
+                    .checked_child_index(new_index, child_offset)
+                    .ok_or(Error::DepthOverflow {
+                        depth: index_calculator.depth(new_index) + 1,
+                    })?;
+                pending.push((child_index, new_child_index));
+            }
+        }
 
-        // get all of the indexes which should be moved out of the source tree
-        let indexes_to_move = self.node(index).map(|n| {
-            n.depth_first_iter(DepthFirstOrder::PreOrder)
-                .map(|n| n.index())
-                .collect::<Vec<_>>()
-        });
+        Ok(())
+    }
 
-        if let Some(indexes_to_move) = indexes_to_move {
-            let mut indexes_to_move_iter = indexes_to_move.into_iter();
+    /// Copies the node at the index and all of its descendants into a new tree.
+    fn clone_subtree(&self, index: usize) -> EytzingerTree<N>
+    where
+        N: Clone,
+    {
+        let mut subtree = EytzingerTree::with_index_calculator(self.index_calculator);
+        if self.node(index).is_none() {
+            return subtree;
+        }
 
-            if let Some(index_to_move) = indexes_to_move_iter.next() {
-                let new_root_value = self.nodes[index_to_move]
-                    .take()
-                    .expect("there should be a value at the index returned by the iterator");
+        let source_levels = self
+            .index_calculator
+            .descendant_level_ranges(index..index + 1, self.nodes.len());
+        let mut target_levels = self.index_calculator.level_ranges();
 
-                self.len -= 1;
+        for source_level in source_levels {
+            let target_level = target_levels.next().expect("index overflow");
 
-                let mut new_node = new_tree.set_root_value(new_root_value);
+            for (offset, value) in self.nodes[source_level].iter().enumerate() {
+                if let Some(value) = value {
+                    subtree.replace_value(target_level.start + offset, value.clone());
+                }
+            }
+        }
 
-                // this is used to determine if we need to move up a level
-                let mut previous_parent = self.parent_index(index_to_move);
+        subtree
+    }
 
-                for index_to_move in indexes_to_move_iter {
-                    let value_to_move = self.nodes[index_to_move]
-                        .take()
-                        .expect("there should be a value at the index returned by the iterator");
+    /// Replaces the node at the index and all of its descendants with the nodes of the subtree.
+    /// The parent of the index must exist unless the subtree is empty.
+    fn graft(&mut self, index: usize, subtree: EytzingerTree<N>) {
+        assert_eq!(
+            self.max_children_per_node(),
+            subtree.max_children_per_node(),
+            "the subtree should have the same max_children_per_node"
+        );
+        let subtree = subtree.relayout(self.index_calculator);
 
-                    self.len -= 1;
+        self.remove(index);
 
-                    let current_parent = self
-                        .parent_index(index_to_move)
-                        .expect("the root should only ever be the first node in the iterator");
-
-                    if let Some(mut previous_parent) = previous_parent {
-                        while current_parent <= previous_parent {
-                            new_node = new_node.to_parent().ok().expect(
-                                "the root should only ever be the first node in the iterator",
-                            );
-                            previous_parent = self.parent_index(previous_parent).unwrap();
-                        }
-                    }
+        let source_len = subtree.nodes.len();
+        let mut source_values = subtree.nodes.into_iter();
+        let mut target_levels = self
+            .index_calculator
+            .descendant_level_ranges(index..index + 1, usize::MAX);
 
-                    previous_parent = Some(current_parent);
+        for source_level in self.index_calculator.level_ranges() {
+            if source_level.start >= source_len {
+                break;
+            }
+            let target_level = target_levels.next().expect("index overflow");
 
-                    let child_offset = index_to_move - self.child_index(current_parent, 0);
-                    new_node = new_node
-                        .to_child_entry(child_offset)
-                        .or_insert(value_to_move);
+            for offset in 0..source_level.len() {
+                match source_values.next() {
+                    Some(Some(value)) => {
+                        self.replace_value(target_level.start + offset, value);
+                    }
+                    Some(None) => {}
+                    None => break,
                 }
             }
         }
+    }
 
-        new_tree
+    fn set_value(&mut self, index: usize, new_value: N) -> NodeMut<'_, N> {
+        self.replace_value(index, new_value);
+
+        NodeMut { tree: self, index }
     }
 
-    fn set_value(&mut self, index: usize, new_value: N) -> NodeMut<N> {
+    fn replace_value(&mut self, index: usize, new_value: N) -> Option<N> {
         self.ensure_size(index);
+        self.bump_generation();
+        self.record_modified(Some(index));
 
-        let old_value = mem::replace(&mut self.nodes[index], Some(new_value));
+        let old_value = self.nodes[index].replace(new_value);
 
         if old_value.is_none() {
             self.len += 1;
         }
 
-        NodeMut { tree: self, index }
+        old_value
+    }
+
+    /// Records the nodes at the indexes as modified by a single mutation, if versioning is enabled.
+    fn record_modified<I>(&mut self, indexes: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        if let Some(versions) = &mut self.versions {
+            versions.record(indexes);
+        }
+    }
+
+    /// Records every node in the subtrees of the roots as modified by a single mutation.
+    fn record_subtree_modified(&mut self, roots: Range<usize>) {
+        if let Some(versions) = &mut self.versions {
+            let nodes = &self.nodes;
+            let levels = self
+                .index_calculator
+                .descendant_level_ranges(roots, nodes.len());
+
+            versions.record(
+                levels
+                    .flatten()
+                    .filter(|&index| nodes.get(index).map_or(false, Option::is_some)),
+            );
+        }
+    }
+
+    /// Invalidates every `NodeRef` taken so far, this must be called whenever a node is set,
+    /// replaced or removed.
+    fn bump_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Gets the index of the first child of the parent without a node, if any can be stored.
+    fn first_vacant_child_index(&self, parent_index: usize) -> Option<usize> {
+        (0..self.max_children_per_node())
+            .map_while(|child_offset| {
+                self.index_calculator
+                    .checked_child_index(parent_index, child_offset)
+            })
+            .find(|&child_index| !matches!(self.nodes.get(child_index), Some(Some(_))))
+    }
+
+    /// Gets the index of the child at the offset if there is a node there, the offset may be
+    /// beyond the max children per node.
+    fn occupied_child_index(&self, parent_index: usize, child_offset: usize) -> Option<usize> {
+        if child_offset >= self.max_children_per_node() {
+            return None;
+        }
+
+        self.index_calculator
+            .checked_child_index(parent_index, child_offset)
+            .filter(|&child_index| matches!(self.nodes.get(child_index), Some(Some(_))))
     }
 
     fn child_index(&self, parent_index: usize, child_offset: usize) -> usize {
@@ -325,7 +3452,96 @@ impl<N> EytzingerTree<N> {
         self.index_calculator.child_indexes(parent_index)
     }
 
-    fn node(&self, index: usize) -> Option<Node<N>> {
+    fn path(&self, index: usize) -> NodePath {
+        NodePath::from(self.index_calculator.child_offsets(index))
+    }
+
+    fn path_index(&self, path: &NodePath) -> Option<usize> {
+        path.child_offsets()
+            .iter()
+            .try_fold(0, |index, &child_offset| {
+                self.index_calculator
+                    .checked_child_index(index, child_offset)
+            })
+    }
+
+    fn checked_path_index(&self, path: &NodePath) -> Result<usize, Error> {
+        let max_children_per_node = self.max_children_per_node();
+
+        path.child_offsets()
+            .iter()
+            .enumerate()
+            .try_fold(0, |index, (depth, &child_offset)| {
+                if child_offset >= max_children_per_node {
+                    return Err(Error::ChildIndexOutOfRange {
+                        child_offset,
+                        max_children_per_node,
+                    });
+                }
+
+                self.index_calculator
+                    .checked_child_index(index, child_offset)
+                    .ok_or(Error::DepthOverflow { depth: depth + 1 })
+            })
+    }
+
+    /// Gets the index of the node at the path, checking that its parent exists so a value may be
+    /// set there.
+    fn checked_insert_index(&self, path: &NodePath) -> Result<usize, Error> {
+        let index = self.checked_path_index(path)?;
+
+        if let Some(parent_index) = self.parent_index(index) {
+            if self.node(parent_index).is_none() {
+                return Err(Error::OrphanInsert { path: path.clone() });
+            }
+        }
+
+        Ok(index)
+    }
+
+    fn node_ref(&self, index: usize) -> NodeRef {
+        NodeRef {
+            id: index,
+            tree_id: self.tree_id,
+            fingerprint: self.generation,
+        }
+    }
+
+    /// Gets whether the reference was taken from this tree since its last structural change. A
+    /// clone is a different tree, they may change independently and end up with the same
+    /// generation. Resolving a reference from another tree is a bug so it panics in debug
+    /// builds rather than silently reading whichever node happens to share its index.
+    fn is_current(&self, node_ref: NodeRef) -> bool {
+        debug_assert_eq!(
+            node_ref.tree_id, self.tree_id,
+            "the node reference was taken from a different tree"
+        );
+
+        node_ref.tree_id == self.tree_id && node_ref.fingerprint == self.generation
+    }
+
+    /// Folds the index of every node in the ranges of storage, in order, stopping as soon as the
+    /// closure breaks. Each level is a contiguous range so this is the breadth-first walk behind
+    /// the internal iteration of trees and nodes.
+    fn try_fold_occupied<B, R, I, F>(&self, ranges: I, init: B, mut f: F) -> ControlFlow<R, B>
+    where
+        I: IntoIterator<Item = Range<usize>>,
+        F: FnMut(B, usize) -> ControlFlow<R, B>,
+    {
+        let mut accumulator = init;
+        for range in ranges {
+            let start = range.start;
+            for (offset, value) in self.nodes[range].iter().enumerate() {
+                if value.is_some() {
+                    accumulator = f(accumulator, start + offset)?;
+                }
+            }
+        }
+
+        ControlFlow::Continue(accumulator)
+    }
+
+    fn node(&self, index: usize) -> Option<Node<'_, N>> {
         if let Some(Some(_)) = self.nodes.get(index) {
             Some(Node { tree: self, index })
         } else {
@@ -333,43 +3549,55 @@ impl<N> EytzingerTree<N> {
         }
     }
 
-    fn node_mut(&mut self, index: usize) -> Result<NodeMut<N>, &mut Self> {
+    fn node_mut(&mut self, index: usize) -> Result<NodeMut<'_, N>, &mut Self> {
         if let Some(Some(_)) = self.nodes.get_mut(index) {
-            Ok(NodeMut {
-                tree: self,
-                index: index,
-            })
+            Ok(NodeMut { tree: self, index })
         } else {
             Err(self)
         }
     }
 
-    fn entry(&mut self, index: usize) -> Entry<N> {
+    fn entry(&self, index: usize) -> Entry<'_, N> {
+        match self.node(index) {
+            Some(node) => Entry::Occupied(node),
+            None => Entry::Vacant(VacantEntry { tree: self, index }),
+        }
+    }
+
+    fn entry_mut(&mut self, index: usize) -> EntryMut<'_, N> {
         match self.node_mut(index) {
-            Ok(node) => Entry::Occupied(node),
-            Err(tree) => Entry::Vacant(VacantEntry { tree, index }),
+            Ok(node) => EntryMut::Occupied(node),
+            Err(tree) => EntryMut::Vacant(VacantEntryMut { tree, index }),
         }
     }
 
-    fn child_entry(&mut self, parent: usize, child: usize) -> Entry<N> {
+    fn child_entry(&self, parent: usize, child: usize) -> Entry<'_, N> {
         let child_index = self.child_index(parent, child);
         self.entry(child_index)
     }
 
+    fn child_entry_mut(&mut self, parent: usize, child: usize) -> EntryMut<'_, N> {
+        let child_index = self.child_index(parent, child);
+        self.entry_mut(child_index)
+    }
+
     fn value(&self, index: usize) -> Option<&Option<N>> {
         self.nodes.get(index)
     }
 
     fn value_mut(&mut self, index: usize) -> Option<&mut Option<N>> {
+        if let Some(Some(_)) = self.nodes.get(index) {
+            self.record_modified(Some(index));
+        }
         self.nodes.get_mut(index)
     }
 
-    fn parent(&self, child: usize) -> Option<Node<N>> {
+    fn parent(&self, child: usize) -> Option<Node<'_, N>> {
         let parent_index = self.parent_index(child)?;
         self.node(parent_index)
     }
 
-    fn parent_mut(&mut self, child: usize) -> Result<NodeMut<N>, &mut Self> {
+    fn parent_mut(&mut self, child: usize) -> Result<NodeMut<'_, N>, &mut Self> {
         if let Some(parent_index) = self.parent_index(child) {
             self.node_mut(parent_index)
         } else {
@@ -377,28 +3605,348 @@ impl<N> EytzingerTree<N> {
         }
     }
 
-    fn child(&self, parent: usize, child: usize) -> Option<Node<N>> {
+    fn child(&self, parent: usize, child: usize) -> Option<Node<'_, N>> {
         let child_index = self.child_index(parent, child);
         self.node(child_index)
     }
 
-    fn child_mut(&mut self, parent: usize, child: usize) -> Result<NodeMut<N>, &mut Self> {
+    fn child_mut(&mut self, parent: usize, child: usize) -> Result<NodeMut<'_, N>, &mut Self> {
         let child_index = self.child_index(parent, child);
         self.node_mut(child_index)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{DepthFirstOrder, EytzingerTree};
-    use matches::assert_matches;
+/// Gets a new id for a tree which is not derived from another, so node references taken from one
+/// tree can be told apart from those taken from another.
+fn next_tree_id() -> u64 {
+    static NEXT_TREE_ID: AtomicU64 = AtomicU64::new(0);
+
+    NEXT_TREE_ID.fetch_add(1, AtomicOrdering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entry_mut::EntryMut, AncestorPolicy, Depth, DepthFirstOrder, Error, EytzingerTree,
+        EytzingerTreeBuilder, NodePath,
+    };
+    use std::mem;
+
+    #[test]
+    #[should_panic]
+    fn new_panics_for_zero_arity() {
+        EytzingerTree::<u32>::new(0);
+    }
+
+    #[test]
+    fn option_tree_is_niche_optimized() {
+        assert_eq!(
+            mem::size_of::<Option<EytzingerTree<u32>>>(),
+            mem::size_of::<EytzingerTree<u32>>()
+        );
+    }
+
+    #[test]
+    fn values_at_depth_returns_whole_levels() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2);
+            root.set_child_value(2, 3).set_child_value(2, 4);
+        }
+
+        assert_eq!(tree.values_at_depth(Depth(1)), &[Some(2), None, Some(3)]);
+        assert_eq!(tree.values_at_depth(Depth(2)).len(), 9);
+        assert_eq!(tree.values_at_depth(Depth(2))[8], Some(4));
+        assert!(tree.values_at_depth(Depth(u32::MAX)).is_empty());
+
+        let mut chain = EytzingerTree::<u32>::new(1);
+        chain.set_root_value(1).set_child_value(0, 2);
+
+        assert_eq!(chain.values_at_depth(Depth(1)), &[Some(2)]);
+        assert!(chain.values_at_depth(Depth(u32::MAX)).is_empty());
+    }
+
+    #[test]
+    fn root_or_default_only_inserts_when_vacant() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+
+        *tree.root_or_default() += 2;
+        *tree.root_or_default() += 3;
+
+        assert_eq!(tree.root().map(|n| *n.value()), Some(5));
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn clone_map_ref_keeps_the_shape() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(2, 2).set_child_value(1, 3);
+        }
+
+        let mapped = tree.clone_map_ref(|value| value.to_string());
+
+        assert_eq!(mapped, tree.clone().map(|value| value.to_string()));
+        assert_eq!(tree.clone_with(|value| *value), tree);
+    }
+
+    #[test]
+    fn cmp_by_orders_by_shape_then_values() {
+        use std::cmp::Ordering;
+
+        let mut root_only = EytzingerTree::<u32>::new(2);
+        root_only.set_root_value(1);
+
+        let mut left = root_only.clone();
+        left.root_mut().unwrap().set_child_value(0, 1);
+
+        let mut right = root_only.clone();
+        right.root_mut().unwrap().set_child_value(1, 1);
+
+        let cmp = |a: &EytzingerTree<u32>, b: &EytzingerTree<u32>| a.cmp_by(b, |x, y| x.cmp(y));
+
+        assert_eq!(cmp(&left, &left.clone()), Ordering::Equal);
+        assert_eq!(cmp(&root_only, &left), Ordering::Less);
+        assert_eq!(cmp(&left, &right), Ordering::Less);
+        assert_eq!(cmp(&right, &left), Ordering::Greater);
+        assert_eq!(
+            cmp(&EytzingerTree::new(3), &EytzingerTree::new(2)),
+            Ordering::Greater
+        );
+
+        let mut bigger_root = left.clone();
+        bigger_root.set_root_value(2);
+        assert_eq!(cmp(&left, &bigger_root), Ordering::Less);
+    }
+
+    #[test]
+    fn eq_by_requires_the_same_shape() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(0, 2);
+
+        let mut other = EytzingerTree::<u32>::new(2);
+        other.set_root_value(1).set_child_value(1, 2);
+
+        assert!(!tree.eq_by(&other, |_, _| true));
+        assert!(tree.eq_by(&tree.clone(), |_, _| true));
+        assert!(!tree.eq_by(&tree.clone(), |_, _| false));
+    }
+
+    #[test]
+    fn remove_child_value_removes_every_descendant() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            let mut child = root.set_child_value(0, 2);
+            child.set_child_value(0, 3);
+            child.set_child_value(1, 4);
+        }
+
+        tree.root_mut().unwrap().remove_child_value(0);
+
+        assert_eq!(tree.len(), 1);
+        assert!(tree
+            .values_at_depth(Depth(2))
+            .iter()
+            .all(|value| value.is_none()));
+    }
+
+    #[test]
+    fn for_each_mut_with_parent_post_order_sees_original_parents() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+        }
+
+        let mut visited = vec![];
+        tree.for_each_mut_with_parent(DepthFirstOrder::PostOrder, |parent, value| {
+            visited.push(*value);
+            *value += parent.cloned().unwrap_or(100);
+        });
+
+        assert_eq!(visited, vec![3, 2, 1]);
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![101, 3, 5]);
+    }
+
+    #[test]
+    fn scan_down_starts_from_init() {
+        let mut tree = EytzingerTree::<&str>::new(3);
+        {
+            let mut root = tree.set_root_value("a");
+            root.set_child_value(2, "c").set_child_value(0, "d");
+            root.set_child_value(0, "b");
+        }
+
+        let paths = tree.scan_down(String::from(">"), |parent, value| {
+            format!("{}{}", parent, value)
+        });
+        let values: Vec<_> = paths
+            .breadth_first_iter()
+            .map(|n| n.value().clone())
+            .collect();
+
+        assert_eq!(values, vec![">a", ">ab", ">ac", ">acd"]);
+        assert_eq!(paths.len(), tree.len());
+    }
+
+    #[test]
+    fn annotate_pairs_depths_with_subtree_sizes() {
+        let mut tree = EytzingerTree::<char>::new(2);
+        {
+            let mut root = tree.set_root_value('a');
+            root.set_child_value(1, 'b').set_child_value(0, 'c');
+        }
+
+        let annotated = tree.annotate(
+            0,
+            |parent_depth, _| parent_depth + 1,
+            |_, children: &[usize]| 1 + children.iter().sum::<usize>(),
+        );
+        let values: Vec<_> = annotated.breadth_first_iter().map(|n| *n.value()).collect();
+
+        assert_eq!(values, vec![(1, 3), (2, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn fold_up_over_a_deep_chain() {
+        let mut tree = EytzingerTree::<u32>::new(1);
+        {
+            let mut node = tree.set_root_value(1);
+            for _ in 1..100_000 {
+                node = node.to_child_entry(0).or_insert(1);
+            }
+        }
+
+        let heights =
+            tree.fold_up(|_, children: &[u32]| 1 + children.first().cloned().unwrap_or(0));
+        assert_eq!(heights.root().map(|n| *n.value()), Some(100_000));
+    }
+
+    #[test]
+    fn split_at_depth_keeps_the_subtree_shapes() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            let mut child = root.set_child_value(2, 2);
+            child.set_child_value(1, 3).set_child_value(0, 4);
+        }
+        let expected_subtree = tree.root().unwrap().child(2).unwrap().to_tree();
+
+        let (top, subtrees) = tree.clone().split_at_depth(Depth(0));
+        assert!(top.is_empty());
+        assert_eq!(subtrees, vec![tree.clone()]);
+
+        let (top, subtrees) = tree.clone().split_at_depth(Depth(1));
+        assert_eq!(top.len(), 1);
+        assert_eq!(subtrees, vec![expected_subtree]);
+
+        let (top, subtrees) = tree.clone().split_at_depth(Depth(4));
+        assert_eq!(top, tree);
+        assert!(subtrees.is_empty());
+    }
+
+    fn chain(len: usize) -> EytzingerTree<usize> {
+        let mut tree = EytzingerTree::new(1);
+        {
+            let mut node = tree.set_root_value(0);
+            for value in 1..len {
+                node = node.to_child_entry(0).or_insert(value);
+            }
+        }
+        tree
+    }
+
+    const DEEP: usize = 100_000;
+
+    #[test]
+    fn deep_chain_iterators() {
+        let tree = chain(DEEP);
+
+        for order in [DepthFirstOrder::PreOrder, DepthFirstOrder::PostOrder] {
+            let iter = tree.depth_first_iter(order).with_stack_capacity(DEEP);
+            assert_eq!(iter.count(), DEEP);
+        }
+        assert_eq!(tree.breadth_first_iter().count(), DEEP);
+        assert_eq!(tree.clone().into_breadth_first_iterator().count(), DEEP);
+    }
+
+    #[test]
+    fn deep_chain_copies_and_comparisons() {
+        let tree = chain(DEEP);
+
+        let copy = tree.clone();
+        assert_eq!(copy, tree);
+        assert!(tree.eq_by(&copy.clone().map(|v| v as u64), |a, b| *a as u64 == *b));
+        assert_eq!(
+            tree.cmp_by(&copy, |a, b| a.cmp(b)),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(tree.root().unwrap().to_tree(), tree);
+        assert_eq!(tree.clone_with(|v| *v), tree);
+    }
+
+    #[test]
+    fn deep_chain_structural_changes() {
+        let mut tree = chain(DEEP);
+
+        let (top, subtrees) = tree
+            .clone()
+            .split_at_depth(Depth::try_from(DEEP / 2).unwrap());
+        assert_eq!(top.len(), DEEP / 2);
+        assert_eq!(subtrees[0].len(), DEEP / 2);
+
+        tree.root_mut().unwrap().remove_child_value(0);
+        assert_eq!(tree.len(), 1);
+        tree.clear();
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn deep_chain_traversals() {
+        let mut tree = chain(DEEP);
+
+        let depths = tree.scan_down(0, |parent, _| parent + 1);
+        assert_eq!(
+            depths.values_at_depth(Depth::try_from(DEEP - 1).unwrap()),
+            &[Some(DEEP)]
+        );
+
+        tree.for_each_mut_with_parent(DepthFirstOrder::PostOrder, |parent, value| {
+            *value = parent.map_or(0, |p| p + 1);
+        });
+        assert_eq!(
+            tree.values_at_depth(Depth::try_from(DEEP - 1).unwrap()),
+            &[Some(DEEP - 1)]
+        );
+
+        let deepest = tree.breadth_first_iter().last().unwrap();
+        assert_eq!(deepest.path().depth(), DEEP - 1);
+        assert!(format!("{:?}", deepest).starts_with("Node {"));
+    }
+
+    #[test]
+    fn deep_chain_conversions_and_queries() {
+        use crate::{boxed::BoxedNode, query::Pattern};
+
+        let tree = chain(DEEP);
+
+        let boxed = Option::<BoxedNode<usize>>::from(tree.clone()).unwrap();
+        assert_eq!(EytzingerTree::from(boxed), tree);
+
+        let leaf = Pattern::<usize>::any_value().child(0, Pattern::vacant());
+        assert_eq!(leaf.find_all(&tree).len(), 1);
+    }
 
     #[test]
     fn root_is_none_for_empty() {
         let mut tree = EytzingerTree::<u32>::new(2);
 
-        assert_matches!(tree.root(), None);
-        assert_matches!(tree.root_mut(), None);
+        assert!(tree.root().is_none());
+        assert!(tree.root_mut().is_none());
     }
 
     #[test]
@@ -416,10 +3964,10 @@ mod tests {
     fn depth_first_iter_returns_empty_for_empty_tree() {
         let tree = EytzingerTree::<u32>::new(2);
 
-        assert_matches!(
-            tree.depth_first_iter(DepthFirstOrder::PostOrder).next(),
-            None
-        )
+        assert!(tree
+            .depth_first_iter(DepthFirstOrder::PostOrder)
+            .next()
+            .is_none())
     }
 
     #[test]
@@ -517,7 +4065,7 @@ mod tests {
     fn breadth_first_iter_returns_empty_for_empty_tree() {
         let tree = EytzingerTree::<u32>::new(2);
 
-        assert_matches!(tree.breadth_first_iter().next(), None)
+        assert!(tree.breadth_first_iter().next().is_none())
     }
 
     #[test]
@@ -573,4 +4121,688 @@ mod tests {
 
         assert_eq!(breadth_first, vec![5, 2, 7, 1, 4, 8, 3]);
     }
+
+    #[test]
+    fn in_order_iter_returns_in_order() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(5);
+            {
+                let mut left = root.set_child_value(0, 2);
+
+                left.set_child_value(0, 1);
+                let mut left_right = left.set_child_value(1, 4);
+                left_right.set_child_value(0, 3);
+            }
+            {
+                let mut right = root.set_child_value(1, 7);
+                right.set_child_value(1, 8);
+            }
+        }
+
+        let in_order: Vec<_> = tree.in_order_iter().map(|n| *n.value()).collect();
+
+        assert_eq!(in_order, vec![1, 2, 3, 4, 5, 7, 8]);
+        assert!(tree.is_search_tree(Ord::cmp));
+        assert!(!tree.is_search_tree(|a, b| b.cmp(a)));
+    }
+
+    #[test]
+    fn is_search_tree_checks_every_ancestor() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(5);
+            // 6 is ordered relative to its parent but not to the root
+            root.set_child_value(0, 2).set_child_value(1, 6);
+        }
+
+        assert!(!tree.is_search_tree(Ord::cmp));
+        assert!(EytzingerTree::<u32>::new(2).is_search_tree(Ord::cmp));
+    }
+
+    #[test]
+    #[should_panic]
+    fn in_order_iter_panics_for_non_binary_trees() {
+        EytzingerTree::<u32>::new(3).in_order_iter();
+    }
+
+    #[test]
+    fn descend_while_stops_at_vacant_children() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        assert!(tree.descend_while(|_| Some(0)).is_none());
+
+        tree.set_root_value(1)
+            .set_child_value(2, 2)
+            .set_child_value(0, 3);
+
+        let (node, path) = tree
+            .descend_while(|value| Some((*value as usize + 1) % 3))
+            .unwrap();
+        assert_eq!(node.value(), &3);
+        assert_eq!(path, NodePath::from(vec![2, 0]));
+        assert_eq!(node.path(), path);
+
+        let (node, path) = tree.descend_while(|_| None).unwrap();
+        assert_eq!(node.value(), &1);
+        assert_eq!(path, NodePath::root());
+    }
+
+    #[test]
+    fn descend_while_mut_ends_in_an_entry() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        assert!(matches!(
+            tree.descend_while_mut(|_| Some(1)),
+            EntryMut::Vacant(_)
+        ));
+
+        tree.set_root_value(1).set_child_value(1, 2);
+
+        match tree.descend_while_mut(|_| Some(1)) {
+            EntryMut::Vacant(entry) => {
+                assert_eq!(entry.parent().map(|n| *n.value()), Some(2));
+                entry.insert(3);
+            }
+            EntryMut::Occupied(_) => panic!("the entry should be vacant"),
+        }
+
+        match tree.descend_while_mut(|value| (*value < 3).then_some(1)) {
+            EntryMut::Occupied(mut node) => *node.value_mut() = 4,
+            EntryMut::Vacant(_) => panic!("the entry should be occupied"),
+        }
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn set_value_at_path_reports_invalid_paths() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+
+        assert_eq!(
+            tree.set_value_at_path(&NodePath::from(vec![2]), 1)
+                .unwrap_err(),
+            Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2
+            }
+        );
+        assert_eq!(
+            tree.set_value_at_path(&NodePath::from(vec![1; 100]), 1)
+                .unwrap_err(),
+            Error::DepthOverflow { depth: 64 }
+        );
+        assert!(matches!(
+            tree.set_value_at_path(&NodePath::from(vec![0]), 1),
+            Err(Error::OrphanInsert { .. })
+        ));
+        assert!(tree.is_empty());
+
+        tree.set_value_at_path(&NodePath::root(), 1).unwrap();
+        let child = tree.set_value_at_path(&NodePath::from(vec![0]), 2).unwrap();
+        assert_eq!(child.as_node().path(), NodePath::from(vec![0]));
+    }
+
+    #[test]
+    fn partition_of_a_rejected_root_removes_everything() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        tree.set_root_value(0).set_child_value(2, 1);
+
+        let (kept, removed) = tree.clone().partition(|value| *value > 0);
+        assert!(kept.is_empty());
+        assert_eq!(removed, vec![tree.clone()]);
+
+        let (kept, removed) = tree.clone().partition(|_| true);
+        assert_eq!(kept, tree);
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn from_path_values_applies_the_ancestor_policy() {
+        let path_values = || {
+            vec![
+                (NodePath::from(vec![0]), 2),
+                (NodePath::from(vec![0, 1, 1]), 4),
+                (NodePath::root(), 1),
+                (NodePath::from(vec![0]), 3),
+            ]
+        };
+
+        assert_eq!(
+            EytzingerTree::from_path_values(2, path_values(), AncestorPolicy::Require).unwrap_err(),
+            Error::OrphanInsert {
+                path: NodePath::from(vec![0, 1, 1])
+            }
+        );
+
+        let discarded =
+            EytzingerTree::from_path_values(2, path_values(), AncestorPolicy::Discard).unwrap();
+        let values: Vec<_> = discarded.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![1, 3]);
+        assert_eq!(discarded.len(), 2);
+
+        let filled =
+            EytzingerTree::from_path_values_filling(2, path_values(), |path| path.depth() * 10)
+                .unwrap();
+        let values: Vec<_> = filled.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![1, 3, 20, 4]);
+
+        assert_eq!(
+            EytzingerTree::from_path_values(
+                2,
+                vec![(NodePath::from(vec![2]), 1)],
+                AncestorPolicy::Discard
+            )
+            .unwrap_err(),
+            Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2
+            }
+        );
+    }
+
+    #[test]
+    fn from_arity_and_root_value() {
+        let tree = EytzingerTree::from((4, 'a'));
+
+        assert_eq!(tree, EytzingerTree::with_root(4, 'a'));
+        assert_eq!(tree.max_children_per_node(), 4);
+        assert_eq!(tree.len(), 1);
+        assert_ne!(tree, EytzingerTree::singleton('a'));
+    }
+
+    #[test]
+    fn map_keeps_the_capacity() {
+        let mut tree = crate::EytzingerTreeBuilder::new().capacity(64).build();
+        tree.set_root_value(1u32).set_child_value(1, 2);
+
+        let mapped = tree.map(|value| u64::from(value) * 2);
+
+        assert!(mapped.capacity() >= 64);
+        assert_eq!(
+            mapped.root().and_then(|n| n.child(1)).map(|n| *n.value()),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn par_find_all_matches_find_all() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        tree.root_or_insert(0);
+        tree.root_mut().unwrap().extend_breadth_first(1..1_000);
+
+        let sequential = tree.find_all(|value| value % 7 == 0);
+        assert_eq!(sequential.len(), 143);
+        assert_eq!(tree.par_find_all(|value| value % 7 == 0), sequential);
+        assert!(tree.par_find_all(|_| false).is_empty());
+        assert!(EytzingerTree::<u32>::new(2)
+            .par_find_all(|_| true)
+            .is_empty());
+    }
+
+    #[test]
+    fn clearing_an_empty_tree() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.clear();
+        assert_eq!(tree.remove_root_value().0, None);
+
+        tree.set_root_value(1).set_child_value(1, 2);
+        tree.clear();
+        assert!(tree.is_empty());
+        assert!(tree.root().is_none());
+    }
+
+    #[test]
+    fn remove_counts_values_which_need_dropping() {
+        let mut copied = EytzingerTree::<u32>::new(2);
+        copied.set_root_value(0).extend_breadth_first(1..20);
+        let mut owned = copied.clone_map_ref(|value| value.to_string());
+
+        copied.root_mut().unwrap().remove_child_value(0);
+        owned.root_mut().unwrap().remove_child_value(0);
+        assert_eq!(copied.len(), copied.breadth_first_iter().count());
+        assert_eq!(owned.len(), copied.len());
+
+        owned.root_mut().unwrap().remove();
+        assert!(owned.is_empty());
+        assert!(owned.breadth_first_iter().next().is_none());
+    }
+
+    #[test]
+    fn shrink_arity_to_fit_keeps_the_order_of_children() {
+        let mut tree = EytzingerTree::<u32>::new(4);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(3, 2).set_child_value(2, 3);
+            root.set_child_value(1, 4)
+                .set_child_value(0, 5)
+                .set_child_value(3, 6);
+        }
+        assert_eq!(tree.min_arity(), 2);
+
+        tree.shrink_arity_to_fit();
+        assert_eq!(tree.max_children_per_node(), 2);
+        assert_eq!(tree.len(), 6);
+
+        let mut expected = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = expected.set_root_value(1);
+            root.set_child_value(0, 4)
+                .set_child_value(0, 5)
+                .set_child_value(0, 6);
+            root.set_child_value(1, 2).set_child_value(0, 3);
+        }
+        assert_eq!(tree, expected);
+
+        assert_eq!(tree.shrink_arity_to_fit(), 0);
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn project_drops_unmapped_and_colliding_children() {
+        let mut tree = EytzingerTree::<u32>::new(4);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+            root.set_child_value(1, 4).set_child_value(3, 5);
+            root.set_child_value(3, 6);
+        }
+
+        // quadrants 0 and 1 both map to the left, 3 to the right and 2 is dropped
+        let projected = tree.clone().project(2, |slot| match slot {
+            0 | 1 => Some(0),
+            3 => Some(1),
+            _ => None,
+        });
+
+        let mut expected = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = expected.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(0, 3);
+            root.set_child_value(1, 6);
+        }
+        assert_eq!(projected, expected);
+        assert_eq!(projected.len(), 4);
+
+        let identity = tree.clone().project(4, Some);
+        assert_eq!(identity, tree);
+    }
+
+    #[test]
+    #[should_panic(expected = "too deep to index")]
+    fn project_panics_when_a_wider_arity_overflows_the_index() {
+        let mut tree = EytzingerTree::<u32>::new(1);
+        {
+            let mut node = tree.set_root_value(0);
+            for value in 1..100 {
+                node = node.to_child_entry(0).or_insert(value);
+            }
+        }
+
+        tree.project(2, Some);
+    }
+
+    #[test]
+    #[should_panic]
+    fn project_panics_on_out_of_range_slot() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(1, 2);
+        tree.project(1, Some);
+    }
+
+    #[test]
+    fn debug_ignores_bookkeeping() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(1, 2);
+        let other = tree.clone();
+        tree.root_mut().unwrap().set_child_value(1, 2);
+
+        assert_eq!(format!("{:?}", tree), format!("{:?}", other));
+        assert_eq!(
+            format!("{:?}", other),
+            "EytzingerTree { nodes: [Some(1), None, Some(2)], max_children_per_node: 2, len: 2 }"
+        );
+    }
+
+    #[test]
+    fn clone_from_reuses_storage() {
+        let mut source = EytzingerTree::<String>::new(2);
+        source
+            .set_root_value("a".to_string())
+            .set_child_value(1, "b".to_string());
+
+        let mut target = EytzingerTree::<String>::new(3);
+        for child_offset in 0..3 {
+            target
+                .root_entry_mut()
+                .or_insert_with(String::new)
+                .set_child_value(child_offset, String::new());
+        }
+        let capacity = target.nodes.capacity();
+
+        target.clone_from(&source);
+        assert_eq!(target, source);
+        assert_eq!(target.nodes.capacity(), capacity);
+    }
+
+    #[test]
+    fn copy_from_reindexes_into_a_wider_layout() {
+        let mut source = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = source.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+            root.set_child_value(1, 4);
+        }
+
+        let mut target = EytzingerTree::<u32>::new(3);
+        target.enable_versioning();
+        target.set_root_value(9).set_child_value(2, 8);
+        let tick = target.current_tick().unwrap();
+
+        target.copy_from(&source).unwrap();
+        assert_eq!(target.len(), 4);
+        assert_eq!(target.nodes_modified_since(tick).count(), 4);
+
+        let mut expected = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = expected.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+            root.set_child_value(1, 4);
+        }
+        assert_eq!(target, expected);
+
+        source.copy_from(&target).unwrap();
+        assert_eq!(source.max_children_per_node(), 2);
+        assert_eq!(source.len(), 4);
+    }
+
+    #[test]
+    fn sibling_groups_skip_leaves() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        assert_eq!(tree.sibling_groups().count(), 0);
+
+        tree.set_root_value(1);
+        assert_eq!(tree.sibling_groups().count(), 0);
+
+        tree.root_mut().unwrap().extend_breadth_first(2..8);
+        let groups: Vec<_> = tree
+            .sibling_groups()
+            .map(|(parent, children)| (*parent.value(), children.count()))
+            .collect();
+        assert_eq!(groups, vec![(1, 2), (2, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn find_duplicate_subtrees_compares_shape_and_values() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        assert!(tree.find_duplicate_subtrees().is_empty());
+
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(0, 2);
+            root.set_child_value(1, 1).set_child_value(1, 2);
+            root.set_child_value(2, 3);
+        }
+        // the children 1 differ by the offset of their child, only the leaves 2 are identical
+        let groups = tree.find_duplicate_subtrees();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+        assert!(groups[0]
+            .iter()
+            .all(|&node_ref| *tree.resolve(node_ref).unwrap().value() == 2));
+    }
+
+    #[test]
+    fn insert_root_pushing_down_moves_every_level() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        tree.insert_root_pushing_down(1, 2).unwrap();
+        assert_eq!(tree.len(), 1);
+
+        tree.root_mut().unwrap().extend_breadth_first(2..8);
+        tree.insert_root_pushing_down(0, 2).unwrap();
+        assert_eq!(tree.len(), 8);
+
+        let paths: Vec<_> = tree
+            .breadth_first_iter()
+            .map(|n| (*n.value(), n.path().to_string()))
+            .collect();
+        assert_eq!(paths[1], (1, "/2".to_string()));
+        assert_eq!(paths[4], (4, "/2/2".to_string()));
+        assert_eq!(paths[7], (7, "/2/0/2".to_string()));
+
+        assert_eq!(
+            tree.insert_root_pushing_down(9, 3).unwrap_err(),
+            Error::ChildIndexOutOfRange {
+                child_offset: 3,
+                max_children_per_node: 3
+            }
+        );
+        assert_eq!(tree.len(), 8);
+    }
+
+    #[test]
+    fn relocate_replaces_the_destination_subtree() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            {
+                let mut left = root.set_child_value(0, 2);
+                left.set_child_value(0, 3).set_child_value(1, 4);
+                left.set_child_value(1, 5);
+            }
+            root.set_child_value(1, 6).set_child_value(0, 7);
+        }
+
+        let replaced = tree
+            .relocate(&NodePath::from(vec![0]), &NodePath::from(vec![1]))
+            .unwrap();
+        let replaced: Vec<_> = replaced.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(replaced, vec![6, 7]);
+        assert_eq!(tree.len(), 5);
+
+        let paths: Vec<_> = tree
+            .breadth_first_iter()
+            .map(|n| (*n.value(), n.path().to_string()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (1, "/".to_string()),
+                (2, "/1".to_string()),
+                (3, "/1/0".to_string()),
+                (5, "/1/1".to_string()),
+                (4, "/1/0/1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn relocate_errors_leave_the_tree_unchanged() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(0, 2);
+        let original = tree.clone();
+
+        let missing = NodePath::from(vec![1]);
+        assert_eq!(
+            tree.relocate(&missing, &NodePath::from(vec![0, 0])),
+            Err(Error::MissingNode { path: missing })
+        );
+
+        let orphan = NodePath::from(vec![1, 0]);
+        assert_eq!(
+            tree.relocate(&NodePath::from(vec![0]), &orphan),
+            Err(Error::OrphanInsert { path: orphan })
+        );
+
+        assert_eq!(
+            tree.relocate(&NodePath::from(vec![0]), &NodePath::root()),
+            Err(Error::OverlappingSubtrees {
+                source: NodePath::from(vec![0]),
+                destination: NodePath::root(),
+            })
+        );
+        assert_eq!(tree, original);
+
+        let path = NodePath::from(vec![0]);
+        assert!(tree.relocate(&path, &path).unwrap().is_empty());
+        assert_eq!(tree, original);
+    }
+
+    #[test]
+    fn union_with_and_intersect_of_empty_trees() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1)
+            .set_child_value(1, 2)
+            .set_child_value(0, 3);
+        let empty = EytzingerTree::<u32>::new(2);
+
+        assert!(tree.intersect(&empty).is_empty());
+        assert!(empty.intersect(&tree).is_empty());
+
+        let union = tree
+            .clone()
+            .union_with(empty.clone(), |_, _| unreachable!());
+        assert_eq!(union, tree);
+        let union = empty
+            .clone()
+            .union_with(tree.clone(), |_, _| unreachable!());
+        assert_eq!(union, tree);
+        assert!(empty
+            .clone()
+            .union_with(empty, |_, _| unreachable!())
+            .is_empty());
+
+        let intersection = tree.intersect(&tree);
+        assert_eq!(intersection.len(), 3);
+        assert!(intersection
+            .breadth_first_iter()
+            .all(|n| n.value().0 == n.value().1));
+    }
+
+    #[test]
+    fn from_sorted_iter_is_complete_and_in_order_for_every_len() {
+        for len in 0..40u32 {
+            let tree = EytzingerTree::from_sorted_iter(0..len);
+
+            assert_eq!(tree.len(), len as usize);
+            assert!(tree.is_dense());
+            assert!(tree.in_order_iter().map(|n| *n.value()).eq(0..len));
+        }
+    }
+
+    #[test]
+    fn search_finds_every_value_and_nothing_else() {
+        for len in 0..40u32 {
+            let tree = EytzingerTree::from_sorted_iter((0..len).map(|value| value * 2));
+
+            for value in 0..len * 2 {
+                let found = tree.search(&value).map(|n| *n.value());
+                assert_eq!(found, Some(value).filter(|value| value % 2 == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn search_descends_sparse_trees() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1)
+            .set_child_value(1, 2)
+            .set_child_value(1, 3)
+            .set_child_value(0, 3);
+
+        assert_eq!(tree.search(&3).map(|n| n.index()), Some(6));
+        assert!(tree.search(&0).is_none());
+        assert!(tree.search(&4).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "binary tree")]
+    fn search_panics_for_non_binary_trees() {
+        EytzingerTree::<u32>::new(3).search(&1);
+    }
+
+    #[test]
+    fn into_forest_at_depth_matches_split_at_depth() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2)
+                .set_child_value(2, 3)
+                .set_child_value(1, 4);
+            let mut right = root.set_child_value(2, 5);
+            right.set_child_value(0, 6);
+            right.set_child_value(1, 7).set_child_value(2, 8);
+        }
+
+        for depth in 0..5 {
+            let (_, expected) = tree.clone().split_at_depth(Depth(depth));
+            let forest: Vec<_> = tree.clone().into_forest_at_depth(Depth(depth)).collect();
+
+            assert_eq!(forest, expected);
+        }
+        assert_eq!(tree.clone().into_forest_at_depth(Depth(1)).len(), 2);
+        assert_eq!(tree.into_forest_at_depth(Depth(u32::MAX)).len(), 0);
+    }
+
+    #[test]
+    fn occupied_paths_are_exactly_the_contained_paths() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(2, 2).set_child_value(0, 3);
+            root.set_child_value(1, 4);
+        }
+        let paths: Vec<_> = tree.occupied_paths().collect();
+        assert_eq!(paths.len(), tree.len());
+
+        let rebuilt = EytzingerTree::from_path_values(
+            3,
+            paths.iter().map(|path| (path.clone(), 0)),
+            AncestorPolicy::Require,
+        )
+        .unwrap();
+        for index in 0..tree.nodes.len() {
+            let path = tree.path(index);
+            assert_eq!(tree.contains_path(&path), paths.contains(&path));
+            assert_eq!(rebuilt.contains_path(&path), tree.contains_path(&path));
+        }
+
+        assert!(!EytzingerTree::<u32>::new(3).contains_path(&NodePath::from(vec![])));
+        assert!(!tree.contains_path(&NodePath::from(vec![0; 200])));
+    }
+
+    #[test]
+    fn into_iter_is_breadth_first_for_every_kind_of_tree() {
+        let mut tree: EytzingerTree<u32> = vec![(4, 4), (0, 0), (1, 1), (2, 2), (4, 40)]
+            .into_iter()
+            .collect();
+        assert_eq!(tree.len(), 4);
+
+        for value in &mut tree {
+            *value += 1;
+        }
+        let nodes: Vec<_> = (&tree).into_iter().map(|n| *n.value()).collect();
+        assert_eq!(nodes, vec![1, 2, 3, 41]);
+        assert_eq!((&mut tree).into_iter().len(), 4);
+
+        tree.extend(vec![(3, 30)]);
+        let values: Vec<_> = tree.into_iter().collect();
+        assert_eq!(values, vec![1, 2, 3, 30, 41]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the parent of the node at index 3 should exist")]
+    fn extend_panics_for_orphans() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.extend(vec![(0, 0), (3, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the index 4 should be the position of a child")]
+    fn extend_panics_for_padding() {
+        let mut tree = EytzingerTreeBuilder::new()
+            .max_children_per_node(3)
+            .pad_arity(true)
+            .build::<u32>();
+        tree.extend(vec![(0, 0), (4, 4)]);
+    }
 }
@@ -0,0 +1,256 @@
+//! Inference over models stored in Eytzinger trees.
+
+use crate::{Error, EytzingerTree};
+
+/// A node of a `DecisionTree`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DecisionNode<T, O> {
+    /// An internal node which continues to its second child when the feature is greater than the
+    /// threshold and to its first child otherwise.
+    Split {
+        /// The position of the feature which is compared.
+        feature: usize,
+        /// The threshold the feature is compared against.
+        threshold: T,
+    },
+
+    /// A leaf node holding the output of the prediction.
+    Leaf(O),
+}
+
+/// A binary decision tree stored in an Eytzinger tree.
+///
+/// Every split is known to have both of its branches, so a prediction is a descent which
+/// computes the index of the next node from the comparison without following any pointers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecisionTree<T, O> {
+    tree: EytzingerTree<DecisionNode<T, O>>,
+}
+
+impl<T, O> DecisionTree<T, O>
+where
+    T: PartialOrd,
+{
+    /// Creates a new decision tree from a binary Eytzinger tree.
+    ///
+    /// # Returns
+    ///
+    /// The decision tree, an `Error::ArityMismatch` if the tree is not binary or an
+    /// `Error::MissingBranch` if a split does not have both of its children. The children of
+    /// leaves are never visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{
+    ///     ml::{DecisionNode, DecisionTree},
+    ///     EytzingerTree,
+    /// };
+    ///
+    /// let mut tree = EytzingerTree::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(DecisionNode::Split {
+    ///         feature: 0,
+    ///         threshold: 2.5,
+    ///     });
+    ///     root.set_child_value(0, DecisionNode::Leaf("small"));
+    ///     root.set_child_value(
+    ///         1,
+    ///         DecisionNode::Split {
+    ///             feature: 1,
+    ///             threshold: 0.0,
+    ///         },
+    ///     )
+    ///     .set_child_value(0, DecisionNode::Leaf("large"));
+    /// }
+    /// assert!(DecisionTree::new(tree.clone()).is_err());
+    ///
+    /// tree.root_mut()
+    ///     .unwrap()
+    ///     .child_mut(1)
+    ///     .unwrap()
+    ///     .set_child_value(1, DecisionNode::Leaf("large and positive"));
+    /// let model = DecisionTree::new(tree).unwrap();
+    ///
+    /// assert_eq!(model.predict(&[1.0, 5.0]), Some(&"small"));
+    /// assert_eq!(model.predict(&[3.0, -1.0]), Some(&"large"));
+    /// assert_eq!(model.predict(&[3.0, 1.0]), Some(&"large and positive"));
+    /// ```
+    pub fn new(tree: EytzingerTree<DecisionNode<T, O>>) -> Result<Self, Error> {
+        if tree.max_children_per_node() != 2 {
+            return Err(Error::ArityMismatch {
+                expected: 2,
+                actual: tree.max_children_per_node(),
+            });
+        }
+
+        for (index, node) in tree.enumerate_values() {
+            if matches!(node, DecisionNode::Split { .. })
+                && (0..2).any(|child_offset| tree.child(index, child_offset).is_none())
+            {
+                return Err(Error::MissingBranch {
+                    path: tree.path(index),
+                });
+            }
+        }
+
+        Ok(Self { tree })
+    }
+
+    /// Gets the underlying Eytzinger tree.
+    pub fn tree(&self) -> &EytzingerTree<DecisionNode<T, O>> {
+        &self.tree
+    }
+
+    /// Converts this into the underlying Eytzinger tree.
+    pub fn into_tree(self) -> EytzingerTree<DecisionNode<T, O>> {
+        self.tree
+    }
+
+    /// Predicts the output for the features.
+    ///
+    /// # Returns
+    ///
+    /// The output of the leaf which is reached, or `None` if the tree is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a split which is visited compares a feature which is out of range.
+    pub fn predict(&self, features: &[T]) -> Option<&O> {
+        let mut index = 0;
+        loop {
+            match self.tree.nodes.get(index)?.as_ref()? {
+                DecisionNode::Split { feature, threshold } => {
+                    index = self.next_index(index, features, *feature, threshold);
+                }
+                DecisionNode::Leaf(output) => return Some(output),
+            }
+        }
+    }
+
+    /// Predicts the outputs for many rows of features, see `predict`.
+    ///
+    /// The rows descend the tree together one level at a time, so the loads of the nodes for
+    /// different rows are independent of each other and can be in flight at the same time
+    /// rather than each descent waiting on its own cache misses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{
+    ///     ml::{DecisionNode, DecisionTree},
+    ///     EytzingerTree,
+    /// };
+    ///
+    /// let mut tree = EytzingerTree::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(DecisionNode::Split {
+    ///         feature: 0,
+    ///         threshold: 10,
+    ///     });
+    ///     root.set_child_value(0, DecisionNode::Leaf('a'));
+    ///     root.set_child_value(1, DecisionNode::Leaf('b'));
+    /// }
+    /// let model = DecisionTree::new(tree).unwrap();
+    ///
+    /// let rows = [[3], [12], [10]];
+    /// assert_eq!(
+    ///     model.predict_batch(&rows),
+    ///     vec![Some(&'a'), Some(&'b'), Some(&'a')]
+    /// );
+    /// ```
+    pub fn predict_batch<R>(&self, rows: &[R]) -> Vec<Option<&O>>
+    where
+        R: AsRef<[T]>,
+    {
+        let mut outputs = vec![None; rows.len()];
+        if self.tree.is_empty() {
+            return outputs;
+        }
+
+        let mut indexes = vec![0; rows.len()];
+        let mut active: Vec<usize> = (0..rows.len()).collect();
+        while !active.is_empty() {
+            active.retain(|&row| {
+                let index = indexes[row];
+                match self.tree.nodes[index]
+                    .as_ref()
+                    .expect("the branches of every split should exist")
+                {
+                    DecisionNode::Split { feature, threshold } => {
+                        indexes[row] =
+                            self.next_index(index, rows[row].as_ref(), *feature, threshold);
+                        true
+                    }
+                    DecisionNode::Leaf(output) => {
+                        outputs[row] = Some(output);
+                        false
+                    }
+                }
+            });
+        }
+
+        outputs
+    }
+
+    fn next_index(&self, index: usize, features: &[T], feature: usize, threshold: &T) -> usize {
+        let child_offset = usize::from(features[feature] > *threshold);
+        self.tree.child_index(index, child_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NodePath;
+
+    fn stump() -> EytzingerTree<DecisionNode<i32, u8>> {
+        let mut tree = EytzingerTree::new(2);
+        {
+            let mut root = tree.set_root_value(DecisionNode::Split {
+                feature: 1,
+                threshold: 0,
+            });
+            root.set_child_value(0, DecisionNode::Leaf(0));
+            root.set_child_value(1, DecisionNode::Leaf(1));
+        }
+        tree
+    }
+
+    #[test]
+    fn new_rejects_invalid_trees() {
+        assert_eq!(
+            DecisionTree::new(EytzingerTree::<DecisionNode<i32, u8>>::new(3)),
+            Err(Error::ArityMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+
+        let mut tree = stump();
+        tree.root_mut().unwrap().remove_child_value(1);
+        assert_eq!(
+            DecisionTree::new(tree),
+            Err(Error::MissingBranch {
+                path: NodePath::root()
+            })
+        );
+    }
+
+    #[test]
+    fn predict_batch_matches_predict() {
+        let model = DecisionTree::new(stump()).unwrap();
+        let rows = [vec![0, -4], vec![0, 4], vec![9, 0]];
+
+        let expected: Vec<_> = rows.iter().map(|row| model.predict(row)).collect();
+        assert_eq!(model.predict_batch(&rows), expected);
+        assert_eq!(expected, vec![Some(&0), Some(&1), Some(&0)]);
+    }
+
+    #[test]
+    fn empty_tree_predicts_nothing() {
+        let model = DecisionTree::<i32, u8>::new(EytzingerTree::new(2)).unwrap();
+        assert_eq!(model.predict(&[1]), None);
+        assert_eq!(model.predict_batch(&[[1]]), vec![None]);
+    }
+}
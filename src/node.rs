@@ -1,11 +1,20 @@
 use crate::{
-    BreadthFirstIter, DepthFirstIter, DepthFirstOrder, EytzingerTree, NodeChildIter, NodeMut,
+    entry::{Entry, VacantEntry},
+    traversal::{BestFirstIter, Levels},
+    BreadthFirstIter, BreadthFirstRevIter, Depth, DepthFirstIter, DepthFirstOrder, EytzingerTree,
+    NodeChildIter, NodeMut, NodePath, NodeRef,
+};
+use std::{
+    array,
+    convert::Infallible,
+    fmt::{self, Debug, Display},
+    iter,
+    ops::{ControlFlow, Deref, Range},
 };
-use std::ops::Deref;
 
 /// Represents a borrowed node in the Eytzinger tree. This node may be used to navigate to parent or
 /// child nodes.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Node<'a, N>
 where
     N: 'a,
@@ -18,10 +27,27 @@ impl<'a, N> Copy for Node<'a, N> {}
 
 impl<'a, N> Clone for Node<'a, N> {
     fn clone(&self) -> Self {
-        Self {
-            tree: self.tree,
-            index: self.index,
-        }
+        *self
+    }
+}
+
+impl<'a, N> Debug for Node<'a, N>
+where
+    N: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let first_child_index = self.tree.child_index(self.index, 0);
+        let child_offsets: Vec<_> = self
+            .child_iter()
+            .map(|child| child.index - first_child_index)
+            .collect();
+
+        f.debug_struct("Node")
+            .field("index", &self.index)
+            .field("path", &format_args!("{}", self.path()))
+            .field("value", self.value())
+            .field("children", &child_offsets)
+            .finish()
     }
 }
 
@@ -46,10 +72,201 @@ impl<'a, N> Node<'a, N> {
         self.tree
     }
 
-    pub(crate) fn index(&self) -> usize {
+    /// Gets the index of this node in the storage of the tree, see `subtree_level_ranges` for how
+    /// nodes are laid out. The index is determined by the path of the node and the max children
+    /// per node, so it stays the same while the node is not moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let node = tree.root().and_then(|n| n.child(1)).and_then(|n| n.child(0)).unwrap();
+    /// assert_eq!(node.index(), 5);
+    /// assert_eq!(node.child_offset(), Some(0));
+    /// assert_eq!(tree.root().unwrap().child_offset(), None);
+    /// ```
+    pub fn index(&self) -> usize {
         self.index
     }
 
+    /// Gets the child offset of this node within its parent, or `None` for the root.
+    pub fn child_offset(&self) -> Option<usize> {
+        self.tree.index_calculator.child_offset(self.index)
+    }
+
+    /// Gets the depth of this node within the tree, the root has a depth of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let node = tree.root().and_then(|n| n.child(1)).and_then(|n| n.child(0)).unwrap();
+    /// assert_eq!(node.depth(), Depth(2));
+    /// assert_eq!(tree.root().unwrap().depth(), Depth::ROOT);
+    /// ```
+    pub fn depth(&self) -> Depth {
+        Depth::saturating_from_usize(self.tree.index_calculator.depth(self.index))
+    }
+
+    /// Gets an iterator over the levels of this node and its descendants from the top down, each
+    /// with its depth within the tree, see `Levels`.
+    pub fn levels(&self) -> Levels<'a, N> {
+        Levels::new(self.tree, Some(*self))
+    }
+
+    /// Copies this node and all of its descendants into a new tree with this node as the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let subtree = tree.root().and_then(|n| n.child(1)).unwrap().to_tree();
+    /// let values: Vec<_> = subtree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![2, 3]);
+    /// assert_eq!(subtree.root().and_then(|n| n.child(0)).map(|n| *n.value()), Some(3));
+    /// ```
+    pub fn to_tree(&self) -> EytzingerTree<N>
+    where
+        N: Clone,
+    {
+        self.tree.clone_subtree(self.index)
+    }
+
+    /// Gets the path from the root of the tree to this node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// tree.set_root_value(1).set_child_value(2, 2).set_child_value(0, 3);
+    ///
+    /// let node = tree.root().and_then(|n| n.child(2)).and_then(|n| n.child(0)).unwrap();
+    /// assert_eq!(node.path(), NodePath::from(vec![2, 0]));
+    /// assert_eq!(node.display_path().to_string(), "/2/0");
+    /// ```
+    pub fn path(&self) -> NodePath {
+        self.tree.path(self.index)
+    }
+
+    /// Gets an object which displays the path from the root of the tree to this node, such as
+    /// `/2/0`. The root is displayed as `/`.
+    pub fn display_path(&self) -> impl Display {
+        self.path()
+    }
+
+    /// Gets the range of storage indexes at each level of the subtree rooted at this node, starting
+    /// with the level containing only this node. The ranges stop at the end of the tree's
+    /// storage, so the last range may be shorter than the full level.
+    ///
+    /// Nodes are stored in breadth-first order with the root at index `0` and the children of the
+    /// node at index `i` at indexes `i * k + 1` to `i * k + k`, where `k` is
+    /// `max_children_per_node`. This layout is stable for a given `max_children_per_node`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let child = tree.root().and_then(|n| n.child(1)).unwrap();
+    /// let ranges: Vec<_> = child.subtree_level_ranges().collect();
+    /// assert_eq!(ranges, vec![2..3, 5..6]);
+    /// ```
+    pub fn subtree_level_ranges(&self) -> impl Iterator<Item = Range<usize>> {
+        self.tree
+            .index_calculator
+            .descendant_level_ranges(self.index..self.index + 1, self.tree.nodes.len())
+    }
+
+    /// Gets an iterator over the storage indexes of this node and every node below it in
+    /// breadth-first order. See `subtree_level_ranges` for how nodes are laid out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(1, 3).set_child_value(1, 4);
+    /// }
+    ///
+    /// let indexes: Vec<_> = tree.root().unwrap().subtree_index_iter().collect();
+    /// assert_eq!(indexes, vec![0, 1, 2, 6]);
+    /// ```
+    pub fn subtree_index_iter(&self) -> impl Iterator<Item = usize> + 'a {
+        let tree = self.tree;
+
+        self.subtree_level_ranges()
+            .flatten()
+            .filter(move |&index| tree.node(index).is_some())
+    }
+
+    /// Gets the number of nodes in the subtree rooted at this node, including this node.
+    ///
+    /// This is counted on demand from the storage taken by the subtree, without traversing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(1, 4);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.subtree_len(), 4);
+    /// assert_eq!(root.descendant_count(), 3);
+    /// assert_eq!(root.child(0).unwrap().descendant_count(), 1);
+    /// ```
+    pub fn subtree_len(&self) -> usize {
+        self.subtree_level_ranges()
+            .map(|level| self.tree.nodes[level].iter().flatten().count())
+            .sum()
+    }
+
+    /// Gets the number of nodes below this node, see `subtree_len`.
+    pub fn descendant_count(&self) -> usize {
+        self.subtree_len() - 1
+    }
+
+    /// Gets a reference to this node which does not borrow the tree, it may be resolved later
+    /// with `EytzingerTree::resolve`.
+    pub fn node_ref(&self) -> NodeRef {
+        self.tree.node_ref(self.index)
+    }
+
+    /// Gets the tick this node was last modified at or `None` if versioning is not enabled, see
+    /// `EytzingerTree::enable_versioning`.
+    pub fn last_modified(&self) -> Option<u64> {
+        self.tree
+            .versions
+            .as_ref()
+            .map(|versions| versions.last_modified(self.index))
+    }
+
     /// Gets the value stored at this node.
     ///
     /// # Examples
@@ -97,6 +314,35 @@ impl<'a, N> Node<'a, N> {
         self.tree.parent(self.index)
     }
 
+    /// Gets the child entry of this node at the specified index.
+    pub fn child_entry(&self, index: usize) -> Entry<'a, N> {
+        self.tree.child_entry(self.index, index)
+    }
+
+    /// Gets the entry of the first child of this node which does not exist, or `None` if every
+    /// child exists or the children would be too deep to be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    ///
+    /// let root = tree.root().unwrap();
+    /// let slot = root.first_vacant_child_slot().unwrap();
+    /// assert_eq!(slot.child_offset(), Some(1));
+    /// ```
+    pub fn first_vacant_child_slot(&self) -> Option<VacantEntry<'a, N>> {
+        let index = self.tree.first_vacant_child_index(self.index)?;
+
+        Some(VacantEntry {
+            tree: self.tree,
+            index,
+        })
+    }
+
     /// Gets the child of this node at the specified index or `None` if there wasn't one.
     ///
     /// # Examples
@@ -121,6 +367,72 @@ impl<'a, N> Node<'a, N> {
         self.tree.child(self.index, index)
     }
 
+    /// Gets an iterator over the values of every child slot of this node in child offset order,
+    /// there is one item per max children per node and it is `None` for a vacant child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// {
+    ///     let mut root = tree.set_root_value(5);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// let root = tree.root().unwrap();
+    /// let child_values: Vec<_> = root.child_values().collect();
+    /// assert_eq!(child_values, vec![None, Some(&3), None]);
+    /// ```
+    pub fn child_values(&self) -> impl Iterator<Item = Option<&'a N>> + 'a {
+        let tree = self.tree;
+        let children = tree
+            .index_calculator
+            .stored_child_indexes(self.index, tree.nodes.len());
+
+        tree.nodes[children]
+            .iter()
+            .map(Option::as_ref)
+            .chain(iter::repeat(None))
+            .take(tree.max_children_per_node())
+    }
+
+    /// Gets the values of every child slot of this node as an array so they can be matched on,
+    /// see `child_values`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is not the max children per node of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(5);
+    ///     root.set_child_value(0, 3);
+    ///     root.set_child_value(1, 8);
+    /// }
+    ///
+    /// match tree.root().unwrap().child_values_array() {
+    ///     [Some(left), Some(right)] => assert!(left < right),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn child_values_array<const K: usize>(&self) -> [Option<&'a N>; K] {
+        assert_eq!(
+            K,
+            self.tree.max_children_per_node(),
+            "the array length should be the max children per node"
+        );
+
+        let mut child_values = self.child_values();
+        array::from_fn(|_| child_values.next().flatten())
+    }
+
     /// Gets an iterator over the immediate children of this node. This only includes children
     /// for which there is a node.
     ///
@@ -153,10 +465,113 @@ impl<'a, N> Node<'a, N> {
         DepthFirstIter::new(self.tree(), Some(*self), order)
     }
 
+    /// Gets an iterator over this and all descendant nodes in ascending order of `key`, see
+    /// `BestFirstIter`.
+    pub fn best_first_iter<K, F>(&self, key: F) -> BestFirstIter<'a, N, K, F>
+    where
+        K: Ord,
+        F: FnMut(Node<'a, N>) -> K,
+    {
+        BestFirstIter::new(self.tree, Some(*self), key)
+    }
+
     /// Gets a breadth-first iterator over this and all child nodes.
     pub fn breadth_first_iter(&self) -> BreadthFirstIter<'a, N> {
         BreadthFirstIter::new(self.tree(), Some(*self))
     }
+
+    /// Gets a reverse breadth-first iterator over this and all child nodes, the deepest level is
+    /// returned first.
+    pub fn breadth_first_rev_iter(&self) -> BreadthFirstRevIter<'a, N> {
+        BreadthFirstRevIter::new(self.tree(), Some(*self))
+    }
+
+    /// Calls the closure on this and all descendant nodes in breadth-first order, stopping at the
+    /// first error.
+    ///
+    /// Unlike `breadth_first_iter` no iterator state is kept, the storage of each level of the
+    /// subtree is scanned in turn which is faster unless the subtree is very sparse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(1, 3).set_child_value(0, 4);
+    /// }
+    ///
+    /// let mut visited = vec![];
+    /// let result = tree.root().unwrap().try_for_each_breadth_first(|node| {
+    ///     if *node.value() > 3 {
+    ///         return Err(*node.value());
+    ///     }
+    ///     visited.push(*node.value());
+    ///     Ok(())
+    /// });
+    ///
+    /// assert_eq!(result, Err(4));
+    /// assert_eq!(visited, vec![1, 2, 3]);
+    /// ```
+    pub fn try_for_each_breadth_first<E, F>(&self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(Node<'a, N>) -> Result<(), E>,
+    {
+        let tree = self.tree;
+        let flow = tree.try_fold_occupied(self.subtree_level_ranges(), (), |(), index| {
+            match f(Node { tree, index }) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(error) => ControlFlow::Break(error),
+            }
+        });
+
+        match flow {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(error) => Err(error),
+        }
+    }
+
+    /// Calls the closure on this and all descendant nodes in breadth-first order, see
+    /// `try_for_each_breadth_first`.
+    pub fn for_each_breadth_first<F>(&self, mut f: F)
+    where
+        F: FnMut(Node<'a, N>),
+    {
+        self.fold_breadth_first((), |(), node| f(node));
+    }
+
+    /// Folds this and all descendant nodes into an accumulator in breadth-first order, see
+    /// `try_for_each_breadth_first`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// let child = tree.root().and_then(|n| n.child(1)).unwrap();
+    /// assert_eq!(child.fold_breadth_first(0, |sum, n| sum + n.value()), 5);
+    /// ```
+    pub fn fold_breadth_first<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Node<'a, N>) -> B,
+    {
+        let tree = self.tree;
+        let flow =
+            tree.try_fold_occupied(self.subtree_level_ranges(), init, |accumulator, index| {
+                ControlFlow::<Infallible, _>::Continue(f(accumulator, Node { tree, index }))
+            });
+
+        match flow {
+            ControlFlow::Continue(accumulator) => accumulator,
+            ControlFlow::Break(never) => match never {},
+        }
+    }
 }
 
 impl<'a, N> Deref for Node<'a, N> {
@@ -219,4 +634,57 @@ mod test {
 
         assert_eq!(child_breadth_first, vec![2, 1, 4, 3]);
     }
+
+    #[test]
+    fn subtree_index_iter_matches_breadth_first_iter() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(2, 2);
+            let mut right = root.set_child_value(2, 3);
+            right.set_child_value(0, 4).set_child_value(1, 5);
+        }
+
+        for node in tree.breadth_first_iter() {
+            let indexes: Vec<_> = node.subtree_index_iter().collect();
+            let expected: Vec<_> = node.breadth_first_iter().map(|n| n.index()).collect();
+            assert_eq!(indexes, expected);
+        }
+    }
+
+    #[test]
+    fn subtree_level_ranges_stop_at_the_end_of_storage() {
+        let mut chain = EytzingerTree::<u32>::new(1);
+        chain.set_root_value(0).set_child_value(0, 1);
+
+        let ranges: Vec<_> = chain.root().unwrap().subtree_level_ranges().collect();
+        assert_eq!(ranges, vec![0..1, 1..2]);
+
+        let mut tree = EytzingerTree::<u32>::new(4);
+        tree.set_root_value(0).set_child_value(1, 1);
+
+        let ranges: Vec<_> = tree.root().unwrap().subtree_level_ranges().collect();
+        assert_eq!(ranges, vec![0..1, 1..3]);
+    }
+
+    #[test]
+    fn debug_summarizes_the_node() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            let mut child = root.set_child_value(1, 2);
+            child.set_child_value(0, 3);
+            child.set_child_value(2, 4);
+        }
+
+        let child = tree.root().and_then(|n| n.child(1)).unwrap();
+        assert_eq!(
+            format!("{:?}", child),
+            "Node { index: 2, path: /1, value: 2, children: [0, 2] }"
+        );
+        assert_eq!(
+            format!("{:?}", tree.root_mut().unwrap()),
+            "Node { index: 0, path: /, value: 1, children: [1] }"
+        );
+    }
 }
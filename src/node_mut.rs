@@ -1,12 +1,19 @@
 use crate::{
-    entry::{Entry, VacantEntry},
-    BreadthFirstIter, DepthFirstIter, DepthFirstOrder, EytzingerTree, Node, NodeChildIter,
+    entry::Entry,
+    entry_mut::{EntryMut, VacantEntryMut},
+    BreadthFirstIter, Depth, DepthFirstIter, DepthFirstOrder, Error, EytzingerTree, Node,
+    NodeChildIter, NodeRef, SubtreeMut,
+};
+use std::{
+    array,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    iter,
+    ops::{Deref, DerefMut},
 };
-use std::ops::{Deref, DerefMut};
 
 /// Represents a borrowed node in the Eytzinger tree. This node may be used mutate this node's value
 /// and child nodes.
-#[derive(Debug)]
 pub struct NodeMut<'a, N>
 where
     N: 'a,
@@ -15,6 +22,15 @@ where
     pub(crate) index: usize,
 }
 
+impl<'a, N> Debug for NodeMut<'a, N>
+where
+    N: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_node().fmt(f)
+    }
+}
+
 impl<'a, N> NodeMut<'a, N> {
     /// Gets the Eytzinger tree this node is for.
     pub fn tree(&self) -> &EytzingerTree<N> {
@@ -77,7 +93,7 @@ impl<'a, N> NodeMut<'a, N> {
     }
 
     /// Gets the parent of this node or `None` is there was none.
-    pub fn parent(&self) -> Option<Node<N>> {
+    pub fn parent(&self) -> Option<Node<'_, N>> {
         self.as_node().parent()
     }
 
@@ -97,15 +113,116 @@ impl<'a, N> NodeMut<'a, N> {
     }
 
     /// Gets the child of this node at the specified index or `None` if there wasn't one.
-    pub fn child(&self, index: usize) -> Option<Node<N>> {
+    pub fn child(&self, index: usize) -> Option<Node<'_, N>> {
         self.as_node().child(index)
     }
 
     /// Gets the mutable child of this node at the specified index or `None` if there wasn't one.
-    pub fn child_mut(&mut self, index: usize) -> Option<NodeMut<N>> {
+    pub fn child_mut(&mut self, index: usize) -> Option<NodeMut<'_, N>> {
         self.tree.child_mut(self.index, index).ok()
     }
 
+    /// Gets an iterator over the values of every child slot of this node, see
+    /// `Node::child_values`.
+    pub fn child_values(&self) -> impl Iterator<Item = Option<&N>> + '_ {
+        self.as_node().child_values()
+    }
+
+    /// Gets an iterator over the mutable values of every child slot of this node in child offset
+    /// order, there is one item per max children per node and it is `None` for a vacant child.
+    pub fn child_values_mut(&mut self) -> impl Iterator<Item = Option<&mut N>> + '_ {
+        let max_children_per_node = self.tree.max_children_per_node();
+        let children = self
+            .tree
+            .index_calculator
+            .stored_child_indexes(self.index, self.tree.nodes.len());
+
+        let nodes = &self.tree.nodes;
+        let occupied: Vec<_> = children
+            .clone()
+            .filter(|&index| nodes[index].is_some())
+            .collect();
+        self.tree.record_modified(occupied);
+
+        self.tree.nodes[children]
+            .iter_mut()
+            .map(Option::as_mut)
+            .chain(iter::repeat_with(|| None))
+            .take(max_children_per_node)
+    }
+
+    /// Gets the mutable values of every child slot of this node as an array so they can be
+    /// matched on, see `child_values_mut`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is not the max children per node of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    /// use std::mem;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 3);
+    ///     root.set_child_value(1, 2);
+    ///
+    ///     if let [Some(left), Some(right)] = root.child_values_array_mut() {
+    ///         mem::swap(left, right);
+    ///     }
+    /// }
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn child_values_array_mut<const K: usize>(&mut self) -> [Option<&mut N>; K] {
+        assert_eq!(
+            K,
+            self.tree.max_children_per_node(),
+            "the array length should be the max children per node"
+        );
+
+        let mut child_values = self.child_values_mut();
+        array::from_fn(|_| child_values.next().flatten())
+    }
+
+    /// Gets mutable views of the subtrees of every child of this node at once, indexed by child
+    /// offset. The entry for a child is `None` if there wasn't one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    /// use std::mem;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(1, 3);
+    /// }
+    ///
+    /// {
+    ///     let mut root = tree.root_mut().unwrap();
+    ///     if let [Some(left), Some(right)] = &mut root.split_children_mut()[..] {
+    ///         mem::swap(left.value_mut(), right.value_mut());
+    ///     }
+    /// }
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 3, 2]);
+    /// ```
+    pub fn split_children_mut(&mut self) -> Vec<Option<SubtreeMut<'_, N>>> {
+        let children = self.tree.child_indexes(self.index);
+        let index_calculator = self.tree.index_calculator;
+        self.tree.record_subtree_modified(children.clone());
+
+        SubtreeMut::split(&mut self.tree.nodes, index_calculator, children)
+    }
+
     /// Gets the mutable child of this node at the specified index or `None` if there wasn't one.
     ///
     /// This differs from `child_mut` in that it takes ownership of the current node and is
@@ -126,32 +243,454 @@ impl<'a, N> NodeMut<'a, N> {
     /// # Returns
     ///
     /// The new mutable child.
-    pub fn set_child_value(&mut self, index: usize, new_value: N) -> NodeMut<N> {
+    pub fn set_child_value(&mut self, index: usize, new_value: N) -> NodeMut<'_, N> {
         self.tree.set_child_value(self.index, index, new_value)
     }
 
+    /// Replaces the child at the specified index, and all of its descendants, with the nodes of
+    /// the subtree. An empty subtree removes the child.
+    ///
+    /// # Returns
+    ///
+    /// The replaced child and its descendants as a tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ArityMismatch` if the subtree has a different max children per node,
+    /// `Error::ChildIndexOutOfRange` if the index is not less than the max children per node and
+    /// `Error::DepthOverflow` if the child is too deep to be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    ///
+    /// let mut subtree = EytzingerTree::<u32>::new(2);
+    /// subtree.set_root_value(3).set_child_value(1, 4);
+    ///
+    /// let mut root = tree.root_mut().unwrap();
+    /// let replaced = root.graft_child(0, subtree).unwrap();
+    /// assert_eq!(replaced.root().map(|n| *n.value()), Some(2));
+    /// assert_eq!(
+    ///     root.graft_child(1, EytzingerTree::new(3)).unwrap_err(),
+    ///     Error::ArityMismatch { expected: 2, actual: 3 }
+    /// );
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 3, 4]);
+    /// ```
+    pub fn graft_child(
+        &mut self,
+        index: usize,
+        subtree: EytzingerTree<N>,
+    ) -> Result<EytzingerTree<N>, Error> {
+        let max_children_per_node = self.tree.max_children_per_node();
+        if subtree.max_children_per_node() != max_children_per_node {
+            return Err(Error::ArityMismatch {
+                expected: max_children_per_node,
+                actual: subtree.max_children_per_node(),
+            });
+        }
+
+        let child_index = self.checked_child_index(index)?;
+        let replaced = self.tree.split_off(child_index);
+        self.tree.graft(child_index, subtree);
+
+        Ok(replaced)
+    }
+
+    /// Replaces the child at the specified index, and all of its descendants, with the nodes of
+    /// a subtree of any arity. Each child of the subtree is moved to the child offset given by
+    /// `slot_map` for its child offset in the subtree, children for which it returns `None` are
+    /// dropped along with their descendants, see `EytzingerTree::project`.
+    ///
+    /// # Returns
+    ///
+    /// The replaced child and its descendants as a tree.
+    ///
+    /// # Errors
+    ///
+    /// The tree is left unchanged on error.
+    ///
+    /// * `Error::SlotConflict` if two children of a node in the subtree map to the same child
+    ///   offset.
+    /// * `Error::ChildIndexOutOfRange` if the index, or a child offset returned by `slot_map` for
+    ///   a child of the subtree, is not less than the max children per node.
+    /// * `Error::DepthOverflow` if the child is too deep to be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Error, EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1);
+    ///
+    /// let mut quadtree = EytzingerTree::<u32>::new(4);
+    /// {
+    ///     let mut root = quadtree.set_root_value(2);
+    ///     root.set_child_value(0, 5);
+    ///     root.set_child_value(1, 3).set_child_value(3, 4);
+    /// }
+    ///
+    /// let mut root = tree.root_mut().unwrap();
+    /// assert_eq!(
+    ///     root.graft_child_projected(0, quadtree.clone(), |slot| Some(slot / 2)),
+    ///     Err(Error::SlotConflict {
+    ///         path: NodePath::root(),
+    ///         child_offset: 0
+    ///     })
+    /// );
+    /// assert_eq!(root.child_iter().count(), 0);
+    ///
+    /// // keep the eastern quadrants
+    /// root.graft_child_projected(0, quadtree, |slot| (slot % 2 == 1).then(|| slot / 2))
+    ///     .unwrap();
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3, 4]);
+    /// ```
+    pub fn graft_child_projected<F>(
+        &mut self,
+        index: usize,
+        subtree: EytzingerTree<N>,
+        slot_map: F,
+    ) -> Result<EytzingerTree<N>, Error>
+    where
+        F: FnMut(usize) -> Option<usize>,
+    {
+        let max_children_per_node = self.tree.max_children_per_node();
+        let slots: Vec<_> = (0..subtree.max_children_per_node()).map(slot_map).collect();
+
+        for node in subtree.breadth_first_iter() {
+            let mut used = vec![false; max_children_per_node];
+            for (slot, &child_offset) in slots.iter().enumerate() {
+                let child_offset = match child_offset {
+                    Some(child_offset) if node.child(slot).is_some() => child_offset,
+                    _ => continue,
+                };
+                match used.get_mut(child_offset) {
+                    Some(true) => {
+                        return Err(Error::SlotConflict {
+                            path: node.path(),
+                            child_offset,
+                        })
+                    }
+                    Some(used) => *used = true,
+                    None => {
+                        return Err(Error::ChildIndexOutOfRange {
+                            child_offset,
+                            max_children_per_node,
+                        })
+                    }
+                }
+            }
+        }
+        self.checked_child_index(index)?;
+
+        let projected = subtree.project(max_children_per_node, |slot| slots[slot]);
+        self.graft_child(index, projected)
+    }
+
+    /// Fills the vacant positions below this node with the values in breadth-first order, until
+    /// there are no more values. The values which are placed become the parents of the vacant
+    /// positions on the next level.
+    ///
+    /// # Returns
+    ///
+    /// The number of values which were placed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    ///
+    /// let placed = tree.root_mut().unwrap().extend_breadth_first(10..14);
+    /// assert_eq!(placed, 4);
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 10, 2, 11, 12, 13]);
+    /// ```
+    pub fn extend_breadth_first<I>(&mut self, values: I) -> usize
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut values = values.into_iter().peekable();
+        let mut placed = 0;
+
+        let levels = self
+            .tree
+            .index_calculator
+            .descendant_level_ranges(self.index..self.index + 1, usize::MAX)
+            .skip(1);
+        for level in levels {
+            if values.peek().is_none() {
+                break;
+            }
+
+            let mut has_parents = false;
+            for index in level {
+                let index_calculator = self.tree.index_calculator;
+                let parent_index = index_calculator
+                    .parent_index(index)
+                    .expect("a child has a parent");
+                let is_padding = index_calculator.child_offset(index)
+                    >= Some(index_calculator.max_children_per_node());
+                if is_padding || self.tree.node(parent_index).is_none() {
+                    continue;
+                }
+                has_parents = true;
+
+                if self.tree.node(index).is_none() {
+                    match values.next() {
+                        Some(value) => {
+                            self.tree.replace_value(index, value);
+                            placed += 1;
+                        }
+                        None => return placed,
+                    }
+                }
+            }
+
+            if !has_parents {
+                break;
+            }
+        }
+
+        placed
+    }
+
+    /// Removes every child of this node along with their descendants, keeping this node.
+    ///
+    /// # Returns
+    ///
+    /// The subtree of each child which was removed, in order of child index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(2, 4);
+    ///     root.set_child_value(2, 3);
+    /// }
+    ///
+    /// let children = tree.root_mut().unwrap().detach_children();
+    ///
+    /// assert_eq!(tree.len(), 1);
+    /// assert_eq!(children.len(), 2);
+    /// assert_eq!(children[0].root().map(|n| *n.value()), Some(2));
+    /// assert_eq!(
+    ///     children[0].root().and_then(|n| n.child(2)).map(|n| *n.value()),
+    ///     Some(4)
+    /// );
+    /// assert_eq!(children[1].len(), 1);
+    /// ```
+    pub fn detach_children(&mut self) -> Vec<EytzingerTree<N>> {
+        self.tree.detach_children(self.index)
+    }
+
+    /// Reorders the children of this node, along with their descendants, so the child at each
+    /// index is the one which was at `permutation[index]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidPermutation` if `permutation` does not contain every child index
+    /// exactly once, the children are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2).set_child_value(0, 4);
+    ///     root.set_child_value(2, 3);
+    /// }
+    ///
+    /// tree.root_mut().unwrap().permute_children(&[2, 1, 0]).unwrap();
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.child(0).map(|n| *n.value()), Some(3));
+    /// assert_eq!(root.child(1).map(|n| *n.value()), None);
+    /// assert_eq!(root.child(2).and_then(|n| n.child(0)).map(|n| *n.value()), Some(4));
+    /// ```
+    pub fn permute_children(&mut self, permutation: &[usize]) -> Result<(), Error> {
+        let max_children_per_node = self.tree.max_children_per_node();
+
+        let mut seen = vec![false; max_children_per_node];
+        if permutation.len() != max_children_per_node {
+            return Err(Error::InvalidPermutation);
+        }
+        for &child_index in permutation {
+            match seen.get_mut(child_index) {
+                Some(seen) if !*seen => *seen = true,
+                _ => return Err(Error::InvalidPermutation),
+            }
+        }
+
+        let mut children: Vec<_> = (0..max_children_per_node)
+            .map(|index| {
+                let child_index = self.tree.child_index(self.index, index);
+                Some(self.tree.split_off(child_index))
+            })
+            .collect();
+
+        for (index, &child_index) in permutation.iter().enumerate() {
+            let child = children[child_index]
+                .take()
+                .expect("each child should be moved once");
+            let child_index = self.tree.child_index(self.index, index);
+            self.tree.graft(child_index, child);
+        }
+
+        Ok(())
+    }
+
     /// Removes the child value at the specified child index. This will also remove all children of
     /// the specified child.
     ///
     /// # Returns
     ///
     /// The old child value if there was one.
-    pub fn remove_child_value(&mut self, index: usize) -> (Option<N>, VacantEntry<N>) {
-        self.child_entry(index).remove()
+    pub fn remove_child_value(&mut self, index: usize) -> (Option<N>, VacantEntryMut<'_, N>) {
+        self.child_entry_mut(index).remove()
     }
 
-    /// Gets the child entry of this node at the specified index. This node is not consumed in the
-    /// process so the child entry is lifetime bound to this node.
-    pub fn child_entry(&mut self, index: usize) -> Entry<N> {
-        self.tree.child_entry(self.index, index)
+    /// Sets the value of the child at the lowest child offset without a node, for trees where the
+    /// positions of children are not meaningful.
+    ///
+    /// # Returns
+    ///
+    /// The new mutable child, or the value if every child already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// let mut root = tree.set_root_value(1);
+    /// root.set_child_value(1, 2);
+    ///
+    /// assert!(root.push_child(3).is_ok());
+    /// assert_eq!(root.push_child(4).err(), Some(4));
+    /// assert_eq!(root.child(0).map(|n| *n.value()), Some(3));
+    /// ```
+    pub fn push_child(&mut self, value: N) -> Result<NodeMut<'_, N>, N> {
+        match (0..self.tree.max_children_per_node()).find(|&index| self.child(index).is_none()) {
+            Some(index) => Ok(self.set_child_value(index, value)),
+            None => Err(value),
+        }
+    }
+
+    /// Removes the child at the specified index along with its descendants, moving the child at
+    /// the highest child offset, and its descendants, into its place. This keeps children which
+    /// were packed into the lowest child offsets packed, without moving any other children.
+    ///
+    /// # Returns
+    ///
+    /// The removed child and its descendants as a tree, which is empty if there was no child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is not less than the max children per node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(4);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.push_child(2).unwrap();
+    ///     root.push_child(3).unwrap();
+    ///     root.push_child(4).unwrap().push_child(5).unwrap();
+    ///
+    ///     let removed = root.swap_remove_child(0);
+    ///     assert_eq!(removed.root().map(|n| *n.value()), Some(2));
+    /// }
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 4, 3, 5]);
+    /// ```
+    pub fn swap_remove_child(&mut self, index: usize) -> EytzingerTree<N> {
+        let child_index = self.tree.child_index(self.index, index);
+        let removed = self.tree.split_off(child_index);
+        if removed.is_empty() {
+            return removed;
+        }
+
+        let last = (index + 1..self.tree.max_children_per_node())
+            .rev()
+            .find(|&last| self.child(last).is_some());
+        if let Some(last) = last {
+            let last_index = self.tree.child_index(self.index, last);
+            let last_child = self.tree.split_off(last_index);
+            self.tree.graft(child_index, last_child);
+        }
+
+        removed
     }
 
     /// Gets the child entry of this node at the specified index.
+    pub fn child_entry(&self, index: usize) -> Entry<'_, N> {
+        self.tree.child_entry(self.index, index)
+    }
+
+    /// Gets the mutable entry of the first child of this node which does not exist, or `None` if
+    /// every child exists or the children would be too deep to be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// let mut root = tree.set_root_value(1);
+    ///
+    /// let mut next = 2;
+    /// while let Some(slot) = root.first_vacant_child_slot() {
+    ///     slot.insert(next);
+    ///     next += 1;
+    /// }
     ///
-    /// This differs from `child_entry` in that it takes ownership of the current node and the
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn first_vacant_child_slot(&mut self) -> Option<VacantEntryMut<'_, N>> {
+        let index = self.tree.first_vacant_child_index(self.index)?;
+
+        Some(VacantEntryMut {
+            tree: self.tree,
+            index,
+        })
+    }
+
+    /// Gets the mutable child entry of this node at the specified index. This node is not consumed
+    /// in the process so the child entry is lifetime bound to this node.
+    pub fn child_entry_mut(&mut self, index: usize) -> EntryMut<'_, N> {
+        self.tree.child_entry_mut(self.index, index)
+    }
+
+    /// Gets the mutable child entry of this node at the specified index.
+    ///
+    /// This differs from `child_entry_mut` in that it takes ownership of the current node and the
     /// entry is lifetime bound to the tree and not to the current node.
-    pub fn to_child_entry(self, index: usize) -> Entry<'a, N> {
-        self.tree.child_entry(self.index, index)
+    pub fn to_child_entry(self, index: usize) -> EntryMut<'a, N> {
+        self.tree.child_entry_mut(self.index, index)
     }
 
     /// Removes this node from the tree.
@@ -172,13 +711,13 @@ impl<'a, N> NodeMut<'a, N> {
     /// }
     /// assert_eq!(tree.root(), None);
     /// ```
-    pub fn remove(self) -> (N, VacantEntry<'a, N>) {
+    pub fn remove(self) -> (N, VacantEntryMut<'a, N>) {
         let value = self
             .tree
             .remove(self.index)
             .expect("there should be a value at the node index");
 
-        let entry = VacantEntry {
+        let entry = VacantEntryMut {
             tree: self.tree,
             index: self.index,
         };
@@ -186,9 +725,48 @@ impl<'a, N> NodeMut<'a, N> {
         (value, entry)
     }
 
+    /// Gets the index of this node in the storage of the tree, see `Node::index`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the child offset of this node within its parent, or `None` for the root.
+    pub fn child_offset(&self) -> Option<usize> {
+        self.as_node().child_offset()
+    }
+
+    /// Gets the depth of this node within the tree, the root has a depth of zero.
+    pub fn depth(&self) -> Depth {
+        self.as_node().depth()
+    }
+
+    /// Gets a reference to this node which does not borrow the tree, it may be resolved later
+    /// with `EytzingerTree::resolve_mut`.
+    pub fn node_ref(&self) -> NodeRef {
+        self.as_node().node_ref()
+    }
+
+    /// Gets the tree index of the child at the specified index, checking it may be stored.
+    fn checked_child_index(&self, index: usize) -> Result<usize, Error> {
+        let max_children_per_node = self.tree.max_children_per_node();
+        if index >= max_children_per_node {
+            return Err(Error::ChildIndexOutOfRange {
+                child_offset: index,
+                max_children_per_node,
+            });
+        }
+
+        self.tree
+            .index_calculator
+            .checked_child_index(self.index, index)
+            .ok_or_else(|| Error::DepthOverflow {
+                depth: self.as_node().path().depth() + 1,
+            })
+    }
+
     /// Gets a view of this mutable node as an immutable node. The resulting node is lifetime bound
     /// to this node so the immutable node may not outlive this mutable node.
-    pub fn as_node(&self) -> Node<N> {
+    pub fn as_node(&self) -> Node<'_, N> {
         Node {
             tree: self.tree,
             index: self.index,
@@ -218,23 +796,106 @@ impl<'a, N> NodeMut<'a, N> {
     /// let child_values: Vec<_> = root.child_iter().map(|n| n.value()).collect();
     /// assert_eq!(child_values, vec![&1, &3]);
     /// ```
-    pub fn child_iter(&self) -> NodeChildIter<N> {
+    pub fn child_iter(&self) -> NodeChildIter<'_, N> {
         self.as_node().child_iter()
     }
 
     /// Gets a depth-first iterator over this and all child nodes.
-    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> DepthFirstIter<N> {
+    pub fn depth_first_iter(&self, order: DepthFirstOrder) -> DepthFirstIter<'_, N> {
         self.as_node().depth_first_iter(order)
     }
 
     /// Gets a breadth-first iterator over this and all child nodes.
-    pub fn breadth_first_iter(&self) -> BreadthFirstIter<N> {
+    pub fn breadth_first_iter(&self) -> BreadthFirstIter<'_, N> {
         self.as_node().breadth_first_iter()
     }
 
+    /// Changes the value of this node and every node below it in place. The values are visited in
+    /// breadth-first order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(1);
+    ///     root.set_child_value(0, 2);
+    ///     root.set_child_value(1, 3).set_child_value(0, 4);
+    /// }
+    ///
+    /// let mut right = tree.root_mut().unwrap().to_child(1).ok().unwrap();
+    /// right.map_subtree_values(|value| *value += 100);
+    ///
+    /// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 103, 104]);
+    /// ```
+    pub fn map_subtree_values<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut N),
+    {
+        self.tree
+            .record_subtree_modified(self.index..self.index + 1);
+        let levels = self
+            .tree
+            .index_calculator
+            .descendant_level_ranges(self.index..self.index + 1, self.tree.nodes.len());
+
+        for level in levels {
+            self.tree.nodes[level].iter_mut().flatten().for_each(&mut f);
+        }
+    }
+
     pub fn split_off(self) -> EytzingerTree<N> {
         self.tree.split_off(self.index)
     }
+
+    /// Rebuilds this binary subtree as a perfectly balanced search tree of the same values, this
+    /// node remains the root of the subtree but may have a different value. The values are
+    /// collected in order and stably sorted by the comparison function, so a subtree which is
+    /// already a search tree keeps the relative order of equal values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1)
+    ///     .set_child_value(1, 2)
+    ///     .set_child_value(1, 3);
+    ///
+    /// tree.root_mut().unwrap().rebalance_subtree_by(Ord::cmp);
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.value(), &2);
+    /// assert_eq!(root.child(0).map(|n| *n.value()), Some(1));
+    /// assert_eq!(root.child(1).map(|n| *n.value()), Some(3));
+    /// ```
+    pub fn rebalance_subtree_by<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&N, &N) -> Ordering,
+    {
+        self.tree.rebalance_by(self.index, cmp);
+    }
+
+    /// Rebuilds this binary subtree as a perfectly balanced search tree of the same values, see
+    /// `rebalance_subtree_by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    pub fn rebalance_subtree(&mut self)
+    where
+        N: Ord,
+    {
+        self.rebalance_subtree_by(Ord::cmp);
+    }
 }
 
 impl<'a, N> Deref for NodeMut<'a, N> {
@@ -253,7 +914,150 @@ impl<'a, N> DerefMut for NodeMut<'a, N> {
 
 #[cfg(test)]
 mod tests {
-    use crate::EytzingerTree;
+    use crate::{Error, EytzingerTree, NodePath};
+
+    #[test]
+    fn graft_child_with_an_empty_subtree_removes_the_child() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(1, 2).set_child_value(0, 3);
+        }
+
+        let mut root = tree.root_mut().unwrap();
+        let replaced = root.graft_child(1, EytzingerTree::new(2)).unwrap();
+        assert_eq!(replaced.len(), 2);
+        assert_eq!(
+            root.graft_child(2, EytzingerTree::new(2)).unwrap_err(),
+            Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2
+            }
+        );
+
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn permute_children_validates_the_permutation() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+        }
+        let original = tree.clone();
+
+        let mut root = tree.root_mut().unwrap();
+        for permutation in [&[0][..], &[0, 0], &[1, 2], &[1, 0, 2]] {
+            assert_eq!(
+                root.permute_children(permutation),
+                Err(Error::InvalidPermutation)
+            );
+        }
+        assert_eq!(tree, original);
+
+        let mut root = tree.root_mut().unwrap();
+        root.permute_children(&[1, 0]).unwrap();
+        root.permute_children(&[1, 0]).unwrap();
+        assert_eq!(tree, original);
+    }
+
+    #[test]
+    fn rebalance_subtree_balances_a_degenerate_subtree() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(100);
+            let mut node = root.set_child_value(0, 1);
+            for value in 2..16 {
+                node = node.to_child_entry(1).or_insert(value);
+            }
+        }
+
+        tree.root_mut()
+            .unwrap()
+            .to_child(0)
+            .ok()
+            .unwrap()
+            .rebalance_subtree();
+
+        assert_eq!(tree.len(), 16);
+        assert!(tree.is_search_tree(Ord::cmp));
+        assert_eq!(tree.root().unwrap().child(0).map(|n| *n.value()), Some(8));
+
+        let height = tree
+            .breadth_first_iter()
+            .map(|n| n.path().depth())
+            .max()
+            .unwrap();
+        assert_eq!(height, 4);
+    }
+
+    #[test]
+    fn rebalance_subtree_sorts_unordered_values() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(3);
+            root.set_child_value(0, 5).set_child_value(0, 4);
+            root.set_child_value(1, 1);
+        }
+
+        tree.root_mut().unwrap().rebalance_subtree();
+
+        let in_order: Vec<_> = tree.in_order_iter().map(|n| *n.value()).collect();
+        assert_eq!(in_order, vec![1, 3, 4, 5]);
+        assert_eq!(tree.root().map(|n| *n.value()), Some(4));
+    }
+
+    #[test]
+    fn extend_breadth_first_only_fills_below_the_node() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2);
+            root.set_child_value(2, 3).set_child_value(1, 4);
+        }
+
+        let mut right = tree.root_mut().unwrap().to_child(2).ok().unwrap();
+        assert_eq!(right.extend_breadth_first(vec![5, 6, 7, 8]), 4);
+        assert_eq!(right.extend_breadth_first(None), 0);
+
+        let right = tree.root().and_then(|n| n.child(2)).unwrap();
+        let values: Vec<_> = right.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![3, 5, 4, 6, 7, 8]);
+        assert_eq!(
+            tree.root()
+                .and_then(|n| n.child(0))
+                .unwrap()
+                .descendant_count(),
+            0
+        );
+        assert_eq!(tree.len(), 8);
+    }
+
+    #[test]
+    fn detach_children_keeps_the_shape_of_each_child() {
+        let mut left = EytzingerTree::<u32>::new(2);
+        left.set_root_value(2)
+            .set_child_value(1, 3)
+            .set_child_value(0, 4);
+        let mut right = EytzingerTree::<u32>::new(2);
+        right.set_root_value(5).set_child_value(1, 6);
+
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            root.graft_child(0, left.clone()).unwrap();
+            root.graft_child(1, right.clone()).unwrap();
+        }
+
+        assert_eq!(
+            tree.root_mut().unwrap().detach_children(),
+            vec![left, right]
+        );
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.breadth_first_iter().count(), 1);
+        assert!(tree.root_mut().unwrap().detach_children().is_empty());
+    }
 
     #[test]
     fn split_off() {
@@ -261,35 +1065,179 @@ mod tests {
 
         let split_off = {
             let mut child = tree
-                .root_entry()
+                .root_entry_mut()
                 .or_insert(10)
                 .to_child_entry(0)
                 .or_insert(5);
             child
-                .child_entry(0)
+                .child_entry_mut(0)
                 .or_insert(4)
-                .child_entry(0)
+                .child_entry_mut(0)
                 .or_insert(1);
-            child.child_entry(1).or_insert(8);
+            child.child_entry_mut(1).or_insert(8);
 
             child.split_off()
         };
 
         let mut expected_remaining = EytzingerTree::new(2);
         {
-            expected_remaining.root_entry().or_insert(10);
+            expected_remaining.root_entry_mut().or_insert(10);
         }
 
         let mut expected_split_off = EytzingerTree::new(2);
         {
-            let mut root = expected_split_off.root_entry().or_insert(5);
+            let mut root = expected_split_off.root_entry_mut().or_insert(5);
 
-            root.child_entry(0).or_insert(4).child_entry(0).or_insert(1);
-            root.child_entry(1).or_insert(8);
+            root.child_entry_mut(0)
+                .or_insert(4)
+                .child_entry_mut(0)
+                .or_insert(1);
+            root.child_entry_mut(1).or_insert(8);
         }
 
         assert_eq!(tree, expected_remaining);
         assert_eq!(split_off, expected_split_off);
     }
 
+    #[test]
+    fn split_children_mut_gives_disjoint_children() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(2, 4);
+            root.set_child_value(2, 2).set_child_value(0, 5);
+        }
+
+        {
+            let mut root = tree.root_mut().unwrap();
+            let mut children = root.split_children_mut();
+            assert_eq!(children.len(), 3);
+            assert!(children[1].is_none());
+
+            if let [Some(left), None, Some(right)] = &mut children[..] {
+                let grandchild = NodePath::from(vec![2]);
+                *left.get_mut(&grandchild).unwrap() += 10;
+                for value in right.values_mut() {
+                    *value *= 10;
+                }
+            } else {
+                panic!("the first and last children should exist");
+            }
+        }
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![0, 1, 20, 14, 50]);
+    }
+
+    #[test]
+    fn split_children_mut_of_a_leaf() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        let mut root = tree.set_root_value(0);
+
+        let children = root.split_children_mut();
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|child| child.is_none()));
+    }
+
+    #[test]
+    fn swap_remove_child_moves_the_last_subtree() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        let mut root = tree.set_root_value(0);
+        root.set_child_value(0, 1);
+        root.set_child_value(2, 3).set_child_value(1, 4);
+
+        assert!(root.swap_remove_child(1).is_empty());
+        assert_eq!(root.swap_remove_child(0).len(), 1);
+        assert_eq!(root.child(0).map(|n| *n.value()), Some(3));
+        assert!(root.child(2).is_none());
+        assert_eq!(
+            root.child(0).and_then(|n| n.child(1)).map(|n| *n.value()),
+            Some(4)
+        );
+
+        assert_eq!(root.swap_remove_child(0).len(), 2);
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn child_values_of_a_leaf_past_the_storage_are_vacant() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        let mut root = tree.set_root_value(1);
+        let mut leaf = root.set_child_value(2, 2);
+
+        assert_eq!(leaf.child_values().count(), 3);
+        assert!(leaf.child_values_mut().all(|value| value.is_none()));
+        assert_eq!(leaf.as_node().child_values_array::<3>(), [None, None, None]);
+    }
+
+    #[test]
+    fn child_values_mut_records_the_children_as_modified() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.enable_versioning();
+        tree.set_root_value(1).set_child_value(1, 2);
+        let tick = tree.current_tick().unwrap();
+
+        for value in tree.root_mut().unwrap().child_values_mut().flatten() {
+            *value += 1;
+        }
+
+        let modified: Vec<_> = tree
+            .nodes_modified_since(tick)
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(modified, vec![3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "max children per node")]
+    fn child_values_array_of_the_wrong_length_panics() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1);
+
+        tree.root().unwrap().child_values_array::<3>();
+    }
+
+    #[test]
+    fn graft_child_projected_rejects_slots_out_of_range_without_changes() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(1, 2);
+        let original = tree.clone();
+
+        let mut ternary = EytzingerTree::<u32>::new(3);
+        ternary.set_root_value(3).set_child_value(2, 4);
+
+        let mut root = tree.root_mut().unwrap();
+        assert_eq!(
+            root.graft_child_projected(1, ternary.clone(), Some),
+            Err(Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2
+            })
+        );
+        assert_eq!(tree, original);
+
+        let mut root = tree.root_mut().unwrap();
+        let replaced = root
+            .graft_child_projected(1, ternary, |slot| slot.checked_sub(1))
+            .unwrap();
+        assert_eq!(replaced.root().map(|n| *n.value()), Some(2));
+        let grafted = tree.root().and_then(|n| n.child(1)).unwrap();
+        assert_eq!(grafted.child(1).map(|n| *n.value()), Some(4));
+    }
+
+    #[test]
+    fn index_and_child_offset_match_the_node() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        let (index, child_offset) = {
+            let mut root = tree.set_root_value(1);
+            assert_eq!(root.child_offset(), None);
+
+            let child = root.set_child_value(2, 2);
+            (child.index(), child.child_offset())
+        };
+
+        let child = tree.root().and_then(|n| n.child(2)).unwrap();
+        assert_eq!(child.index(), index);
+        assert_eq!(child_offset, Some(2));
+    }
 }
@@ -0,0 +1,128 @@
+use std::{
+    fmt::{self, Display},
+    iter::FromIterator,
+};
+
+/// The path from the root of an Eytzinger tree to a node, this is the child offset taken at each
+/// level. The root has an empty path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodePath {
+    child_offsets: Vec<usize>,
+}
+
+impl NodePath {
+    /// Gets the path of the root node.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Gets whether this is the path of the root node.
+    pub fn is_root(&self) -> bool {
+        self.child_offsets.is_empty()
+    }
+
+    /// Gets the depth of the node this path leads to, the root has a depth of zero.
+    pub fn depth(&self) -> usize {
+        self.child_offsets.len()
+    }
+
+    /// Gets the child offset taken at each level.
+    pub fn child_offsets(&self) -> &[usize] {
+        &self.child_offsets
+    }
+
+    /// Gets the path of the child at the specified offset.
+    pub fn child(&self, child_offset: usize) -> Self {
+        let mut child = self.clone();
+        child.push(child_offset);
+        child
+    }
+
+    /// Gets the path of the parent or `None` if this is the path of the root node.
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.clone();
+        parent.pop().map(|_| parent)
+    }
+
+    /// Extends this path to the child at the specified offset.
+    pub fn push(&mut self, child_offset: usize) {
+        self.child_offsets.push(child_offset);
+    }
+
+    /// Shortens this path to its parent.
+    ///
+    /// # Returns
+    ///
+    /// The child offset which was removed or `None` if this was the path of the root node.
+    pub fn pop(&mut self) -> Option<usize> {
+        self.child_offsets.pop()
+    }
+}
+
+impl Display for NodePath {
+    /// Formats the path as the child offsets separated by `/`, such as `/0/3/1`. The root path is
+    /// formatted as `/`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str("/");
+        }
+
+        for child_offset in &self.child_offsets {
+            write!(f, "/{}", child_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<usize>> for NodePath {
+    fn from(child_offsets: Vec<usize>) -> Self {
+        Self { child_offsets }
+    }
+}
+
+impl<'a> From<&'a [usize]> for NodePath {
+    fn from(child_offsets: &'a [usize]) -> Self {
+        Self {
+            child_offsets: child_offsets.to_vec(),
+        }
+    }
+}
+
+impl FromIterator<usize> for NodePath {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        Self {
+            child_offsets: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::NodePath;
+
+    #[test]
+    fn child_and_parent_are_inverse() {
+        let path = NodePath::from(vec![0, 3]);
+        let child = path.child(1);
+
+        assert_eq!(child.child_offsets(), &[0, 3, 1]);
+        assert_eq!(child.depth(), 3);
+        assert_eq!(child.parent(), Some(path));
+    }
+
+    #[test]
+    fn display_separates_child_offsets() {
+        assert_eq!(NodePath::root().to_string(), "/");
+        assert_eq!(NodePath::from(vec![0, 3, 1]).to_string(), "/0/3/1");
+    }
+
+    #[test]
+    fn root_has_no_parent() {
+        assert!(NodePath::root().is_root());
+        assert_eq!(NodePath::root().parent(), None);
+    }
+}
@@ -0,0 +1,118 @@
+/// A reference to a node which does not borrow the tree it was taken from.
+///
+/// The reference is cheap to copy and may be sent to other threads or stored for later, it is
+/// resolved back to the node with `EytzingerTree::resolve`. Resolving fails once any node in the
+/// tree has been set, replaced or removed, changes made in place through `NodeMut::value_mut` do
+/// not invalidate the reference. A reference should only be resolved against the tree it was
+/// taken from, resolving it against any other tree, including a clone, panics in debug builds and
+/// fails otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeRef {
+    pub(crate) id: usize,
+    pub(crate) tree_id: u64,
+    pub(crate) fingerprint: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, EytzingerTree, NodeRef};
+    use std::{sync::mpsc, thread};
+
+    fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+
+    #[test]
+    fn node_ref_is_send_sync_and_static() {
+        assert_send_sync_static::<NodeRef>();
+    }
+
+    #[test]
+    fn node_ref_resolves_after_crossing_threads() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(0, 2);
+
+        let (sender, receiver) = mpsc::channel();
+        let child_ref = tree.root().and_then(|n| n.child(0)).unwrap().node_ref();
+        thread::spawn(move || sender.send(child_ref).unwrap())
+            .join()
+            .unwrap();
+
+        let received = receiver.recv().unwrap();
+        assert_eq!(tree.resolve(received).map(|n| *n.value()), Some(2));
+    }
+
+    #[test]
+    fn node_ref_survives_in_place_changes() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        let root_ref = tree.set_root_value(1).node_ref();
+
+        *tree.resolve_mut(root_ref).unwrap().value_mut() = 7;
+
+        assert_eq!(tree.resolve(root_ref).map(|n| *n.value()), Some(7));
+    }
+
+    #[test]
+    fn node_ref_is_invalidated_by_structural_changes() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        let root_ref = tree.set_root_value(1).node_ref();
+        tree.set_root_value(2);
+        assert!(tree.resolve(root_ref).is_none());
+
+        let root_ref = tree.root().unwrap().node_ref();
+        tree.root_mut().unwrap().set_child_value(1, 3);
+        assert!(tree.resolve(root_ref).is_none());
+
+        let root_ref = tree.root().unwrap().node_ref();
+        tree.root_mut().unwrap().remove_child_value(1);
+        assert!(tree.resolve(root_ref).is_none());
+
+        let root_ref = tree.root().unwrap().node_ref();
+        tree.clear();
+        assert!(tree.resolve(root_ref).is_none());
+        assert!(tree.resolve_mut(root_ref).is_none());
+    }
+
+    #[test]
+    fn try_resolve_reports_stale_references() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        let root_ref = tree.set_root_value(1).node_ref();
+
+        *tree.try_resolve_mut(root_ref).unwrap().value_mut() = 2;
+        assert_eq!(tree.try_resolve(root_ref).map(|n| *n.value()), Ok(2));
+
+        tree.root_mut().unwrap().set_child_value(0, 3);
+        assert_eq!(tree.try_resolve(root_ref).err(), Some(Error::StaleNodeId));
+        assert_eq!(
+            tree.try_resolve_mut(root_ref).err(),
+            Some(Error::StaleNodeId)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "different tree"))]
+    fn node_ref_from_a_clone_changed_in_step_is_rejected() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1);
+        let mut clone = tree.clone();
+
+        tree.root_mut().unwrap().set_child_value(0, 2);
+        clone.root_mut().unwrap().set_child_value(1, 3);
+
+        let tree_ref = tree.root().and_then(|n| n.child(0)).unwrap().node_ref();
+        let clone_ref = clone.root().and_then(|n| n.child(1)).unwrap().node_ref();
+
+        assert!(tree.resolve(clone_ref).is_none());
+        assert!(clone.resolve(tree_ref).is_none());
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "different tree"))]
+    fn node_ref_from_another_tree_is_rejected() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        let root_ref = tree.set_root_value(1).node_ref();
+
+        let mut other = EytzingerTree::<u32>::new(2);
+        other.set_root_value(2);
+
+        assert!(other.resolve(root_ref).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::EytzingerTree;
+use std::hash::{Hash, Hasher};
+
+/// A view of an Eytzinger tree which is compared and hashed by the path and value of each of its
+/// nodes, ignoring the max children per node it is stored with.
+///
+/// The same logical tree stored with different arities is equal through this view, unlike the
+/// trees themselves. Created by `EytzingerTree::normalized`.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::EytzingerTree;
+/// use std::collections::HashSet;
+///
+/// let mut narrow = EytzingerTree::<u32>::new(4);
+/// narrow.set_root_value(1).set_child_value(3, 2);
+///
+/// let mut wide = EytzingerTree::<u32>::new(8);
+/// wide.set_root_value(1).set_child_value(3, 2);
+///
+/// assert_ne!(narrow, wide);
+/// assert_eq!(narrow.normalized(), wide.normalized());
+///
+/// let unique: HashSet<_> = vec![narrow.normalized(), wide.normalized()]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(unique.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct NormalizedTree<'a, N> {
+    tree: &'a EytzingerTree<N>,
+}
+
+impl<'a, N> NormalizedTree<'a, N> {
+    pub(crate) fn new(tree: &'a EytzingerTree<N>) -> Self {
+        Self { tree }
+    }
+
+    /// Gets the tree this is a view of.
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
+        self.tree
+    }
+}
+
+impl<'a, N> Clone for NormalizedTree<'a, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N> Copy for NormalizedTree<'a, N> {}
+
+impl<'a, 'b, N, M> PartialEq<NormalizedTree<'b, M>> for NormalizedTree<'a, N>
+where
+    N: PartialEq<M>,
+{
+    fn eq(&self, other: &NormalizedTree<'b, M>) -> bool {
+        self.tree.shape_value_eq(other.tree)
+    }
+}
+
+impl<'a, N> Eq for NormalizedTree<'a, N> where N: Eq {}
+
+impl<'a, N> Hash for NormalizedTree<'a, N>
+where
+    N: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.tree.len().hash(state);
+        for node in self.tree.breadth_first_iter() {
+            node.path().hash(state);
+            node.value().hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EytzingerTree;
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    fn hash<T: Hash>(value: T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_across_arities() {
+        let mut binary = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = binary.set_root_value(1);
+            root.set_child_value(1, 2).set_child_value(0, 3);
+            root.set_child_value(0, 4);
+        }
+        let mut quaternary = EytzingerTree::<u32>::new(4);
+        {
+            let mut root = quaternary.set_root_value(1);
+            root.set_child_value(0, 4);
+            root.set_child_value(1, 2).set_child_value(0, 3);
+        }
+
+        assert_eq!(binary.normalized(), quaternary.normalized());
+        assert_eq!(hash(binary.normalized()), hash(quaternary.normalized()));
+    }
+
+    #[test]
+    fn different_paths_are_not_equal() {
+        let mut left = EytzingerTree::<u32>::new(2);
+        left.set_root_value(1).set_child_value(0, 2);
+        let mut right = EytzingerTree::<u32>::new(4);
+        right.set_root_value(1).set_child_value(1, 2);
+
+        assert_ne!(left.normalized(), right.normalized());
+        assert!(!left.shape_value_eq(&right));
+
+        right.root_mut().unwrap().remove_child_value(1);
+        assert!(!left.shape_value_eq(&right));
+    }
+}
@@ -0,0 +1,593 @@
+//! Trees where a node may occasionally have more children than the max children per node.
+//!
+//! The children which do not fit in the Eytzinger layout are kept in an overflow list for their
+//! parent. Paths address these children with child offsets which continue past the max children
+//! per node, so with 2 max children per node the child offset 2 is the first overflow child.
+//!
+//! The overflow children are only visited through an `OverflowTree` and its `OverflowNode`s. The
+//! underlying `EytzingerTree` and its own iterators only see the nodes which fit in the layout.
+
+use crate::{EytzingerTree, Node, NodeChildIter, NodePath};
+use std::{
+    collections::{HashMap, VecDeque},
+    iter::FusedIterator,
+    slice,
+};
+
+/// An Eytzinger tree along with overflow lists for the nodes which have more children than fit.
+///
+/// The overflow children of a node are trees of their own, so they may have overflow children
+/// too. Getting the length, cloning, comparing and dropping do not recurse through the nested
+/// trees, formatting with `Debug` does.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{overflow::OverflowTree, NodePath};
+///
+/// let mut tree = OverflowTree::new(2);
+/// tree.set_root_value(0);
+///
+/// let root = NodePath::root();
+/// let paths: Vec<_> = (1..=4)
+///     .map(|value| tree.push_child(&root, value).unwrap())
+///     .collect();
+/// assert_eq!(paths[3], NodePath::from(vec![3]));
+///
+/// assert_eq!(tree.len(), 5);
+/// assert_eq!(tree.tree().len(), 3);
+///
+/// let root = tree.root().unwrap();
+/// let values: Vec<_> = root.child_iter().map(|n| *n.value()).collect();
+/// assert_eq!(values, vec![1, 2, 3, 4]);
+/// ```
+#[derive(Debug)]
+pub struct OverflowTree<N> {
+    tree: EytzingerTree<N>,
+    overflow: HashMap<usize, Vec<OverflowTree<N>>>,
+}
+
+impl<N> OverflowTree<N> {
+    /// Creates a new empty tree with the specified max children per node stored in the Eytzinger
+    /// layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    pub fn new(max_children_per_node: usize) -> Self {
+        Self::from(EytzingerTree::new(max_children_per_node))
+    }
+
+    /// Gets the Eytzinger tree holding the nodes which fit in the layout.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Gets the max children per node stored in the Eytzinger layout.
+    pub fn max_children_per_node(&self) -> usize {
+        self.tree.max_children_per_node()
+    }
+
+    /// Gets the number of nodes in the tree, including those in overflow lists.
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+
+        let mut pending = vec![self];
+        while let Some(tree) = pending.pop() {
+            len += tree.tree.len();
+            pending.extend(tree.overflow.values().flatten());
+        }
+
+        len
+    }
+
+    /// Gets whether the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Gets the root node or `None` if the tree is empty.
+    pub fn root(&self) -> Option<OverflowNode<'_, N>> {
+        self.tree.root().map(|_| OverflowNode {
+            owner: self,
+            index: 0,
+        })
+    }
+
+    /// Sets the value of the root node.
+    ///
+    /// # Returns
+    ///
+    /// The old value of the root node, if there was one.
+    pub fn set_root_value(&mut self, new_value: N) -> Option<N> {
+        self.tree.replace_value(0, new_value)
+    }
+
+    /// Gets the value of the node at the path, if there is one.
+    pub fn get(&self, path: &NodePath) -> Option<&N> {
+        let (owner, index) = self.locate(path.child_offsets())?;
+        owner.tree.nodes[index].as_ref()
+    }
+
+    /// Gets the mutable value of the node at the path, if there is one.
+    pub fn get_mut(&mut self, path: &NodePath) -> Option<&mut N> {
+        let (owner, index) = self.locate_mut(path.child_offsets())?;
+        owner.tree.nodes[index].as_mut()
+    }
+
+    /// Adds a child to the node at the path, in the lowest vacant child offset of the Eytzinger
+    /// layout or at the end of the overflow list of the node if there is none.
+    ///
+    /// # Returns
+    ///
+    /// The path of the new child, or `None` if there is no node at the path.
+    pub fn push_child(&mut self, parent: &NodePath, value: N) -> Option<NodePath> {
+        let (owner, index) = self.locate_mut(parent.child_offsets())?;
+
+        let max_children_per_node = owner.max_children_per_node();
+        let vacant = (0..max_children_per_node).find_map(|child_offset| {
+            let child_index = owner.tree.child_index(index, child_offset);
+            owner
+                .tree
+                .node(child_index)
+                .is_none()
+                .then_some((child_offset, child_index))
+        });
+
+        let child_offset = match vacant {
+            Some((child_offset, child_index)) => {
+                owner.tree.set_value(child_index, value);
+                child_offset
+            }
+            None => {
+                let mut child = OverflowTree::new(max_children_per_node);
+                child.set_root_value(value);
+
+                let overflow = owner.overflow.entry(index).or_default();
+                overflow.push(child);
+                max_children_per_node + overflow.len() - 1
+            }
+        };
+
+        Some(parent.child(child_offset))
+    }
+
+    /// Removes the node at the path along with all of its descendants. Removing an overflow child
+    /// moves the overflow children after it down by one child offset.
+    ///
+    /// # Returns
+    ///
+    /// The old value of the node, if there was one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{overflow::OverflowTree, NodePath};
+    ///
+    /// let mut tree = OverflowTree::new(1);
+    /// tree.set_root_value(0);
+    /// for value in 1..=3 {
+    ///     tree.push_child(&NodePath::root(), value);
+    /// }
+    ///
+    /// assert_eq!(tree.remove(&NodePath::from(vec![1])), Some(2));
+    /// assert_eq!(tree.get(&NodePath::from(vec![1])), Some(&3));
+    ///
+    /// assert_eq!(tree.remove(&NodePath::from(vec![0])), Some(1));
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn remove(&mut self, path: &NodePath) -> Option<N> {
+        let (last, parent_offsets) = match path.child_offsets().split_last() {
+            Some(split) => split,
+            None => {
+                self.overflow.clear();
+                return self.tree.remove(0);
+            }
+        };
+
+        let (owner, parent_index) = self.locate_mut(parent_offsets)?;
+        let max_children_per_node = owner.max_children_per_node();
+        if *last >= max_children_per_node {
+            let overflow = owner.overflow.get_mut(&parent_index)?;
+            let position = last - max_children_per_node;
+            if position >= overflow.len() {
+                return None;
+            }
+
+            let mut removed = overflow.remove(position);
+            if overflow.is_empty() {
+                owner.overflow.remove(&parent_index);
+            }
+            return removed.tree.remove(0);
+        }
+
+        let index = owner.tree.child_index(parent_index, *last);
+        let tree = &owner.tree;
+        owner
+            .overflow
+            .retain(|&parent, _| !is_in_subtree(tree, index, parent));
+        owner.tree.remove(index)
+    }
+
+    fn locate(&self, child_offsets: &[usize]) -> Option<(&Self, usize)> {
+        let mut owner = self;
+        let mut index = 0;
+        owner.tree.node(index)?;
+
+        for &child_offset in child_offsets {
+            let max_children_per_node = owner.max_children_per_node();
+            if child_offset < max_children_per_node {
+                index = owner
+                    .tree
+                    .index_calculator
+                    .checked_child_index(index, child_offset)?;
+                owner.tree.node(index)?;
+            } else {
+                owner = owner
+                    .overflow
+                    .get(&index)?
+                    .get(child_offset - max_children_per_node)?;
+                index = 0;
+            }
+        }
+
+        Some((owner, index))
+    }
+
+    fn locate_mut(&mut self, child_offsets: &[usize]) -> Option<(&mut Self, usize)> {
+        let mut owner = self;
+        let mut index = 0;
+        owner.tree.node(index)?;
+
+        for &child_offset in child_offsets {
+            let max_children_per_node = owner.max_children_per_node();
+            if child_offset < max_children_per_node {
+                index = owner
+                    .tree
+                    .index_calculator
+                    .checked_child_index(index, child_offset)?;
+                owner.tree.node(index)?;
+            } else {
+                owner = owner
+                    .overflow
+                    .get_mut(&index)?
+                    .get_mut(child_offset - max_children_per_node)?;
+                index = 0;
+            }
+        }
+
+        Some((owner, index))
+    }
+}
+
+impl<N: Clone> Clone for OverflowTree<N> {
+    fn clone(&self) -> Self {
+        let shallow_clone = |tree: &Self| Self::from(tree.tree.clone());
+
+        // every nested tree is cloned without its overflow lists in breadth-first order, along
+        // with the position of its parent's clone and the key of the list it belongs to
+        let mut sources = vec![self];
+        let mut clones = vec![shallow_clone(self)];
+        let mut parents = vec![];
+
+        let mut position = 0;
+        while let Some(&source) = sources.get(position) {
+            for (&key, list) in &source.overflow {
+                // reversed so that popping the clones below restores the order of the list
+                for child in list.iter().rev() {
+                    sources.push(child);
+                    clones.push(shallow_clone(child));
+                    parents.push((position, key));
+                }
+            }
+            position += 1;
+        }
+
+        // children always come after their parents so each clone is complete when it is moved
+        // into the overflow list of its parent
+        for (parent, key) in parents.into_iter().rev() {
+            let child = clones.pop().expect("every child should have a clone");
+            clones[parent].overflow.entry(key).or_default().push(child);
+        }
+
+        clones.pop().expect("the root should have a clone")
+    }
+}
+
+impl<N: PartialEq> PartialEq for OverflowTree<N> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut pending = vec![(self, other)];
+        while let Some((tree, other)) = pending.pop() {
+            if tree.tree != other.tree || tree.overflow.len() != other.overflow.len() {
+                return false;
+            }
+
+            for (key, list) in &tree.overflow {
+                match other.overflow.get(key) {
+                    Some(other_list) if other_list.len() == list.len() => {
+                        pending.extend(list.iter().zip(other_list))
+                    }
+                    _ => return false,
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl<N: Eq> Eq for OverflowTree<N> {}
+
+impl<N> Drop for OverflowTree<N> {
+    fn drop(&mut self) {
+        // each nested tree is dropped once its overflow lists have been moved out, so dropping
+        // it does not recurse
+        let mut pending: Vec<_> = self.overflow.drain().flat_map(|(_, list)| list).collect();
+        while let Some(mut tree) = pending.pop() {
+            pending.extend(tree.overflow.drain().flat_map(|(_, list)| list));
+        }
+    }
+}
+
+impl<N> From<EytzingerTree<N>> for OverflowTree<N> {
+    fn from(tree: EytzingerTree<N>) -> Self {
+        Self {
+            tree,
+            overflow: HashMap::new(),
+        }
+    }
+}
+
+fn is_in_subtree<N>(tree: &EytzingerTree<N>, root: usize, mut index: usize) -> bool {
+    while index > root {
+        index = match tree.parent_index(index) {
+            Some(parent) => parent,
+            None => return false,
+        };
+    }
+    index == root
+}
+
+/// Represents a borrowed node in an `OverflowTree`.
+#[derive(Debug)]
+pub struct OverflowNode<'a, N> {
+    owner: &'a OverflowTree<N>,
+    index: usize,
+}
+
+impl<'a, N> Copy for OverflowNode<'a, N> {}
+
+impl<'a, N> Clone for OverflowNode<'a, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N> OverflowNode<'a, N> {
+    /// Gets the value stored at this node.
+    pub fn value(&self) -> &'a N {
+        self.node().value()
+    }
+
+    /// Gets this node within the Eytzinger tree which holds it, which is the tree of an overflow
+    /// list for overflow children and their descendants.
+    pub fn node(&self) -> Node<'a, N> {
+        self.owner
+            .tree
+            .node(self.index)
+            .expect("the node should exist")
+    }
+
+    /// Gets the children of this node stored in its overflow list.
+    pub fn overflow_children(&self) -> &'a [OverflowTree<N>] {
+        self.owner
+            .overflow
+            .get(&self.index)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Gets an iterator over the children of this node, the children in the Eytzinger layout are
+    /// followed by those in the overflow list.
+    pub fn child_iter(&self) -> OverflowChildIter<'a, N> {
+        OverflowChildIter {
+            owner: self.owner,
+            inline: self.node().child_iter(),
+            overflow: self.overflow_children().iter(),
+        }
+    }
+
+    /// Gets a pre-order depth-first iterator over this and all descendant nodes, including
+    /// those in overflow lists.
+    pub fn depth_first_iter(&self) -> OverflowDepthFirstIter<'a, N> {
+        OverflowDepthFirstIter {
+            pending: vec![*self],
+        }
+    }
+
+    /// Gets a breadth-first iterator over this and all descendant nodes, including those in
+    /// overflow lists. The children of each node are visited in the order of `child_iter`.
+    pub fn breadth_first_iter(&self) -> OverflowBreadthFirstIter<'a, N> {
+        OverflowBreadthFirstIter {
+            pending: VecDeque::from(vec![*self]),
+        }
+    }
+}
+
+/// An iterator over the children of an `OverflowNode`.
+#[derive(Debug, Clone)]
+pub struct OverflowChildIter<'a, N> {
+    owner: &'a OverflowTree<N>,
+    inline: NodeChildIter<'a, N>,
+    overflow: slice::Iter<'a, OverflowTree<N>>,
+}
+
+impl<'a, N> Iterator for OverflowChildIter<'a, N> {
+    type Item = OverflowNode<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(child) = self.inline.next() {
+            return Some(OverflowNode {
+                owner: self.owner,
+                index: child.index,
+            });
+        }
+
+        self.overflow
+            .next()
+            .map(|owner| OverflowNode { owner, index: 0 })
+    }
+}
+
+impl<'a, N> FusedIterator for OverflowChildIter<'a, N> {}
+
+/// A pre-order depth-first iterator over the nodes of an `OverflowTree`.
+#[derive(Debug, Clone)]
+pub struct OverflowDepthFirstIter<'a, N> {
+    pending: Vec<OverflowNode<'a, N>>,
+}
+
+impl<'a, N> Iterator for OverflowDepthFirstIter<'a, N> {
+    type Item = OverflowNode<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.pending.pop()?;
+
+        let start = self.pending.len();
+        self.pending.extend(node.child_iter());
+        self.pending[start..].reverse();
+
+        Some(node)
+    }
+}
+
+impl<'a, N> FusedIterator for OverflowDepthFirstIter<'a, N> {}
+
+/// A breadth-first iterator over the nodes of an `OverflowTree`.
+#[derive(Debug, Clone)]
+pub struct OverflowBreadthFirstIter<'a, N> {
+    pending: VecDeque<OverflowNode<'a, N>>,
+}
+
+impl<'a, N> Iterator for OverflowBreadthFirstIter<'a, N> {
+    type Item = OverflowNode<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.pending.pop_front()?;
+        self.pending.extend(node.child_iter());
+
+        Some(node)
+    }
+}
+
+impl<'a, N> FusedIterator for OverflowBreadthFirstIter<'a, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_children_can_overflow() {
+        let mut tree = OverflowTree::new(1);
+        tree.set_root_value(0);
+        let root = NodePath::root();
+        tree.push_child(&root, 1);
+        let second = tree.push_child(&root, 2).unwrap();
+        tree.push_child(&second, 3);
+        let wide = tree.push_child(&second, 4).unwrap();
+        assert_eq!(wide, NodePath::from(vec![1, 1]));
+
+        assert_eq!(tree.get(&wide), Some(&4));
+        *tree.get_mut(&wide).unwrap() = 5;
+
+        let values: Vec<_> = tree
+            .root()
+            .unwrap()
+            .depth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 5]);
+        assert_eq!(tree.len(), 5);
+    }
+
+    #[test]
+    fn removing_a_node_drops_its_overflow_lists() {
+        let mut tree = OverflowTree::new(1);
+        tree.set_root_value(0);
+        let child = tree.push_child(&NodePath::root(), 1).unwrap();
+        tree.push_child(&child, 2);
+        tree.push_child(&child, 3);
+        assert_eq!(tree.len(), 4);
+
+        assert_eq!(tree.remove(&child), Some(1));
+        assert_eq!(tree.len(), 1);
+        assert!(tree.overflow.is_empty());
+        assert_eq!(tree.push_child(&NodePath::root(), 4), Some(child));
+        assert_eq!(tree.remove(&NodePath::from(vec![0, 1])), None);
+        assert_eq!(tree.push_child(&NodePath::from(vec![5]), 6), None);
+    }
+
+    #[test]
+    fn breadth_first_visits_overflow_children_after_inline_ones() {
+        let mut tree = OverflowTree::new(1);
+        tree.set_root_value(0);
+        let root = NodePath::root();
+        let first = tree.push_child(&root, 1).unwrap();
+        let second = tree.push_child(&root, 2).unwrap();
+        tree.push_child(&first, 3);
+        tree.push_child(&second, 4);
+        tree.push_child(&second, 5);
+
+        let values: Vec<_> = tree
+            .root()
+            .unwrap()
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn clone_keeps_the_order_of_overflow_lists() {
+        let mut tree = OverflowTree::new(1);
+        tree.set_root_value(0);
+        let root = NodePath::root();
+        for value in 1..5 {
+            let child = tree.push_child(&root, value).unwrap();
+            for grandchild in 0..3 {
+                tree.push_child(&child, value * 10 + grandchild);
+            }
+        }
+
+        let clone = tree.clone();
+        assert_eq!(clone, tree);
+        assert_eq!(clone.len(), tree.len());
+
+        let values = |tree: &OverflowTree<u32>| -> Vec<u32> {
+            let root = tree.root().unwrap();
+            root.depth_first_iter().map(|n| *n.value()).collect()
+        };
+        assert_eq!(values(&clone), values(&tree));
+
+        *tree.get_mut(&NodePath::from(vec![2, 1])).unwrap() = 0;
+        assert_ne!(clone, tree);
+    }
+
+    #[test]
+    fn deep_overflow_chains_do_not_recurse() {
+        // every node has one inline child and the next tree of the chain as an overflow child
+        let mut tree = OverflowTree::new(1);
+        tree.set_root_value(0);
+        for value in 1..100_000 {
+            let mut parent = OverflowTree::new(1);
+            parent.set_root_value(value);
+            parent.push_child(&NodePath::root(), value);
+            parent.overflow.insert(0, vec![tree]);
+            tree = parent;
+        }
+
+        assert_eq!(tree.len(), 199_999);
+
+        let clone = tree.clone();
+        assert!(clone == tree);
+    }
+}
@@ -0,0 +1,482 @@
+use crate::{EytzingerTree, Node};
+use std::fmt::{self, Debug};
+
+/// What a pattern requires of the node at its position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatternKind<T> {
+    /// An existing node whose value matches.
+    Value(T),
+
+    /// An existing node with any value.
+    AnyValue,
+
+    /// Anything, whether or not there is a node. Child patterns are ignored.
+    AnySubtree,
+
+    /// No node.
+    Vacant,
+}
+
+/// Where a child pattern is matched relative to its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChildPosition {
+    /// The child at the specified offset.
+    At(usize),
+
+    /// Any one of the children, the first offset which matches is used. Several `Any` patterns may
+    /// match the same child.
+    Any,
+}
+
+/// A small tree of requirements which may be matched against the nodes of an Eytzinger tree.
+///
+/// Children which are not given a pattern are not constrained. A pattern may be bound to a name
+/// so the node it matched can be retrieved from the `Match`. Matching recurses once per level of
+/// the pattern, which is expected to be small, but never per level of the tree.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{query::Pattern, EytzingerTree};
+///
+/// // x + 0
+/// let mut tree = EytzingerTree::new(2);
+/// {
+///     let mut add = tree.set_root_value("+");
+///     add.set_child_value(0, "x");
+///     add.set_child_value(1, "0");
+/// }
+///
+/// let pattern = Pattern::value("+")
+///     .child(0, Pattern::any_value().bind("lhs"))
+///     .child(1, Pattern::value("0"));
+///
+/// let matches = pattern.find_all(&tree);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].get("lhs").map(|n| *n.value()), Some("x"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern<T> {
+    kind: PatternKind<T>,
+    binding: Option<String>,
+    children: Vec<(ChildPosition, Pattern<T>)>,
+}
+
+impl<T> Pattern<T> {
+    /// Creates a new pattern of the specified kind.
+    pub fn new(kind: PatternKind<T>) -> Self {
+        Self {
+            kind,
+            binding: None,
+            children: vec![],
+        }
+    }
+
+    /// Creates a new pattern matching an existing node whose value matches.
+    pub fn value(value: T) -> Self {
+        Self::new(PatternKind::Value(value))
+    }
+
+    /// Creates a new pattern matching an existing node with any value.
+    pub fn any_value() -> Self {
+        Self::new(PatternKind::AnyValue)
+    }
+
+    /// Creates a new pattern matching anything, whether or not there is a node.
+    pub fn any_subtree() -> Self {
+        Self::new(PatternKind::AnySubtree)
+    }
+
+    /// Creates a new pattern matching only when there is no node.
+    pub fn vacant() -> Self {
+        Self::new(PatternKind::Vacant)
+    }
+
+    /// Binds the node matched by this pattern to the specified name. Nothing is bound if there
+    /// is no node.
+    pub fn bind<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.binding = Some(name.into());
+        self
+    }
+
+    /// Adds a pattern for the child at the specified offset.
+    pub fn child(self, child_offset: usize, child: Pattern<T>) -> Self {
+        self.child_at(ChildPosition::At(child_offset), child)
+    }
+
+    /// Adds a pattern which must match any one of the children.
+    pub fn any_child(self, child: Pattern<T>) -> Self {
+        self.child_at(ChildPosition::Any, child)
+    }
+
+    /// Adds a pattern for the child at the specified position.
+    pub fn child_at(mut self, position: ChildPosition, child: Pattern<T>) -> Self {
+        self.children.push((position, child));
+        self
+    }
+
+    /// Gets what this pattern requires of the node at its position.
+    pub fn kind(&self) -> &PatternKind<T> {
+        &self.kind
+    }
+
+    /// Gets the name the node matched by this pattern is bound to.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Gets the child patterns with their positions.
+    pub fn children(&self) -> &[(ChildPosition, Pattern<T>)] {
+        &self.children
+    }
+
+    /// Matches this pattern against the specified node, values are compared with `==`.
+    pub fn matches_at<'a, N>(&self, node: Node<'a, N>) -> Option<Match<'a, '_, N>>
+    where
+        T: PartialEq<N>,
+    {
+        self.matches_at_by(node, |pattern_value, value| pattern_value == value)
+    }
+
+    /// Matches this pattern against the specified node, values are compared with `f`.
+    pub fn matches_at_by<'a, N, F>(&self, node: Node<'a, N>, mut f: F) -> Option<Match<'a, '_, N>>
+    where
+        F: FnMut(&T, &N) -> bool,
+    {
+        let mut bindings = vec![];
+        if self.match_index(node.tree, node.index, &mut f, &mut bindings) {
+            Some(Match { node, bindings })
+        } else {
+            None
+        }
+    }
+
+    /// Finds every node of the tree this pattern matches at, in breadth-first order. Values are
+    /// compared with `==`.
+    pub fn find_all<'a, N>(&self, tree: &'a EytzingerTree<N>) -> Vec<Match<'a, '_, N>>
+    where
+        T: PartialEq<N>,
+    {
+        self.find_all_by(tree, |pattern_value, value| pattern_value == value)
+    }
+
+    /// Finds every node of the tree this pattern matches at, in breadth-first order. Values are
+    /// compared with `f`.
+    pub fn find_all_by<'a, N, F>(
+        &self,
+        tree: &'a EytzingerTree<N>,
+        mut f: F,
+    ) -> Vec<Match<'a, '_, N>>
+    where
+        F: FnMut(&T, &N) -> bool,
+    {
+        tree.breadth_first_iter()
+            .filter_map(|node| self.matches_at_by(node, &mut f))
+            .collect()
+    }
+
+    fn match_index<'a, 'p, N, F>(
+        &'p self,
+        tree: &'a EytzingerTree<N>,
+        index: usize,
+        f: &mut F,
+        bindings: &mut Vec<(&'p str, Node<'a, N>)>,
+    ) -> bool
+    where
+        F: FnMut(&T, &N) -> bool,
+    {
+        let node = tree.node(index);
+
+        let matched = match (&self.kind, node) {
+            (PatternKind::AnySubtree, _) => true,
+            (PatternKind::Vacant, node) => node.is_none(),
+            (_, None) => false,
+            (PatternKind::Value(pattern_value), Some(node)) => f(pattern_value, node.value()),
+            (PatternKind::AnyValue, Some(_)) => true,
+        };
+        if !matched {
+            return false;
+        }
+
+        let bindings_len = bindings.len();
+        if let (Some(binding), Some(node)) = (&self.binding, node) {
+            bindings.push((binding, node));
+        }
+
+        let children_matched = match self.kind {
+            PatternKind::AnySubtree | PatternKind::Vacant => true,
+            PatternKind::Value(_) | PatternKind::AnyValue => {
+                Self::match_children(&self.children, tree, index, f, bindings)
+            }
+        };
+
+        if !children_matched {
+            bindings.truncate(bindings_len);
+        }
+        children_matched
+    }
+
+    fn match_children<'a, 'p, N, F>(
+        children: &'p [(ChildPosition, Pattern<T>)],
+        tree: &'a EytzingerTree<N>,
+        parent_index: usize,
+        f: &mut F,
+        bindings: &mut Vec<(&'p str, Node<'a, N>)>,
+    ) -> bool
+    where
+        F: FnMut(&T, &N) -> bool,
+    {
+        let ((position, child), rest) = match children.split_first() {
+            Some(first) => first,
+            None => return true,
+        };
+
+        let child_offsets = match *position {
+            ChildPosition::At(child_offset) => child_offset..child_offset.saturating_add(1),
+            ChildPosition::Any => 0..tree.max_children_per_node(),
+        };
+
+        for child_offset in child_offsets {
+            let child_index = match tree
+                .index_calculator
+                .checked_child_index(parent_index, child_offset)
+            {
+                Some(child_index) => child_index,
+                None => continue,
+            };
+
+            let bindings_len = bindings.len();
+            if child.match_index(tree, child_index, f, bindings)
+                && Self::match_children(rest, tree, parent_index, f, bindings)
+            {
+                return true;
+            }
+            bindings.truncate(bindings_len);
+        }
+
+        false
+    }
+}
+
+type Build<N> = dyn Fn(&Match<'_, '_, N>) -> EytzingerTree<N>;
+
+/// A rewrite rule which replaces the subtrees matching a pattern with the tree built from the
+/// match, see `EytzingerTree::rewrite`.
+pub struct Rule<N> {
+    pattern: Pattern<N>,
+    build: Box<Build<N>>,
+}
+
+impl<N> Rule<N> {
+    /// Creates a new rule which replaces subtrees matching `pattern` with the tree returned by
+    /// `build`, an empty tree removes the subtree. The built tree must have the same
+    /// `max_children_per_node` as the tree being rewritten.
+    pub fn new<F>(pattern: Pattern<N>, build: F) -> Self
+    where
+        F: Fn(&Match<'_, '_, N>) -> EytzingerTree<N> + 'static,
+    {
+        Self {
+            pattern,
+            build: Box::new(build),
+        }
+    }
+
+    /// Gets the pattern this rule matches.
+    pub fn pattern(&self) -> &Pattern<N> {
+        &self.pattern
+    }
+
+    /// Builds the replacement for the subtree rooted at `node` if this rule matches it.
+    pub(crate) fn apply(&self, node: Node<'_, N>) -> Option<EytzingerTree<N>>
+    where
+        N: PartialEq,
+    {
+        self.pattern.matches_at(node).map(|m| (self.build)(&m))
+    }
+}
+
+impl<N> Debug for Rule<N>
+where
+    N: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule")
+            .field("pattern", &self.pattern)
+            .finish()
+    }
+}
+
+/// Where a pattern matched and the nodes bound by it.
+#[derive(Debug)]
+pub struct Match<'a, 'p, N> {
+    node: Node<'a, N>,
+    bindings: Vec<(&'p str, Node<'a, N>)>,
+}
+
+impl<'a, 'p, N> Match<'a, 'p, N> {
+    /// Gets the node the root of the pattern matched.
+    pub fn node(&self) -> Node<'a, N> {
+        self.node
+    }
+
+    /// Gets the node bound to the specified name.
+    pub fn get(&self, name: &str) -> Option<Node<'a, N>> {
+        self.bindings
+            .iter()
+            .find(|(binding, _)| *binding == name)
+            .map(|(_, node)| *node)
+    }
+
+    /// Gets an iterator over every bound name and node in the order they were bound.
+    pub fn bindings(&self) -> impl Iterator<Item = (&'p str, Node<'a, N>)> + '_ {
+        self.bindings.iter().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{query::Pattern, EytzingerTree};
+
+    // (a * b) + (c * 1)
+    fn expression() -> EytzingerTree<&'static str> {
+        let mut tree = EytzingerTree::new(2);
+        {
+            let mut add = tree.set_root_value("+");
+            {
+                let mut left = add.set_child_value(0, "*");
+                left.set_child_value(0, "a");
+                left.set_child_value(1, "b");
+            }
+            let mut right = add.set_child_value(1, "*");
+            right.set_child_value(0, "c");
+            right.set_child_value(1, "1");
+        }
+        tree
+    }
+
+    #[test]
+    fn find_all_returns_every_location() {
+        let tree = expression();
+        let pattern = Pattern::value("*")
+            .child(0, Pattern::any_value().bind("lhs"))
+            .child(1, Pattern::any_value().bind("rhs"));
+
+        let matches = pattern.find_all(&tree);
+        let bound: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                (
+                    *m.get("lhs").unwrap().value(),
+                    *m.get("rhs").unwrap().value(),
+                )
+            })
+            .collect();
+        assert_eq!(bound, vec![("a", "b"), ("c", "1")]);
+        assert_eq!(matches[1].node().path().to_string(), "/1");
+    }
+
+    #[test]
+    fn any_child_tries_each_position() {
+        let tree = expression();
+        let pattern = Pattern::value("*").any_child(Pattern::value("1").bind("one"));
+
+        let matches = pattern.find_all(&tree);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].get("one").unwrap().path().to_string(), "/1/1");
+    }
+
+    #[test]
+    fn vacant_and_any_subtree() {
+        let tree = expression();
+
+        let leaf = Pattern::<&str>::any_value()
+            .child(0, Pattern::vacant())
+            .child(1, Pattern::vacant());
+        assert_eq!(leaf.find_all(&tree).len(), 4);
+
+        let anything = Pattern::<&str>::any_value().child(0, Pattern::any_subtree().bind("left"));
+        let matches = anything.find_all(&tree);
+        assert_eq!(matches.len(), 7);
+        assert_eq!(
+            matches.iter().filter(|m| m.get("left").is_some()).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn failed_branches_do_not_leave_bindings() {
+        let tree = expression();
+        let pattern = Pattern::value("+").any_child(
+            Pattern::any_value()
+                .bind("product")
+                .child(1, Pattern::value("1")),
+        );
+
+        let m = pattern.matches_at(tree.root().unwrap()).unwrap();
+        let bindings: Vec<_> = m
+            .bindings()
+            .map(|(name, node)| (name, node.index()))
+            .collect();
+        assert_eq!(bindings, vec![("product", 2)]);
+    }
+
+    #[test]
+    fn matches_at_by_compares_with_the_closure() {
+        let tree = expression();
+        let pattern = Pattern::value(1usize);
+
+        let lengths: Vec<_> = pattern
+            .find_all_by(&tree, |len, value| value.len() == *len)
+            .iter()
+            .map(|m| *m.node().value())
+            .collect();
+        assert_eq!(lengths.len(), 7);
+    }
+
+    #[test]
+    fn rewrite_replaces_matches_bottom_up() {
+        use crate::query::Rule;
+
+        // a * 1 => a, c * 1 => c
+        let mut tree = expression();
+        let times_one = Rule::new(
+            Pattern::value("*")
+                .child(0, Pattern::any_subtree().bind("x"))
+                .child(1, Pattern::value("1")),
+            |m| m.get("x").unwrap().to_tree(),
+        );
+        // b => n with b' as its right child, growing the tree where b was
+        let nest = Rule::new(Pattern::value("b"), |_| {
+            let mut nested = EytzingerTree::new(2);
+            nested.set_root_value("n").set_child_value(1, "b'");
+            nested
+        });
+
+        assert_eq!(tree.rewrite(&[times_one, nest]), 2);
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec!["+", "*", "c", "a", "n", "b'"]);
+        assert_eq!(
+            tree.root()
+                .and_then(|n| n.child(0))
+                .and_then(|n| n.child(1))
+                .and_then(|n| n.child(1))
+                .map(|n| *n.value()),
+            Some("b'")
+        );
+    }
+
+    #[test]
+    fn rewrite_with_an_empty_tree_removes_the_subtree() {
+        use crate::query::Rule;
+
+        let mut tree = expression();
+        let remove_products = Rule::new(Pattern::value("*"), |_| EytzingerTree::new(2));
+
+        assert_eq!(tree.rewrite(&[remove_products]), 2);
+        assert_eq!(tree.len(), 1);
+    }
+}
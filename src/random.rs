@@ -0,0 +1,152 @@
+//! Random trees and random changes to trees, for testing and benchmarking code over many shapes
+//! of tree.
+
+use crate::EytzingerTree;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::VecDeque;
+
+impl<N> EytzingerTree<N> {
+    /// Moves the children of every node to a random permutation of the child offsets, the
+    /// descendants of each child move along with it. Each node's children are permuted
+    /// independently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// tree.set_root_value(1).set_child_value(0, 2).set_child_value(0, 3);
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let mut shuffled = tree.clone();
+    /// shuffled.shuffle_children(&mut rng);
+    ///
+    /// assert_eq!(shuffled.len(), 3);
+    /// let values: Vec<_> = shuffled.breadth_first_iter().map(|n| *n.value()).collect();
+    /// assert_eq!(values, vec![1, 2, 3]);
+    /// ```
+    pub fn shuffle_children<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut child_offsets: Vec<_> = (0..self.max_children_per_node()).collect();
+
+        // each node is moved to a position at the same depth so no index can overflow
+        let mut pending = VecDeque::new();
+        if matches!(self.nodes.first(), Some(Some(_))) {
+            pending.push_back((0, 0));
+        }
+        while let Some((index, new_index)) = pending.pop_front() {
+            child_offsets.shuffle(rng);
+            for (child_offset, &new_child_offset) in child_offsets.iter().enumerate() {
+                if let Some(child_index) = self.occupied_child_index(index, child_offset) {
+                    let new_child_index = self.child_index(new_index, new_child_offset);
+                    pending.push_back((child_index, new_child_index));
+                }
+            }
+
+            if new_index >= nodes.len() {
+                nodes.resize_with(new_index + 1, || None);
+            }
+            nodes[new_index] = self.nodes[index].take();
+        }
+
+        self.nodes = nodes;
+        self.bump_generation();
+        if let Some(versions) = &mut self.versions {
+            versions.forget_indexes();
+        }
+        self.record_modified(0..self.nodes.len());
+    }
+}
+
+/// Creates a new tree with the number of nodes, each node after the root is placed at a vacant
+/// child of an existing node chosen uniformly at random. The value of each node is created by
+/// `value_factory` in the order the nodes are placed.
+///
+/// The depth of the tree grows with the logarithm of the number of nodes on average, as does
+/// the depth of a tree grown by inserting into random positions. Positions too deep to be
+/// stored are never chosen.
+///
+/// # Panics
+///
+/// Panics if `max_children_per_node` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::random::random_tree;
+/// use rand::{rngs::StdRng, Rng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let tree = random_tree(&mut rng, 4, 100, |rng| rng.gen_range(0..10u32));
+///
+/// assert_eq!(tree.len(), 100);
+/// assert_eq!(tree.max_children_per_node(), 4);
+/// ```
+pub fn random_tree<N, R, F>(
+    rng: &mut R,
+    max_children_per_node: usize,
+    node_count: usize,
+    mut value_factory: F,
+) -> EytzingerTree<N>
+where
+    R: Rng + ?Sized,
+    F: FnMut(&mut R) -> N,
+{
+    let mut tree = EytzingerTree::new(max_children_per_node);
+
+    // every position a node may be placed at
+    let mut vacant = vec![0];
+    while tree.len() < node_count && !vacant.is_empty() {
+        let index = vacant.swap_remove(rng.gen_range(0..vacant.len()));
+        let value = value_factory(rng);
+        tree.replace_value(index, value);
+
+        vacant.extend((0..max_children_per_node).map_while(|child_offset| {
+            tree.index_calculator
+                .checked_child_index(index, child_offset)
+        }));
+    }
+
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DepthFirstOrder;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn shuffle_children_keeps_the_children_of_every_node() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let tree = random_tree(&mut rng, 3, 200, |_| ());
+        let tree = tree.fold_up(|_, children: &[usize]| 1 + children.iter().sum::<usize>());
+
+        let mut shuffled = tree.clone();
+        shuffled.shuffle_children(&mut rng);
+        assert_eq!(shuffled.len(), tree.len());
+        assert_ne!(shuffled, tree);
+
+        // each value is the size of its subtree, which only stays true if subtrees move whole
+        for node in shuffled.depth_first_iter(DepthFirstOrder::PreOrder) {
+            let children: usize = node.child_iter().map(|child| *child.value()).sum();
+            assert_eq!(*node.value(), 1 + children);
+        }
+    }
+
+    #[test]
+    fn random_tree_stops_when_no_position_can_be_stored() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let tree = random_tree(&mut rng, 2, 0, |_| ());
+        assert!(tree.is_empty());
+
+        let tree = random_tree(&mut rng, 1, 50, |_| ());
+        assert_eq!(tree.len(), 50);
+        assert!(tree.is_dense());
+    }
+}
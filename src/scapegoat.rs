@@ -0,0 +1,413 @@
+use crate::{traversal::InOrderIter, EytzingerTree};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    iter::{FromIterator, FusedIterator},
+};
+
+/// The default balance factor of a `ScapegoatTree`.
+pub const DEFAULT_ALPHA: f64 = 0.55;
+
+/// A self-balancing ordered set stored in a binary Eytzinger tree.
+///
+/// Values are inserted as leaves of a binary search tree. Whenever a leaf ends up deeper than
+/// `log(len) / log(1 / alpha)` the nearest ancestor whose larger child subtree holds more than
+/// `alpha` of its values, the scapegoat, is rebuilt as a perfectly balanced subtree. A removed value
+/// is swapped down with its in-order predecessor or successor until it is a leaf, so removing only
+/// moves the values along a single path, and the whole tree is rebuilt once it shrinks below
+/// `alpha` of its largest size since the last full rebuild.
+///
+/// The storage of an Eytzinger tree grows exponentially with its height, which is why the default
+/// `alpha` is much closer to `0.5` than the `2 / 3` often used for pointer-based scapegoat trees.
+#[derive(Clone)]
+pub struct ScapegoatTree<T> {
+    tree: EytzingerTree<T>,
+    alpha: f64,
+    max_len: usize,
+}
+
+impl<T> ScapegoatTree<T> {
+    /// Creates a new empty tree with the default balance factor.
+    pub fn new() -> Self {
+        Self::with_alpha(DEFAULT_ALPHA)
+    }
+
+    /// Creates a new empty tree with the specified balance factor. Smaller values keep the tree
+    /// shallower, and so its storage smaller, at the cost of rebuilding more often.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not strictly between `0.5` and `1`.
+    pub fn with_alpha(alpha: f64) -> Self {
+        assert!(
+            alpha > 0.5 && alpha < 1.0,
+            "alpha should be between 0.5 and 1"
+        );
+
+        Self {
+            tree: EytzingerTree::new(2),
+            alpha,
+            max_len: 0,
+        }
+    }
+
+    /// Gets the balance factor of this tree.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Gets the number of values in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Gets whether the tree has no values.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Removes every value from the tree.
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.max_len = 0;
+    }
+
+    /// Gets an iterator over the values of the tree in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.tree.in_order_iter(),
+        }
+    }
+
+    /// Gets the binary Eytzinger tree the values are stored in.
+    pub fn tree(&self) -> &EytzingerTree<T> {
+        &self.tree
+    }
+
+    /// Gets the greatest depth a value may be inserted at without rebuilding.
+    fn max_depth(&self) -> usize {
+        ((self.len() as f64).ln() / (1.0 / self.alpha).ln()).floor() as usize
+    }
+}
+
+impl<T> ScapegoatTree<T>
+where
+    T: Ord,
+{
+    /// Gets whether the tree contains the specified value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(value).is_ok()
+    }
+
+    /// Adds a value to the tree, returning whether it was not already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::scapegoat::ScapegoatTree;
+    ///
+    /// let mut tree = ScapegoatTree::new();
+    /// for value in 0..100 {
+    ///     assert!(tree.insert(value));
+    /// }
+    /// assert!(!tree.insert(50));
+    ///
+    /// assert_eq!(tree.len(), 100);
+    /// assert!(tree.tree().is_search_tree(Ord::cmp));
+    /// assert!(tree.tree().breadth_first_iter().all(|n| n.path().depth() < 10));
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        let index = match self.find(&value) {
+            Ok(_) => return false,
+            Err(index) => index,
+        };
+
+        self.tree.set_value(index, value);
+        self.max_len = self.max_len.max(self.len());
+
+        if self.tree.path(index).depth() > self.max_depth() {
+            self.rebuild_scapegoat(index);
+        }
+
+        true
+    }
+
+    /// Removes a value from the tree, returning whether it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::scapegoat::ScapegoatTree;
+    ///
+    /// let mut tree: ScapegoatTree<_> = (0..10).collect();
+    /// assert!(tree.remove(&3));
+    /// assert!(!tree.remove(&3));
+    ///
+    /// assert_eq!(tree.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 4, 5, 6, 7, 8, 9]);
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = match self.find(value) {
+            Ok(index) => index,
+            Err(_) => return false,
+        };
+
+        // swapping with an in-order neighbour keeps the order of every other value
+        let mut moved = vec![];
+        let mut leaf = index;
+        while let Some(neighbour) = self.in_order_neighbour(leaf) {
+            self.tree.nodes.swap(leaf, neighbour);
+            moved.push(leaf);
+            leaf = neighbour;
+        }
+
+        let parent = self.tree.parent_index(leaf);
+        self.tree.nodes[leaf] = None;
+        self.tree.len -= 1;
+        self.tree.bump_generation();
+        self.tree.record_modified(moved.into_iter().chain(parent));
+
+        if (self.len() as f64) < self.alpha * self.max_len as f64 {
+            self.rebuild(0);
+            self.max_len = self.len();
+        }
+
+        true
+    }
+
+    /// Finds the index of the value or the vacant index it would be inserted at.
+    fn find<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut index = 0;
+
+        while let Some(Some(current)) = self.tree.nodes.get(index) {
+            index = match value.cmp(current.borrow()) {
+                Ordering::Less => self.tree.child_index(index, 0),
+                Ordering::Equal => return Ok(index),
+                Ordering::Greater => self.tree.child_index(index, 1),
+            };
+        }
+
+        Err(index)
+    }
+
+    /// Rebuilds the nearest ancestor of the too deep node which is not alpha-weight-balanced.
+    fn rebuild_scapegoat(&mut self, index: usize) {
+        let mut child = index;
+        let mut child_len = 1;
+
+        while let Some(parent) = self.tree.parent_index(child) {
+            let child_offset = child - self.tree.child_index(parent, 0);
+            let sibling = self.tree.child_index(parent, 1 - child_offset);
+            let sibling_len = self.tree.node(sibling).map_or(0, |n| n.subtree_len());
+            let parent_len = child_len + sibling_len + 1;
+
+            if child_len as f64 > self.alpha * parent_len as f64 {
+                self.rebuild(parent);
+                return;
+            }
+
+            child = parent;
+            child_len = parent_len;
+        }
+    }
+}
+
+impl<T> ScapegoatTree<T> {
+    /// Rebuilds the subtree at the index as a perfectly balanced subtree. The values of a search
+    /// tree are already in order so they are not sorted.
+    fn rebuild(&mut self, index: usize) {
+        let mut subtree = self.tree.split_off(index);
+        let in_order: Vec<_> = subtree.in_order_iter().map(|n| n.index()).collect();
+        let values = in_order
+            .into_iter()
+            .map(|subtree_index| subtree.nodes[subtree_index].take())
+            .collect();
+
+        self.tree.build_balanced(index, values);
+    }
+
+    /// Gets the index of the in-order predecessor of the node, or its in-order successor if it
+    /// has no left child. `None` if the node is a leaf.
+    fn in_order_neighbour(&self, index: usize) -> Option<usize> {
+        let (child_offset, extreme_offset) = [(0, 1), (1, 0)]
+            .into_iter()
+            .find(|&(child_offset, _)| self.tree.child(index, child_offset).is_some())?;
+
+        let mut neighbour = self.tree.child_index(index, child_offset);
+        while self.tree.child(neighbour, extreme_offset).is_some() {
+            neighbour = self.tree.child_index(neighbour, extreme_offset);
+        }
+
+        Some(neighbour)
+    }
+}
+
+impl<T> Default for ScapegoatTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for ScapegoatTree<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Extend<T> for ScapegoatTree<T>
+where
+    T: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ScapegoatTree<T>
+where
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ScapegoatTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a `ScapegoatTree` in ascending order.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    inner: InOrderIter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|n| n.value())
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::scapegoat::ScapegoatTree;
+
+    fn height<T>(tree: &ScapegoatTree<T>) -> usize {
+        tree.tree()
+            .breadth_first_iter()
+            .last()
+            .map_or(0, |n| n.path().depth())
+    }
+
+    #[test]
+    fn ascending_inserts_stay_shallow() {
+        let tree: ScapegoatTree<_> = (0..1_000).collect();
+
+        assert_eq!(tree.len(), 1_000);
+        assert!(tree.tree().is_search_tree(Ord::cmp));
+        assert!(height(&tree) <= 12);
+        assert_eq!(
+            tree.iter().cloned().collect::<Vec<_>>(),
+            (0..1_000).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn removes_keep_the_remaining_values() {
+        let mut tree = ScapegoatTree::with_alpha(0.6);
+        for value in 0..500u32 {
+            tree.insert(value.wrapping_mul(2_654_435_761) % 1_000);
+        }
+
+        for value in 0..1_000 {
+            if value % 3 != 0 {
+                tree.remove(&value);
+            }
+        }
+
+        assert!(tree.tree().is_search_tree(Ord::cmp));
+        assert!(tree.iter().all(|v| v % 3 == 0));
+        assert_eq!(tree.len(), tree.iter().count());
+        for value in 0..1_000 {
+            if value % 3 == 0 {
+                assert_eq!(tree.contains(&value), tree.iter().any(|v| *v == value));
+            }
+        }
+    }
+
+    #[test]
+    fn removing_only_moves_values_along_one_path() {
+        let mut tree: ScapegoatTree<u32> = (0..1_000).collect();
+        let indexes = |tree: &ScapegoatTree<u32>| -> Vec<(u32, usize)> {
+            let mut indexes: Vec<_> = tree
+                .tree()
+                .breadth_first_iter()
+                .map(|n| (*n.value(), n.index()))
+                .collect();
+            indexes.sort_unstable();
+            indexes
+        };
+
+        let before = indexes(&tree);
+        let root = *tree.tree().root().unwrap().value();
+        assert!(tree.remove(&root));
+
+        let after = indexes(&tree);
+        let moved = after.iter().filter(|moved| !before.contains(moved)).count();
+        assert!(moved <= height(&tree) + 1);
+        assert!(tree.tree().is_search_tree(Ord::cmp));
+        assert_eq!(tree.len(), 999);
+    }
+
+    #[test]
+    fn removing_everything_empties_the_tree() {
+        let mut tree: ScapegoatTree<_> = (0..64).rev().collect();
+
+        for value in 0..64 {
+            assert!(tree.remove(&value));
+        }
+
+        assert!(tree.is_empty());
+        assert!(tree.tree().root().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_alpha_panics_for_unbalanced_factors() {
+        ScapegoatTree::<u32>::with_alpha(0.5);
+    }
+}
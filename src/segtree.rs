@@ -0,0 +1,279 @@
+use crate::{EytzingerIndexCalculator, EytzingerTree};
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+/// A monoid used to combine the values stored in a `SegmentTree`.
+pub trait Op<T> {
+    /// Gets the identity value, combining any value with the identity must yield that value.
+    fn identity() -> T;
+
+    /// Combines two values. This must be associative but need not be commutative, `left` always
+    /// covers the lower positions.
+    fn combine(left: &T, right: &T) -> T;
+}
+
+/// A static segment tree stored in a binary Eytzinger tree.
+///
+/// The leaves hold the original values and every parent holds the combination of its two
+/// children, this allows any range of values to be combined in `O(log n)`.
+pub struct SegmentTree<T, O> {
+    tree: EytzingerTree<T>,
+    len: usize,
+    leaf_count: usize,
+    op: PhantomData<O>,
+}
+
+impl<T, O> SegmentTree<T, O>
+where
+    O: Op<T>,
+{
+    /// Creates a new segment tree over the specified values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::segtree::{Op, SegmentTree};
+    ///
+    /// struct Sum;
+    ///
+    /// impl Op<u32> for Sum {
+    ///     fn identity() -> u32 {
+    ///         0
+    ///     }
+    ///
+    ///     fn combine(left: &u32, right: &u32) -> u32 {
+    ///         left + right
+    ///     }
+    /// }
+    ///
+    /// let segment_tree = SegmentTree::<u32, Sum>::from_slice(&[1, 2, 3, 4, 5]);
+    /// assert_eq!(segment_tree.query(1..4), 9);
+    /// ```
+    pub fn from_slice(values: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let leaf_count = values.len().next_power_of_two();
+        let first_leaf = leaf_count - 1;
+
+        let mut nodes: Vec<Option<T>> = Vec::with_capacity(first_leaf + leaf_count);
+        nodes.resize_with(first_leaf, || None);
+        nodes.extend(values.iter().cloned().map(Some));
+        nodes.resize_with(first_leaf + leaf_count, || Some(O::identity()));
+
+        for index in (0..first_leaf).rev() {
+            nodes[index] = Some(Self::combine_children(&nodes, index));
+        }
+
+        Self {
+            tree: EytzingerTree {
+                len: nodes.len(),
+                nodes,
+                index_calculator: EytzingerIndexCalculator::new(2),
+                tree_id: crate::next_tree_id(),
+                generation: 0,
+                versions: None,
+            },
+            len: values.len(),
+            leaf_count,
+            op: PhantomData,
+        }
+    }
+
+    /// Gets the number of values in the segment tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets whether the segment tree has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the value at the specified position or `None` if the position is out of range.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            self.tree.nodes[self.leaf_index(index)].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Gets the underlying Eytzinger tree, the leaves are at the deepest level and each parent
+    /// holds the combination of its children.
+    pub fn tree(&self) -> &EytzingerTree<T> {
+        &self.tree
+    }
+
+    /// Combines all of the values within the specified range. An empty range yields the identity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn query<R>(&self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflow"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflow"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end,
+            "the range start should not exceed the range end"
+        );
+        assert!(
+            end <= self.len,
+            "the range end should not exceed the length"
+        );
+
+        // these positions are 1-based so siblings only differ in their lowest bit
+        let mut left_position = start + self.leaf_count;
+        let mut right_position = end + self.leaf_count;
+
+        let mut left_value = O::identity();
+        let mut right_value = O::identity();
+
+        while left_position < right_position {
+            if left_position & 1 == 1 {
+                left_value = O::combine(&left_value, self.position_value(left_position));
+                left_position += 1;
+            }
+            if right_position & 1 == 1 {
+                right_position -= 1;
+                right_value = O::combine(self.position_value(right_position), &right_value);
+            }
+            left_position >>= 1;
+            right_position >>= 1;
+        }
+
+        O::combine(&left_value, &right_value)
+    }
+
+    /// Updates the value at the specified position, recomputing all of its ancestors.
+    ///
+    /// # Returns
+    ///
+    /// The old value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn update(&mut self, index: usize, value: T) -> T {
+        assert!(index < self.len, "the index should be less than the length");
+
+        let leaf_index = self.leaf_index(index);
+        let old_value = self.tree.nodes[leaf_index]
+            .replace(value)
+            .expect("every leaf should have a value");
+
+        let mut current = leaf_index;
+        while let Some(parent) = self.tree.parent_index(current) {
+            let combined = Self::combine_children(&self.tree.nodes, parent);
+            self.tree.nodes[parent] = Some(combined);
+            current = parent;
+        }
+
+        old_value
+    }
+
+    fn leaf_index(&self, index: usize) -> usize {
+        self.leaf_count - 1 + index
+    }
+
+    fn position_value(&self, position: usize) -> &T {
+        self.tree.nodes[position - 1]
+            .as_ref()
+            .expect("every node should have a value")
+    }
+
+    fn combine_children(nodes: &[Option<T>], parent: usize) -> T {
+        let left = nodes[2 * parent + 1]
+            .as_ref()
+            .expect("every node should have a value");
+        let right = nodes[2 * parent + 2]
+            .as_ref()
+            .expect("every node should have a value");
+
+        O::combine(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segtree::{Op, SegmentTree};
+
+    struct Sum;
+
+    impl Op<u32> for Sum {
+        fn identity() -> u32 {
+            0
+        }
+
+        fn combine(left: &u32, right: &u32) -> u32 {
+            left + right
+        }
+    }
+
+    struct Concat;
+
+    impl Op<String> for Concat {
+        fn identity() -> String {
+            String::new()
+        }
+
+        fn combine(left: &String, right: &String) -> String {
+            format!("{}{}", left, right)
+        }
+    }
+
+    #[test]
+    fn query_combines_every_range() {
+        let values = [3, 1, 4, 1, 5, 9, 2];
+        let segment_tree = SegmentTree::<u32, Sum>::from_slice(&values);
+
+        for start in 0..=values.len() {
+            for end in start..=values.len() {
+                let expected: u32 = values[start..end].iter().sum();
+                assert_eq!(segment_tree.query(start..end), expected);
+            }
+        }
+        assert_eq!(segment_tree.query(..), 25);
+    }
+
+    #[test]
+    fn query_preserves_order() {
+        let values: Vec<_> = "segment".chars().map(|c| c.to_string()).collect();
+        let segment_tree = SegmentTree::<String, Concat>::from_slice(&values);
+
+        assert_eq!(segment_tree.query(2..6), "gmen");
+        assert_eq!(segment_tree.query(..=2), "seg");
+    }
+
+    #[test]
+    fn update_recomputes_ancestors() {
+        let mut segment_tree = SegmentTree::<u32, Sum>::from_slice(&[1, 2, 3, 4, 5]);
+
+        assert_eq!(segment_tree.update(2, 10), 3);
+        assert_eq!(segment_tree.get(2), Some(&10));
+        assert_eq!(segment_tree.query(..), 22);
+        assert_eq!(segment_tree.query(2..3), 10);
+        assert_eq!(segment_tree.query(3..), 9);
+    }
+
+    #[test]
+    fn empty_query_is_identity() {
+        let segment_tree = SegmentTree::<u32, Sum>::from_slice(&[]);
+
+        assert!(segment_tree.is_empty());
+        assert_eq!(segment_tree.query(..), 0);
+    }
+}
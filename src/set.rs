@@ -0,0 +1,313 @@
+use crate::EytzingerTree;
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug},
+    iter::{FromIterator, FusedIterator},
+};
+
+/// An ordered set stored in a complete binary Eytzinger tree.
+///
+/// The values are laid out so that an in-order traversal of the tree yields them in ascending
+/// order, this makes lookups a cache-friendly descent from the root. The set is built in one go
+/// from its values and is not modified afterwards.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EytzingerSet<T> {
+    tree: EytzingerTree<T>,
+}
+
+impl<T> EytzingerSet<T> {
+    /// Creates a new empty set.
+    pub fn new() -> Self {
+        Self {
+            tree: EytzingerTree::new(2),
+        }
+    }
+
+    /// Gets the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Gets whether the set has no values.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Gets an iterator over the values of the set in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.tree.nodes)
+    }
+
+    /// Gets the binary Eytzinger tree the values are stored in.
+    pub fn tree(&self) -> &EytzingerTree<T> {
+        &self.tree
+    }
+}
+
+impl<T> EytzingerSet<T>
+where
+    T: Ord,
+{
+    /// Creates a new set from values which are already sorted in ascending order. Repeated values
+    /// are only included once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values are not sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::set::EytzingerSet;
+    ///
+    /// let set = EytzingerSet::from_sorted_iter(vec![1, 2, 2, 3, 5, 8]);
+    ///
+    /// assert_eq!(set.len(), 5);
+    /// assert!(set.contains(&3));
+    /// assert!(!set.contains(&4));
+    /// ```
+    pub fn from_sorted_iter<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut sorted: Vec<T> = Vec::new();
+        for value in values {
+            if let Some(last) = sorted.last() {
+                match last.cmp(&value) {
+                    Ordering::Less => {}
+                    Ordering::Equal => continue,
+                    Ordering::Greater => panic!("the values should be sorted"),
+                }
+            }
+            sorted.push(value);
+        }
+
+        Self::from_sorted_vec(sorted)
+    }
+
+    /// Gets whether the set contains the specified value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.tree
+            .search_by(|current| current.borrow().cmp(value))
+            .is_some()
+    }
+
+    /// Creates a new set containing the values which are in either this set or `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::set::EytzingerSet;
+    ///
+    /// let a: EytzingerSet<_> = vec![1, 3, 5].into_iter().collect();
+    /// let b: EytzingerSet<_> = vec![3, 4].into_iter().collect();
+    ///
+    /// let union: Vec<_> = a.union(&b).iter().cloned().collect();
+    /// assert_eq!(union, vec![1, 3, 4, 5]);
+    /// ```
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let mut values = Vec::with_capacity(self.len() + other.len());
+        let mut left = self.iter().peekable();
+        let mut right = other.iter().peekable();
+
+        loop {
+            let next = match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) => match l.cmp(r) {
+                    Ordering::Less => left.next(),
+                    Ordering::Equal => {
+                        right.next();
+                        left.next()
+                    }
+                    Ordering::Greater => right.next(),
+                },
+                (Some(_), None) => left.next(),
+                (None, Some(_)) => right.next(),
+                (None, None) => break,
+            };
+            values.extend(next.cloned());
+        }
+
+        Self::from_sorted_vec(values)
+    }
+
+    /// Creates a new set containing the values which are in both this set and `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::set::EytzingerSet;
+    ///
+    /// let a: EytzingerSet<_> = vec![1, 3, 5].into_iter().collect();
+    /// let b: EytzingerSet<_> = vec![3, 4, 5].into_iter().collect();
+    ///
+    /// let intersection: Vec<_> = a.intersection(&b).iter().cloned().collect();
+    /// assert_eq!(intersection, vec![3, 5]);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let mut values = Vec::with_capacity(self.len().min(other.len()));
+        let mut left = self.iter().peekable();
+        let mut right = other.iter().peekable();
+
+        while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+            match l.cmp(r) {
+                Ordering::Less => {
+                    left.next();
+                }
+                Ordering::Equal => {
+                    values.push((*l).clone());
+                    left.next();
+                    right.next();
+                }
+                Ordering::Greater => {
+                    right.next();
+                }
+            }
+        }
+
+        Self::from_sorted_vec(values)
+    }
+
+    /// Lays out already sorted and deduplicated values so an in-order traversal yields them in
+    /// order.
+    fn from_sorted_vec(sorted: Vec<T>) -> Self {
+        Self {
+            tree: EytzingerTree::from_sorted_iter(sorted),
+        }
+    }
+}
+
+impl<T> Default for EytzingerSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for EytzingerSet<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for EytzingerSet<T>
+where
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut values: Vec<_> = iter.into_iter().collect();
+        values.sort();
+        values.dedup();
+
+        Self::from_sorted_vec(values)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a EytzingerSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of an `EytzingerSet` in ascending order.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    nodes: &'a [Option<T>],
+    pending: Vec<usize>,
+    current: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(nodes: &'a [Option<T>]) -> Self {
+        Self {
+            nodes,
+            pending: vec![],
+            current: 0,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(Some(_)) = self.nodes.get(self.current) {
+            self.pending.push(self.current);
+            self.current = 2 * self.current + 1;
+        }
+
+        let index = self.pending.pop()?;
+        self.current = 2 * index + 2;
+
+        self.nodes[index].as_ref()
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::set::EytzingerSet;
+
+    #[test]
+    fn iter_returns_ascending_order() {
+        for len in 0..40 {
+            let set: EytzingerSet<_> = (0..len).rev().collect();
+
+            assert_eq!(set.len(), len);
+            assert_eq!(
+                set.iter().cloned().collect::<Vec<_>>(),
+                (0..len).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn contains_finds_every_value() {
+        let set: EytzingerSet<_> = (0..100).map(|v| v * 2).collect();
+
+        for value in 0..200 {
+            assert_eq!(set.contains(&value), value % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter_removes_repeated_values() {
+        let set = EytzingerSet::from_sorted_iter(vec![1, 1, 2, 3, 3, 3]);
+
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_sorted_iter_panics_for_unsorted_values() {
+        EytzingerSet::from_sorted_iter(vec![2, 1]);
+    }
+
+    #[test]
+    fn contains_supports_borrowed_values() {
+        let set: EytzingerSet<String> =
+            vec!["b".to_string(), "a".to_string()].into_iter().collect();
+
+        assert!(set.contains("a"));
+        assert!(!set.contains("c"));
+    }
+}
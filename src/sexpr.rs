@@ -0,0 +1,255 @@
+use crate::{Error, EytzingerTree, Node};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'s> {
+    Open,
+    Close,
+    Atom(&'s str),
+}
+
+const VACANT: &str = "_";
+
+impl<N> EytzingerTree<N> {
+    /// Creates a new Eytzinger tree from an s-expression such as `(5 (2 1 (4 3 _)) (7 _ 8))`.
+    ///
+    /// A node with children is a list of its value followed by its children, a leaf may be written
+    /// as just its value and `_` is a vacant child. Vacant children at the end of a list may be
+    /// left out and an empty tree is written as `_`. Each value is parsed from its atom by
+    /// `parse_value`.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::InvalidSexpr` if the text is not a single tree or `parse_value` fails.
+    /// * `Error::ChildIndexOutOfRange` if a list has more than the max children per node.
+    /// * `Error::DepthOverflow` if a node would be too deep to be indexed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_children_per_node` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let tree = EytzingerTree::<u32>::from_sexpr(2, "(5 (2 1 (4 3)) (7 _ 8))", |atom| {
+    ///     atom.parse().ok()
+    /// })
+    /// .unwrap();
+    ///
+    /// assert!(tree.is_search_tree(Ord::cmp));
+    /// assert_eq!(
+    ///     tree.to_sexpr(|value| value.to_string()),
+    ///     "(5 (2 1 (4 3 _)) (7 _ 8))"
+    /// );
+    /// ```
+    pub fn from_sexpr<F>(
+        max_children_per_node: usize,
+        text: &str,
+        mut parse_value: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&str) -> Option<N>,
+    {
+        let mut tree = Self::new(max_children_per_node);
+        let mut tokens = tokenize(text).into_iter();
+        // the index and the number of children so far of each list which has not been closed
+        let mut open_lists: Vec<(usize, usize)> = vec![];
+        let mut has_root = false;
+
+        while let Some((position, token)) = tokens.next() {
+            match token {
+                Token::Close => {
+                    open_lists.pop().ok_or(Error::InvalidSexpr { position })?;
+                }
+                Token::Atom(VACANT) => {
+                    tree.next_sexpr_index(&mut open_lists, &mut has_root, position)?;
+                }
+                Token::Atom(atom) => {
+                    let index = tree.next_sexpr_index(&mut open_lists, &mut has_root, position)?;
+                    let value = parse_value(atom).ok_or(Error::InvalidSexpr { position })?;
+                    tree.set_value(index, value);
+                }
+                Token::Open => {
+                    let index = tree.next_sexpr_index(&mut open_lists, &mut has_root, position)?;
+                    let value = match tokens.next() {
+                        Some((_, Token::Atom(atom))) if atom != VACANT => {
+                            parse_value(atom).ok_or(Error::InvalidSexpr { position })?
+                        }
+                        Some((position, _)) => return Err(Error::InvalidSexpr { position }),
+                        None => {
+                            return Err(Error::InvalidSexpr {
+                                position: text.len(),
+                            })
+                        }
+                    };
+                    tree.set_value(index, value);
+                    open_lists.push((index, 0));
+                }
+            }
+        }
+
+        if has_root && open_lists.is_empty() {
+            Ok(tree)
+        } else {
+            Err(Error::InvalidSexpr {
+                position: text.len(),
+            })
+        }
+    }
+
+    /// Gets the s-expression of this tree, the inverse of `from_sexpr`. Each value is converted
+    /// into an atom by `format_value`, which should not produce whitespace, brackets or `_`.
+    ///
+    /// A leaf is written as just its value and every other node as a list of its value followed by
+    /// all of its possible children, with `_` for each vacant child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(3);
+    /// assert_eq!(tree.to_sexpr(|value| value.to_string()), "_");
+    ///
+    /// tree.set_root_value(1).set_child_value(1, 2);
+    /// assert_eq!(tree.to_sexpr(|value| value.to_string()), "(1 _ 2 _)");
+    /// ```
+    pub fn to_sexpr<F>(&self, mut format_value: F) -> String
+    where
+        F: FnMut(&N) -> String,
+    {
+        let mut text = String::new();
+        match self.root() {
+            Some(root) => write_sexpr(root, &mut text, &mut format_value),
+            None => text.push_str(VACANT),
+        }
+        text
+    }
+
+    /// Gets the index of the next node of an s-expression, which is either the next child of the
+    /// innermost open list or the root.
+    fn next_sexpr_index(
+        &self,
+        open_lists: &mut [(usize, usize)],
+        has_root: &mut bool,
+        position: usize,
+    ) -> Result<usize, Error> {
+        let depth = open_lists.len();
+        match open_lists.last_mut() {
+            Some((parent, child_count)) => {
+                let max_children_per_node = self.max_children_per_node();
+                if *child_count >= max_children_per_node {
+                    return Err(Error::ChildIndexOutOfRange {
+                        child_offset: *child_count,
+                        max_children_per_node,
+                    });
+                }
+
+                let index = self
+                    .index_calculator
+                    .checked_child_index(*parent, *child_count)
+                    .ok_or(Error::DepthOverflow { depth })?;
+                *child_count += 1;
+                Ok(index)
+            }
+            None if !*has_root => {
+                *has_root = true;
+                Ok(0)
+            }
+            None => Err(Error::InvalidSexpr { position }),
+        }
+    }
+}
+
+fn write_sexpr<N, F>(node: Node<'_, N>, text: &mut String, format_value: &mut F)
+where
+    F: FnMut(&N) -> String,
+{
+    if node.child_iter().next().is_none() {
+        text.push_str(&format_value(node.value()));
+        return;
+    }
+
+    text.push('(');
+    text.push_str(&format_value(node.value()));
+    for child_offset in 0..node.tree().max_children_per_node() {
+        text.push(' ');
+        match node.child(child_offset) {
+            Some(child) => write_sexpr(child, text, format_value),
+            None => text.push_str(VACANT),
+        }
+    }
+    text.push(')');
+}
+
+/// Splits the text into brackets and atoms, each with its byte offset.
+fn tokenize(text: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = vec![];
+    let mut atom_start = None;
+
+    for (position, c) in text.char_indices() {
+        let token = match c {
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            c if c.is_whitespace() => None,
+            _ => {
+                atom_start.get_or_insert(position);
+                continue;
+            }
+        };
+
+        if let Some(start) = atom_start.take() {
+            tokens.push((start, Token::Atom(&text[start..position])));
+        }
+        tokens.extend(token.map(|token| (position, token)));
+    }
+
+    if let Some(start) = atom_start {
+        tokens.push((start, Token::Atom(&text[start..])));
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, EytzingerTree};
+
+    fn parse(max_children_per_node: usize, text: &str) -> Result<EytzingerTree<u32>, Error> {
+        EytzingerTree::from_sexpr(max_children_per_node, text, |atom| atom.parse().ok())
+    }
+
+    #[test]
+    fn round_trips() {
+        for text in ["_", "1", "(1 2 _)", "(5 (2 1 (4 3 _)) (7 _ 8))"] {
+            let tree = parse(2, text).unwrap();
+            assert_eq!(tree.to_sexpr(|value| value.to_string()), text);
+        }
+    }
+
+    #[test]
+    fn whitespace_is_flexible() {
+        let tree = parse(3, "  (1\n\t(2 _ 3)(4) )  ").unwrap();
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn invalid_text_is_rejected() {
+        assert_eq!(parse(2, ""), Err(Error::InvalidSexpr { position: 0 }));
+        assert_eq!(parse(2, "(1 2"), Err(Error::InvalidSexpr { position: 4 }));
+        assert_eq!(parse(2, "1 2"), Err(Error::InvalidSexpr { position: 2 }));
+        assert_eq!(parse(2, "(1 2))"), Err(Error::InvalidSexpr { position: 5 }));
+        assert_eq!(parse(2, "(_ 2)"), Err(Error::InvalidSexpr { position: 1 }));
+        assert_eq!(parse(2, "(1 x)"), Err(Error::InvalidSexpr { position: 3 }));
+        assert_eq!(
+            parse(2, "(1 2 3 4)"),
+            Err(Error::ChildIndexOutOfRange {
+                child_offset: 2,
+                max_children_per_node: 2,
+            })
+        );
+    }
+}
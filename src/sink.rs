@@ -0,0 +1,226 @@
+//! Building Eytzinger trees from streams of node events.
+//!
+//! This is the inverse of the `export` module: a `TreeSink` receives the nodes of a tree in
+//! pre-order, each node started with its value and ended after all of its children, and builds the
+//! tree from them. Parsers of nested formats can produce these events directly.
+
+use crate::{Error, EytzingerTree};
+
+/// An event describing part of a tree in pre-order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SinkEvent<N> {
+    /// Starts a node with the value, as the next child of the most recently started node which has
+    /// not been ended or as the root if there is no such node.
+    StartNode(N),
+    /// Ends the most recently started node which has not been ended.
+    EndNode,
+}
+
+/// Builds an Eytzinger tree from a stream of node events, validating them as they are received.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::sink::TreeSink;
+///
+/// let mut sink = TreeSink::new(2);
+/// sink.start_node(1).unwrap();
+/// sink.start_node(2).unwrap();
+/// sink.end_node().unwrap();
+/// sink.start_node(3).unwrap();
+/// sink.end_node().unwrap();
+/// sink.end_node().unwrap();
+///
+/// let tree = sink.finish().unwrap();
+/// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+/// assert_eq!(values, vec![1, 2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TreeSink<N> {
+    tree: EytzingerTree<N>,
+    // the index and the number of children so far of each started node which has not been ended
+    open_nodes: Vec<(usize, usize)>,
+}
+
+impl<N> TreeSink<N> {
+    /// Creates a new sink building a tree with the specified max children per node.
+    pub fn new(max_children_per_node: usize) -> Self {
+        Self {
+            tree: EytzingerTree::new(max_children_per_node),
+            open_nodes: vec![],
+        }
+    }
+
+    /// Builds a tree from all of the events.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from `push`, or from `finish` if every event was accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{
+    ///     sink::{SinkEvent, TreeSink},
+    ///     Error,
+    /// };
+    ///
+    /// let tree = TreeSink::build(
+    ///     3,
+    ///     vec![
+    ///         SinkEvent::StartNode('a'),
+    ///         SinkEvent::StartNode('b'),
+    ///         SinkEvent::EndNode,
+    ///         SinkEvent::EndNode,
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(tree.len(), 2);
+    ///
+    /// let unbalanced = TreeSink::build(3, vec![SinkEvent::StartNode('a')]);
+    /// assert_eq!(unbalanced.unwrap_err(), Error::UnbalancedEvents);
+    /// ```
+    pub fn build<I>(max_children_per_node: usize, events: I) -> Result<EytzingerTree<N>, Error>
+    where
+        I: IntoIterator<Item = SinkEvent<N>>,
+    {
+        let mut sink = Self::new(max_children_per_node);
+        for event in events {
+            sink.push(event)?;
+        }
+        sink.finish()
+    }
+
+    /// Receives the next event.
+    ///
+    /// # Errors
+    ///
+    /// See `start_node` and `end_node`.
+    pub fn push(&mut self, event: SinkEvent<N>) -> Result<(), Error> {
+        match event {
+            SinkEvent::StartNode(value) => self.start_node(value),
+            SinkEvent::EndNode => self.end_node(),
+        }
+    }
+
+    /// Starts a node with the value, as the next child of the most recently started node which has
+    /// not been ended or as the root if there is no such node.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::ChildIndexOutOfRange` if the parent already has the max children per node.
+    /// * `Error::DepthOverflow` if the node would be too deep to be indexed.
+    /// * `Error::UnbalancedEvents` if this would be a second root.
+    pub fn start_node(&mut self, value: N) -> Result<(), Error> {
+        let depth = self.open_nodes.len();
+        let index = match self.open_nodes.last_mut() {
+            Some((parent, child_count)) => {
+                let max_children_per_node = self.tree.max_children_per_node();
+                if *child_count >= max_children_per_node {
+                    return Err(Error::ChildIndexOutOfRange {
+                        child_offset: *child_count,
+                        max_children_per_node,
+                    });
+                }
+
+                let index = self
+                    .tree
+                    .index_calculator
+                    .checked_child_index(*parent, *child_count)
+                    .ok_or(Error::DepthOverflow { depth })?;
+                *child_count += 1;
+                index
+            }
+            None if self.tree.is_empty() => 0,
+            None => return Err(Error::UnbalancedEvents),
+        };
+
+        self.tree.set_value(index, value);
+        self.open_nodes.push((index, 0));
+        Ok(())
+    }
+
+    /// Ends the most recently started node which has not been ended.
+    ///
+    /// # Errors
+    ///
+    /// `Error::UnbalancedEvents` if every started node has already been ended.
+    pub fn end_node(&mut self) -> Result<(), Error> {
+        self.open_nodes
+            .pop()
+            .map(|_| ())
+            .ok_or(Error::UnbalancedEvents)
+    }
+
+    /// Gets the tree built from the events.
+    ///
+    /// # Errors
+    ///
+    /// `Error::UnbalancedEvents` if a started node has not been ended.
+    pub fn finish(self) -> Result<EytzingerTree<N>, Error> {
+        if self.open_nodes.is_empty() {
+            Ok(self.tree)
+        } else {
+            Err(Error::UnbalancedEvents)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        export::TextExporter,
+        sink::{SinkEvent, TreeSink},
+        Error,
+    };
+
+    #[test]
+    fn children_are_placed_in_order() {
+        let mut sink = TreeSink::new(3);
+        for event in [
+            SinkEvent::StartNode(1),
+            SinkEvent::StartNode(2),
+            SinkEvent::StartNode(3),
+            SinkEvent::EndNode,
+            SinkEvent::EndNode,
+            SinkEvent::StartNode(4),
+            SinkEvent::EndNode,
+            SinkEvent::EndNode,
+        ] {
+            sink.push(event).unwrap();
+        }
+
+        let tree = sink.finish().unwrap();
+        let mut text = String::new();
+        tree.export(&mut TextExporter::new(&mut text)).unwrap();
+        assert_eq!(text, "1\n  0: 2\n    0: 3\n  1: 4\n");
+    }
+
+    #[test]
+    fn too_many_children_is_rejected() {
+        let mut sink = TreeSink::new(1);
+        sink.start_node(1).unwrap();
+        sink.start_node(2).unwrap();
+        sink.end_node().unwrap();
+
+        assert_eq!(
+            sink.start_node(3),
+            Err(Error::ChildIndexOutOfRange {
+                child_offset: 1,
+                max_children_per_node: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn unbalanced_events_are_rejected() {
+        let mut sink = TreeSink::<u32>::new(2);
+        assert_eq!(sink.end_node(), Err(Error::UnbalancedEvents));
+
+        sink.start_node(1).unwrap();
+        sink.end_node().unwrap();
+        assert_eq!(sink.start_node(2), Err(Error::UnbalancedEvents));
+
+        assert_eq!(TreeSink::<u32>::build(2, None).unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,324 @@
+//! Quadtrees and octrees stored in Eytzinger trees.
+//!
+//! Each node covers an axis-aligned cell and its `2^D` children cover the halves of that cell
+//! along every axis. The child offset of a sub-cell has bit `axis` set when it covers the upper
+//! half along that axis.
+
+use crate::{EytzingerTree, Node, NodeMut};
+use std::iter::FusedIterator;
+
+/// An axis-aligned box in `D` dimensions, the lower bounds are inclusive and the upper bounds are
+/// exclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb<const D: usize> {
+    /// The lower corner of the box.
+    pub min: [f64; D],
+
+    /// The upper corner of the box.
+    pub max: [f64; D],
+}
+
+impl<const D: usize> Aabb<D> {
+    /// Creates a new box between the two corners.
+    pub fn new(min: [f64; D], max: [f64; D]) -> Self {
+        Self { min, max }
+    }
+
+    /// Gets the centre of the box, where it is split into its sub-cells.
+    pub fn centre(&self) -> [f64; D] {
+        let mut centre = self.min;
+        for (axis, value) in centre.iter_mut().enumerate() {
+            *value += (self.max[axis] - self.min[axis]) / 2.0;
+        }
+        centre
+    }
+
+    /// Gets whether the point lies within this box.
+    pub fn contains(&self, point: &[f64; D]) -> bool {
+        (0..D).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+
+    /// Gets whether this box and the other box overlap.
+    pub fn intersects(&self, other: &Aabb<D>) -> bool {
+        (0..D).all(|axis| self.min[axis] < other.max[axis] && other.min[axis] < self.max[axis])
+    }
+
+    /// Gets the child offset of the sub-cell which contains the point. Points outside of this box
+    /// are assigned to the nearest sub-cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::spatial::Aabb;
+    ///
+    /// let bounds = Aabb::new([0.0, 0.0], [10.0, 10.0]);
+    /// assert_eq!(bounds.child_for_point(&[1.0, 1.0]), 0);
+    /// assert_eq!(bounds.child_for_point(&[6.0, 1.0]), 1);
+    /// assert_eq!(bounds.child_for_point(&[1.0, 6.0]), 2);
+    /// assert_eq!(bounds.child_for_point(&[6.0, 6.0]), 3);
+    /// ```
+    pub fn child_for_point(&self, point: &[f64; D]) -> usize {
+        let centre = self.centre();
+        (0..D)
+            .filter(|&axis| point[axis] >= centre[axis])
+            .fold(0, |offset, axis| offset | (1 << axis))
+    }
+
+    /// Gets the sub-cell of this box at the child offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the child offset is not less than `2^D`.
+    pub fn child_bounds(&self, child_offset: usize) -> Aabb<D> {
+        assert!(
+            child_offset < 1 << D,
+            "the child offset should be less than 2^D"
+        );
+
+        let centre = self.centre();
+        let mut child = *self;
+        for (axis, &split) in centre.iter().enumerate() {
+            if child_offset & (1 << axis) != 0 {
+                child.min[axis] = split;
+            } else {
+                child.max[axis] = split;
+            }
+        }
+        child
+    }
+}
+
+/// An Eytzinger tree where each node covers a cell of a `D` dimensional box, with `2^D` children
+/// per node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Orthtree<N, const D: usize> {
+    tree: EytzingerTree<N>,
+    bounds: Aabb<D>,
+}
+
+/// An orthtree over two dimensions, with 4 children per node.
+pub type Quadtree<N> = Orthtree<N, 2>;
+
+/// An orthtree over three dimensions, with 8 children per node.
+pub type Octree<N> = Orthtree<N, 3>;
+
+impl<N, const D: usize> Orthtree<N, D> {
+    /// Creates a new empty orthtree where the root covers the bounds.
+    pub fn new(bounds: Aabb<D>) -> Self {
+        Self {
+            tree: EytzingerTree::new(1 << D),
+            bounds,
+        }
+    }
+
+    /// Gets the box covered by the root.
+    pub fn bounds(&self) -> &Aabb<D> {
+        &self.bounds
+    }
+
+    /// Gets the underlying Eytzinger tree.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Gets the underlying Eytzinger tree mutably, child offsets can be found with
+    /// `Aabb::child_for_point`.
+    pub fn tree_mut(&mut self) -> &mut EytzingerTree<N> {
+        &mut self.tree
+    }
+
+    /// Converts this into the underlying Eytzinger tree.
+    pub fn into_tree(self) -> EytzingerTree<N> {
+        self.tree
+    }
+
+    /// Descends from the root towards the point for as long as there are nodes.
+    ///
+    /// # Returns
+    ///
+    /// The deepest node whose cell contains the point along with that cell, or `None` if the
+    /// point is outside of the bounds or the tree is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::spatial::{Aabb, Quadtree};
+    ///
+    /// let bounds = Aabb::new([0.0, 0.0], [8.0, 8.0]);
+    /// let mut quadtree = Quadtree::new(bounds);
+    /// quadtree
+    ///     .tree_mut()
+    ///     .set_root_value("root")
+    ///     .set_child_value(bounds.child_for_point(&[6.0, 1.0]), "south east");
+    ///
+    /// let (node, cell) = quadtree.descend_to_leaf(&[7.0, 3.0]).unwrap();
+    /// assert_eq!(*node.value(), "south east");
+    /// assert_eq!(cell, Aabb::new([4.0, 0.0], [8.0, 4.0]));
+    ///
+    /// let (node, _) = quadtree.descend_to_leaf(&[1.0, 7.0]).unwrap();
+    /// assert_eq!(*node.value(), "root");
+    ///
+    /// assert!(quadtree.descend_to_leaf(&[9.0, 1.0]).is_none());
+    /// ```
+    pub fn descend_to_leaf(&self, point: &[f64; D]) -> Option<(Node<'_, N>, Aabb<D>)> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+
+        let mut node = self.tree.root()?;
+        let mut cell = self.bounds;
+        loop {
+            let child_offset = cell.child_for_point(point);
+            match node.child(child_offset) {
+                Some(child) => {
+                    node = child;
+                    cell = cell.child_bounds(child_offset);
+                }
+                None => return Some((node, cell)),
+            }
+        }
+    }
+
+    /// Descends from the root towards the point for as long as there are nodes, see
+    /// `descend_to_leaf`.
+    pub fn descend_to_leaf_mut(&mut self, point: &[f64; D]) -> Option<(NodeMut<'_, N>, Aabb<D>)> {
+        if !self.bounds.contains(point) {
+            return None;
+        }
+
+        let mut node = self.tree.root_mut()?;
+        let mut cell = self.bounds;
+        loop {
+            let child_offset = cell.child_for_point(point);
+            match node.to_child(child_offset) {
+                Ok(child) => {
+                    node = child;
+                    cell = cell.child_bounds(child_offset);
+                }
+                Err(node) => return Some((node, cell)),
+            }
+        }
+    }
+
+    /// Gets an iterator over the nodes whose cells overlap the region, along with their cells.
+    /// Subtrees whose cells do not overlap the region are skipped entirely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::spatial::{Aabb, Quadtree};
+    ///
+    /// let bounds = Aabb::new([0.0, 0.0], [8.0, 8.0]);
+    /// let mut quadtree = Quadtree::new(bounds);
+    /// {
+    ///     let mut root = quadtree.tree_mut().set_root_value(0);
+    ///     for child_offset in 0..4 {
+    ///         root.set_child_value(child_offset, child_offset + 1);
+    ///     }
+    /// }
+    ///
+    /// let mut values: Vec<_> = quadtree
+    ///     .region_iter(Aabb::new([5.0, 1.0], [7.0, 6.0]))
+    ///     .map(|(node, _)| *node.value())
+    ///     .collect();
+    /// values.sort();
+    /// assert_eq!(values, vec![0, 2, 4]);
+    /// ```
+    pub fn region_iter(&self, region: Aabb<D>) -> RegionIter<'_, N, D> {
+        let pending = self
+            .tree
+            .root()
+            .filter(|_| self.bounds.intersects(&region))
+            .map(|root| (root, self.bounds))
+            .into_iter()
+            .collect();
+
+        RegionIter { region, pending }
+    }
+}
+
+/// An iterator over the nodes of an orthtree whose cells overlap a region, see
+/// `Orthtree::region_iter`.
+#[derive(Debug, Clone)]
+pub struct RegionIter<'a, N, const D: usize> {
+    region: Aabb<D>,
+    pending: Vec<(Node<'a, N>, Aabb<D>)>,
+}
+
+impl<'a, N, const D: usize> Iterator for RegionIter<'a, N, D> {
+    type Item = (Node<'a, N>, Aabb<D>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, cell) = self.pending.pop()?;
+
+        for child_offset in (0..1 << D).rev() {
+            if let Some(child) = node.child(child_offset) {
+                let child_cell = cell.child_bounds(child_offset);
+                if child_cell.intersects(&self.region) {
+                    self.pending.push((child, child_cell));
+                }
+            }
+        }
+
+        Some((node, cell))
+    }
+}
+
+impl<'a, N, const D: usize> FusedIterator for RegionIter<'a, N, D> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn child_bounds_round_trips_child_for_point() {
+        let bounds = Aabb::new([0.0, 0.0, 0.0], [2.0, 4.0, 8.0]);
+        for child_offset in 0..8 {
+            let cell = bounds.child_bounds(child_offset);
+            assert_eq!(bounds.child_for_point(&cell.centre()), child_offset);
+            assert!(bounds.contains(&cell.min));
+        }
+    }
+
+    #[test]
+    fn region_iter_prunes_disjoint_cells() {
+        let bounds = Aabb::new([0.0, 0.0], [4.0, 4.0]);
+        let mut quadtree = Quadtree::new(bounds);
+        {
+            let mut root = quadtree.tree_mut().set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(3, 2);
+            root.set_child_value(3, 3).set_child_value(0, 4);
+        }
+
+        let values: Vec<_> = quadtree
+            .region_iter(Aabb::new([0.5, 0.5], [1.5, 1.5]))
+            .map(|(node, _)| *node.value())
+            .collect();
+        assert_eq!(values, vec![0, 1, 2]);
+
+        assert_eq!(
+            quadtree
+                .region_iter(Aabb::new([5.0, 5.0], [6.0, 6.0]))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn descend_to_leaf_mut_finds_deepest_node() {
+        let bounds = Aabb::new([0.0, 0.0], [4.0, 4.0]);
+        let mut quadtree = Quadtree::new(bounds);
+        quadtree.tree_mut().set_root_value(0).set_child_value(3, 1);
+
+        let (mut node, cell) = quadtree.descend_to_leaf_mut(&[3.0, 3.0]).unwrap();
+        assert_eq!(cell, Aabb::new([2.0, 2.0], [4.0, 4.0]));
+        *node.value_mut() = 5;
+
+        let values: Vec<_> = quadtree
+            .tree()
+            .breadth_first_iter()
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, vec![0, 5]);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{EytzingerIndexCalculator, NodePath};
+use std::{mem, ops::Range};
+
+/// A mutable view of a subtree which does not overlap with any other `SubtreeMut` handed out at
+/// the same time, so several of them may be used at once, even from different threads.
+///
+/// The values of existing nodes may be changed but nodes may not be added or removed.
+#[derive(Debug)]
+pub struct SubtreeMut<'a, N> {
+    root_index: usize,
+    index_calculator: EytzingerIndexCalculator,
+    levels: Vec<&'a mut [Option<N>]>,
+}
+
+impl<'a, N> SubtreeMut<'a, N> {
+    /// Splits the storage into the subtrees rooted at each of the `roots`, which must all be at
+    /// the same depth. Vacant roots result in `None`.
+    pub(crate) fn split(
+        nodes: &'a mut [Option<N>],
+        index_calculator: EytzingerIndexCalculator,
+        roots: Range<usize>,
+    ) -> Vec<Option<Self>> {
+        let storage_len = nodes.len();
+        let mut levels: Vec<Vec<&'a mut [Option<N>]>> = roots.clone().map(|_| vec![]).collect();
+
+        // each level of the subtrees is contiguous in storage, with each subtree taking an equal
+        // share of it from left to right
+        let mut remaining = nodes;
+        let mut remaining_start = 0;
+        let mut share_len: usize = 1;
+
+        for level in index_calculator.descendant_level_ranges(roots.clone(), storage_len) {
+            let (_, rest) = mem::take(&mut remaining).split_at_mut(level.start - remaining_start);
+            let (level_values, rest) = rest.split_at_mut(level.len());
+
+            for (subtree_levels, share) in levels.iter_mut().zip(level_values.chunks_mut(share_len))
+            {
+                subtree_levels.push(share);
+            }
+
+            remaining = rest;
+            remaining_start = level.end;
+            share_len = share_len.saturating_mul(index_calculator.stride());
+        }
+
+        levels
+            .into_iter()
+            .zip(roots)
+            .map(|(levels, root_index)| match levels.first() {
+                Some(root_level) if root_level[0].is_some() => Some(Self {
+                    root_index,
+                    index_calculator,
+                    levels,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Gets the path of the root of this subtree within the whole tree.
+    pub fn path(&self) -> NodePath {
+        NodePath::from(self.index_calculator.child_offsets(self.root_index))
+    }
+
+    /// Gets the value of the root of this subtree.
+    pub fn value(&self) -> &N {
+        self.levels[0][0]
+            .as_ref()
+            .expect("the root of a subtree should exist")
+    }
+
+    /// Gets the mutable value of the root of this subtree.
+    pub fn value_mut(&mut self) -> &mut N {
+        self.levels[0][0]
+            .as_mut()
+            .expect("the root of a subtree should exist")
+    }
+
+    /// Gets the value of the node at the specified path relative to the root of this subtree.
+    pub fn get(&self, path: &NodePath) -> Option<&N> {
+        let (depth, offset) = self.locate(path)?;
+        self.levels.get(depth)?.get(offset)?.as_ref()
+    }
+
+    /// Gets the mutable value of the node at the specified path relative to the root of this
+    /// subtree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{Depth, EytzingerTree, NodePath};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// tree.set_root_value(1).set_child_value(1, 2).set_child_value(0, 3);
+    ///
+    /// for mut subtree in tree.subtrees_at_depth_mut(Depth(1)) {
+    ///     if let Some(value) = subtree.get_mut(&NodePath::from(vec![0])) {
+    ///         *value *= 10;
+    ///     }
+    /// }
+    ///
+    /// let path = NodePath::from(vec![1, 0]);
+    /// assert_eq!(tree.subtrees_at_depth_mut(Depth(0))[0].get(&path), Some(&30));
+    /// ```
+    pub fn get_mut(&mut self, path: &NodePath) -> Option<&mut N> {
+        let (depth, offset) = self.locate(path)?;
+        self.levels.get_mut(depth)?.get_mut(offset)?.as_mut()
+    }
+
+    /// Gets an iterator over the mutable values of every node in this subtree in breadth-first
+    /// order.
+    pub fn values_mut(&mut self) -> Box<dyn Iterator<Item = &mut N> + '_> {
+        Box::new(
+            self.levels
+                .iter_mut()
+                .flat_map(|level| level.iter_mut())
+                .flat_map(|value| value.as_mut()),
+        )
+    }
+
+    /// Gets the level and the offset within that level of the node at the relative path.
+    fn locate(&self, path: &NodePath) -> Option<(usize, usize)> {
+        let max_children_per_node = self.index_calculator.max_children_per_node();
+        let stride = self.index_calculator.stride();
+
+        let offset = path
+            .child_offsets()
+            .iter()
+            .try_fold(0usize, |offset, &child_offset| {
+                if child_offset < max_children_per_node {
+                    offset.checked_mul(stride)?.checked_add(child_offset)
+                } else {
+                    None
+                }
+            })?;
+
+        Some((path.depth(), offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Depth, EytzingerTree, NodePath};
+
+    fn full_tree(max_children_per_node: usize, depth: usize) -> EytzingerTree<usize> {
+        let mut tree = EytzingerTree::new(max_children_per_node);
+        tree.set_root_value(0);
+
+        let mut level = vec![NodePath::root()];
+        for _ in 0..depth {
+            let mut next_level = vec![];
+            for path in level {
+                for child_offset in 0..max_children_per_node {
+                    let child = path.child(child_offset);
+                    let index = tree.path_index(&child).unwrap();
+                    tree.set_value(index, index);
+                    next_level.push(child);
+                }
+            }
+            level = next_level;
+        }
+
+        tree
+    }
+
+    #[test]
+    fn subtrees_cover_every_node_below_the_depth() {
+        let mut tree = full_tree(3, 4);
+
+        let mut visited: Vec<usize> = tree
+            .subtrees_at_depth_mut(Depth(2))
+            .into_iter()
+            .flat_map(|mut subtree| subtree.values_mut().map(|v| *v).collect::<Vec<_>>())
+            .collect();
+        visited.sort_unstable();
+
+        assert_eq!(visited, (4..tree.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn subtree_paths_are_relative_to_their_root() {
+        let mut tree = full_tree(2, 3);
+
+        let subtrees = tree.subtrees_at_depth_mut(Depth(1));
+        assert_eq!(subtrees.len(), 2);
+        assert_eq!(subtrees[1].path(), NodePath::from(vec![1]));
+        assert_eq!(subtrees[1].value(), &2);
+        assert_eq!(subtrees[1].get(&NodePath::from(vec![1, 0])), Some(&13));
+        assert_eq!(subtrees[1].get(&NodePath::from(vec![2])), None);
+        assert_eq!(subtrees[1].get(&NodePath::from(vec![0, 0, 0])), None);
+    }
+
+    #[test]
+    fn vacant_subtrees_are_skipped() {
+        let mut tree = EytzingerTree::<u32>::new(4);
+        tree.set_root_value(1).set_child_value(2, 2);
+
+        let subtrees = tree.subtrees_at_depth_mut(Depth(1));
+        assert_eq!(subtrees.len(), 1);
+        assert_eq!(subtrees[0].value(), &2);
+
+        assert!(tree.subtrees_at_depth_mut(Depth(2)).is_empty());
+    }
+
+    #[test]
+    fn par_for_each_subtree_mut_visits_every_subtree() {
+        let mut tree = full_tree(2, 10);
+
+        tree.par_for_each_subtree_mut(Depth(3), |mut subtree| {
+            for value in subtree.values_mut() {
+                *value += 1;
+            }
+        });
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        let expected: Vec<_> = (0..tree.len())
+            .map(|index| if index < 7 { index } else { index + 1 })
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn subtrees_of_a_deep_chain() {
+        let mut tree = EytzingerTree::new(1);
+        {
+            let mut node = tree.set_root_value(0);
+            for value in 1..10_000 {
+                node = node.to_child_entry(0).or_insert(value);
+            }
+        }
+
+        let mut subtrees = tree.subtrees_at_depth_mut(Depth(5_000));
+        assert_eq!(subtrees.len(), 1);
+        assert_eq!(subtrees[0].values_mut().count(), 5_000);
+    }
+
+    #[test]
+    fn depths_below_the_stored_nodes_have_no_subtrees() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1);
+
+        assert!(tree.subtrees_at_depth_mut(Depth(40)).is_empty());
+        tree.par_for_each_subtree_mut(Depth(40), |_| panic!("there should be no subtrees"));
+        assert_eq!(tree.root().map(|n| *n.value()), Some(1));
+    }
+}
@@ -7,14 +7,41 @@ pub(crate) use self::traversal_root::TraversalRoot;
 mod breadth_first_iter;
 pub use self::breadth_first_iter::BreadthFirstIter;
 
+mod breadth_first_rev_iter;
+pub use self::breadth_first_rev_iter::BreadthFirstRevIter;
+
 mod breadth_first_iterator;
 pub use self::breadth_first_iterator::BreadthFirstIterator;
 
+mod breadth_first_values_mut;
+pub use self::breadth_first_values_mut::BreadthFirstValuesMut;
+
+mod levels;
+pub use self::levels::{Level, Levels};
+
 mod depth_first_order;
 pub use self::depth_first_order::DepthFirstOrder;
 
 mod depth_first_iter;
 pub use self::depth_first_iter::DepthFirstIter;
 
+mod in_order_iter;
+pub use self::in_order_iter::InOrderIter;
+
 mod depth_first_iterator;
 pub use self::depth_first_iterator::DepthFirstIterator;
+
+mod subtree_drain;
+pub use self::subtree_drain::SubtreeDrain;
+
+mod iter_snapshot;
+pub use self::iter_snapshot::IterSnapshot;
+
+mod nodes;
+pub use self::nodes::{Ids, Nodes, Values, ValuesCloned, WithDepth};
+
+mod zip_iter;
+pub use self::zip_iter::{zip_iter, ZipIter};
+
+mod best_first_iter;
+pub use self::best_first_iter::BestFirstIter;
@@ -0,0 +1,151 @@
+use crate::{EytzingerTree, Node};
+use std::{cmp::Reverse, collections::BinaryHeap, iter::FusedIterator};
+
+/// An iterator over nodes in ascending order of a key, where the children of a node only become
+/// candidates once the node has been returned.
+///
+/// The subtree of the most recently returned node may be pruned with `prune_current_subtree`,
+/// which is how branch-and-bound searches skip subtrees which cannot improve on the best result
+/// so far. Nodes with equal keys are returned in breadth-first order.
+#[derive(Debug, Clone)]
+pub struct BestFirstIter<'a, N, K, F>
+where
+    N: 'a,
+{
+    tree: &'a EytzingerTree<N>,
+    key: F,
+    pending: BinaryHeap<Reverse<(K, usize)>>,
+    current: Option<usize>,
+}
+
+impl<'a, N, K, F> BestFirstIter<'a, N, K, F>
+where
+    K: Ord,
+    F: FnMut(Node<'a, N>) -> K,
+{
+    pub(crate) fn new(tree: &'a EytzingerTree<N>, node: Option<Node<'a, N>>, mut key: F) -> Self {
+        let pending = node
+            .map(|node| Reverse((key(node), node.index())))
+            .into_iter()
+            .collect();
+
+        Self {
+            tree,
+            key,
+            pending,
+            current: None,
+        }
+    }
+
+    /// Prevents the children of the node which was returned last, and so its whole subtree, from
+    /// being returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::EytzingerTree;
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(2);
+    /// {
+    ///     let mut root = tree.set_root_value(0);
+    ///     root.set_child_value(0, 5).set_child_value(0, 6);
+    ///     root.set_child_value(1, 3).set_child_value(1, 9);
+    /// }
+    ///
+    /// let mut iter = tree.best_first_iter(|node| *node.value());
+    /// let mut values = vec![];
+    /// while let Some(node) = iter.next() {
+    ///     values.push(*node.value());
+    ///     if *node.value() == 3 {
+    ///         iter.prune_current_subtree();
+    ///     }
+    /// }
+    /// assert_eq!(values, vec![0, 3, 5, 6]);
+    /// ```
+    pub fn prune_current_subtree(&mut self) {
+        self.current = None;
+    }
+
+    /// Gets the tree this iterator is for.
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
+        self.tree
+    }
+}
+
+impl<'a, N, K, F> Iterator for BestFirstIter<'a, N, K, F>
+where
+    K: Ord,
+    F: FnMut(Node<'a, N>) -> K,
+{
+    type Item = Node<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(current) = self.current.take() {
+            let tree = self.tree;
+            for child in tree
+                .node(current)
+                .into_iter()
+                .flat_map(|node| node.child_iter())
+            {
+                self.pending
+                    .push(Reverse(((self.key)(child), child.index())));
+            }
+        }
+
+        let Reverse((_, index)) = self.pending.pop()?;
+        self.current = Some(index);
+        self.tree.node(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pending.len(), Some(self.tree.len()))
+    }
+}
+
+impl<'a, N, K, F> FusedIterator for BestFirstIter<'a, N, K, F>
+where
+    K: Ord,
+    F: FnMut(Node<'a, N>) -> K,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EytzingerTree;
+
+    #[test]
+    fn returns_nodes_in_ascending_key_order() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(4);
+            root.set_child_value(0, 8).set_child_value(2, 1);
+            root.set_child_value(1, 6);
+            root.set_child_value(2, 5).set_child_value(0, 7);
+        }
+
+        let values: Vec<_> = tree
+            .best_first_iter(|node| *node.value())
+            .map(|n| *n.value())
+            .collect();
+        // a child is only a candidate once its parent has been returned
+        assert_eq!(values, vec![4, 5, 6, 7, 8, 1]);
+    }
+
+    #[test]
+    fn equal_keys_are_breadth_first() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).extend_breadth_first(2..8);
+
+        let values: Vec<_> = tree
+            .root()
+            .unwrap()
+            .best_first_iter(|_| 0)
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(values, (1..8).collect::<Vec<_>>());
+        assert!(EytzingerTree::<u32>::new(2)
+            .best_first_iter(|n| *n.value())
+            .next()
+            .is_none());
+    }
+}
@@ -1,41 +1,47 @@
-use crate::{
-    traversal::{NodeChildIter, TraversalRoot},
-    EytzingerTree, Node,
-};
-use std::collections::VecDeque;
+use crate::{traversal::TraversalRoot, EytzingerTree, Node};
 use std::iter::FusedIterator;
+use std::mem;
 
 /// A breadth-first iterator.
+///
+/// The iterator works one level at a time, the next level is found from the children of the
+/// nodes on the current level so vacant parts of the tree are skipped without being visited.
 #[derive(Debug)]
 pub struct BreadthFirstIter<'a, N>
 where
     N: 'a,
 {
     root: TraversalRoot<'a, N>,
-    nodes: VecDeque<NodeChildIter<'a, N>>,
+    current_level: Vec<usize>,
+    position: usize,
+    next_level: Vec<usize>,
 }
 
 impl<'a, N> Clone for BreadthFirstIter<'a, N> {
     fn clone(&self) -> Self {
         BreadthFirstIter {
             root: self.root,
-            nodes: self.nodes.clone(),
+            current_level: self.current_level.clone(),
+            position: self.position,
+            next_level: vec![],
         }
     }
 }
 
 impl<'a, N> BreadthFirstIter<'a, N> {
     pub(crate) fn new(tree: &'a EytzingerTree<N>, node: Option<Node<'a, N>>) -> Self {
-        let mut nodes = VecDeque::new();
-
-        let root = if let Some(node) = node {
-            nodes.push_back(node.child_iter());
-            TraversalRoot::Node(node)
+        let (root, current_level) = if let Some(node) = node {
+            (TraversalRoot::Node(node), vec![node.index()])
         } else {
-            TraversalRoot::Tree(tree)
+            (TraversalRoot::Tree(tree), vec![])
         };
 
-        Self { root, nodes }
+        Self {
+            root,
+            current_level,
+            position: 0,
+            next_level: vec![],
+        }
     }
 
     /// Gets the starting/root node of this iterator or `None` if there was not one. There will be
@@ -48,26 +54,126 @@ impl<'a, N> BreadthFirstIter<'a, N> {
     pub fn tree(&self) -> &'a EytzingerTree<N> {
         self.root.tree()
     }
+
+    fn advance_level(&mut self) {
+        let tree = self.tree();
+        let storage_len = tree.nodes.len();
+
+        self.next_level.clear();
+        for &parent_index in &self.current_level {
+            self.next_level.extend(
+                tree.index_calculator
+                    .stored_child_indexes(parent_index, storage_len)
+                    .filter(|&child_index| tree.nodes[child_index].is_some()),
+            );
+        }
+
+        mem::swap(&mut self.current_level, &mut self.next_level);
+        self.position = 0;
+    }
 }
 
 impl<'a, N> Iterator for BreadthFirstIter<'a, N> {
     type Item = Node<'a, N>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(mut current) = self.nodes.pop_front() {
-            if let Some(next) = current.next() {
-                self.nodes.push_front(current);
-                self.nodes.push_back(next.child_iter());
-            } else {
-                return Some(current.node());
+        loop {
+            if let Some(&index) = self.current_level.get(self.position) {
+                self.position += 1;
+
+                return Some(Node {
+                    tree: self.tree(),
+                    index,
+                });
             }
+
+            if self.current_level.is_empty() {
+                return None;
+            }
+            self.advance_level();
         }
-        None
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.tree().len()))
+        let remaining_on_level = self.current_level.len() - self.position;
+
+        (remaining_on_level, Some(self.tree().len()))
+    }
+
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let tree = self.tree();
+        let mut accumulator = init;
+        while !self.current_level.is_empty() {
+            for &index in &self.current_level[self.position..] {
+                accumulator = f(accumulator, Node { tree, index });
+            }
+            self.advance_level();
+        }
+
+        accumulator
     }
 }
 
 impl<'a, N> FusedIterator for BreadthFirstIter<'a, N> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::EytzingerTree;
+
+    #[test]
+    fn sparse_wide_tree_is_visited_in_order() {
+        let mut tree = EytzingerTree::<usize>::new(16);
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(15, 2).set_child_value(15, 4);
+            root.set_child_value(3, 1).set_child_value(0, 3);
+        }
+
+        let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn clone_resumes_from_the_same_position() {
+        let mut tree = EytzingerTree::<usize>::new(2);
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(1, 3);
+            root.set_child_value(1, 2);
+        }
+
+        let mut iter = tree.breadth_first_iter();
+        iter.next();
+        iter.next();
+
+        let rest: Vec<_> = iter.clone().map(|n| *n.value()).collect();
+        assert_eq!(rest, vec![2, 3]);
+        assert_eq!(iter.count(), 2);
+    }
+
+    #[test]
+    fn fold_resumes_from_the_same_position_as_next() {
+        let mut tree = EytzingerTree::<usize>::new(3);
+        {
+            let mut root = tree.set_root_value(0);
+            root.set_child_value(0, 1).set_child_value(2, 4);
+            root.set_child_value(2, 2);
+            root.set_child_value(1, 3).set_child_value(0, 5);
+        }
+
+        let expected: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+        for skipped in 0..=expected.len() {
+            let mut iter = tree.breadth_first_iter();
+            iter.by_ref().take(skipped).for_each(drop);
+
+            let rest = iter.fold(vec![], |mut values, node| {
+                values.push(*node.value());
+                values
+            });
+            assert_eq!(rest, expected[skipped..]);
+        }
+    }
+}
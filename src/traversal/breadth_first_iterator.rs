@@ -1,6 +1,6 @@
 use crate::EytzingerTree;
 use std::collections::VecDeque;
-use std::iter::{ExactSizeIterator, FusedIterator};
+use std::iter::FusedIterator;
 use std::ops::Range;
 
 /// A breadth-first iterator which returns owned values.
@@ -30,7 +30,7 @@ impl<N> Iterator for BreadthFirstIterator<N> {
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(mut current) = self.pending_nodes.pop_front() {
             if let Some(next) = current.next() {
-                if current.len() > 0 {
+                if !current.is_empty() {
                     // If there are still more remaining nodes at this level put them to the front
                     // of the queue
                     self.pending_nodes.push_front(current);
@@ -0,0 +1,114 @@
+use crate::{traversal::TraversalRoot, EytzingerTree, Node};
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+/// A reverse breadth-first iterator, the deepest level is returned first and the nodes within
+/// each level are returned from left to right.
+///
+/// Each level is scanned in storage order, so this is fastest for dense trees.
+#[derive(Debug)]
+pub struct BreadthFirstRevIter<'a, N>
+where
+    N: 'a,
+{
+    root: TraversalRoot<'a, N>,
+    levels: Vec<Range<usize>>,
+    current_level: Range<usize>,
+}
+
+impl<'a, N> Clone for BreadthFirstRevIter<'a, N> {
+    fn clone(&self) -> Self {
+        BreadthFirstRevIter {
+            root: self.root,
+            levels: self.levels.clone(),
+            current_level: self.current_level.clone(),
+        }
+    }
+}
+
+impl<'a, N> BreadthFirstRevIter<'a, N> {
+    pub(crate) fn new(tree: &'a EytzingerTree<N>, node: Option<Node<'a, N>>) -> Self {
+        let (root, levels) = if let Some(node) = node {
+            let levels = tree
+                .index_calculator
+                .descendant_level_ranges(node.index()..node.index() + 1, tree.nodes.len())
+                .collect();
+
+            (TraversalRoot::Node(node), levels)
+        } else {
+            (TraversalRoot::Tree(tree), vec![])
+        };
+
+        Self {
+            root,
+            levels,
+            current_level: 0..0,
+        }
+    }
+
+    /// Gets the starting/root node of this iterator or `None` if there was not one. There will be
+    /// no starting node for an empty Eytzinger tree.
+    pub fn starting_node(&self) -> Option<Node<'a, N>> {
+        self.root.starting_node()
+    }
+
+    /// Gets the tree this iterator is for.
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
+        self.root.tree()
+    }
+}
+
+impl<'a, N> Iterator for BreadthFirstRevIter<'a, N> {
+    type Item = Node<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.tree();
+
+        loop {
+            for index in self.current_level.by_ref() {
+                if tree.nodes[index].is_some() {
+                    return Some(Node { tree, index });
+                }
+            }
+
+            self.current_level = self.levels.pop()?;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.tree().len()))
+    }
+}
+
+impl<'a, N> FusedIterator for BreadthFirstRevIter<'a, N> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::EytzingerTree;
+
+    #[test]
+    fn deepest_level_is_returned_first() {
+        let mut tree = EytzingerTree::<u32>::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(2, 5);
+            let mut right = root.set_child_value(2, 3);
+            right.set_child_value(0, 6);
+            right.set_child_value(1, 7).set_child_value(0, 8);
+        }
+
+        let values: Vec<_> = tree.breadth_first_rev_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![8, 5, 6, 7, 2, 3, 1]);
+
+        let right = tree.root().unwrap().child(2).unwrap();
+        let values: Vec<_> = right.breadth_first_rev_iter().map(|n| *n.value()).collect();
+        assert_eq!(values, vec![8, 6, 7, 3]);
+    }
+
+    #[test]
+    fn empty_tree_has_no_nodes() {
+        let tree = EytzingerTree::<u32>::new(2);
+
+        assert!(tree.breadth_first_rev_iter().next().is_none());
+    }
+}
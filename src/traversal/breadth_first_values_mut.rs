@@ -0,0 +1,39 @@
+use std::iter::FusedIterator;
+use std::slice;
+
+/// A breadth-first iterator over mutable references to the values of a tree.
+///
+/// The nodes of each level are stored together from left to right so they are visited in the
+/// order of their storage.
+#[derive(Debug)]
+pub struct BreadthFirstValuesMut<'a, N> {
+    nodes: slice::IterMut<'a, Option<N>>,
+    remaining: usize,
+}
+
+impl<'a, N> BreadthFirstValuesMut<'a, N> {
+    pub(crate) fn new(nodes: &'a mut [Option<N>], len: usize) -> Self {
+        Self {
+            nodes: nodes.iter_mut(),
+            remaining: len,
+        }
+    }
+}
+
+impl<'a, N> Iterator for BreadthFirstValuesMut<'a, N> {
+    type Item = &'a mut N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.nodes.find_map(Option::as_mut)?;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, N> ExactSizeIterator for BreadthFirstValuesMut<'a, N> {}
+
+impl<'a, N> FusedIterator for BreadthFirstValuesMut<'a, N> {}
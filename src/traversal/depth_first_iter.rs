@@ -2,7 +2,6 @@ use crate::{
     traversal::{DepthFirstOrder, NodeChildIter, TraversalRoot},
     EytzingerTree, Node,
 };
-use matches::matches;
 use std::iter::FusedIterator;
 
 /// A depth-first iterator
@@ -48,6 +47,29 @@ impl<'a, N> DepthFirstIter<'a, N> {
         }
     }
 
+    /// Reserves room for the specified depth in the stack of pending nodes, so deep trees can be
+    /// iterated without the stack being reallocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::{traversal::DepthFirstOrder, EytzingerTree};
+    ///
+    /// let mut tree = EytzingerTree::<u32>::new(1);
+    /// tree.set_root_value(1).set_child_value(0, 2);
+    ///
+    /// let values: Vec<_> = tree
+    ///     .depth_first_iter(DepthFirstOrder::PostOrder)
+    ///     .with_stack_capacity(2)
+    ///     .map(|n| *n.value())
+    ///     .collect();
+    /// assert_eq!(values, vec![2, 1]);
+    /// ```
+    pub fn with_stack_capacity(mut self, capacity: usize) -> Self {
+        self.nodes.reserve(capacity);
+        self
+    }
+
     /// Gets the order of depth-first iteration.
     pub fn order(&self) -> DepthFirstOrder {
         self.order
@@ -1,26 +1,26 @@
 use crate::{DepthFirstOrder, EytzingerTree};
-use matches::matches;
+use std::iter::{ExactSizeIterator, FusedIterator};
 
 /// A depth-first iterator which returns owned values.
 #[derive(Debug, Clone)]
 pub struct DepthFirstIterator<N> {
-    order: DepthFirstOrder,
     tree: EytzingerTree<N>,
-    index: usize,
+    state: DepthFirstTake,
 }
 
 impl<N> DepthFirstIterator<N> {
     pub(crate) fn new(tree: EytzingerTree<N>, order: DepthFirstOrder) -> Self {
+        let root = if tree.root().is_some() { Some(0) } else { None };
+
         Self {
-            order,
             tree,
-            index: 0,
+            state: DepthFirstTake::new(root, order),
         }
     }
 
     /// Gets the order of depth-first iteration.
     pub fn order(&self) -> DepthFirstOrder {
-        self.order
+        self.state.order
     }
 }
 
@@ -28,46 +28,131 @@ impl<N> Iterator for DepthFirstIterator<N> {
     type Item = N;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if self
-                .tree
-                .value(self.index)
-                .and_then(|v| v.as_ref())
-                .is_some()
-            {
-                let current_index = self.index;
-                self.index = self.tree.child_index(current_index, 0);
-                if matches!(self.order, DepthFirstOrder::PreOrder) {
-                    let value = self
-                        .tree
-                        .value_mut(current_index)
-                        .and_then(|v| v.take())
-                        .expect("the value should not have been taken already");
-                    return Some(value);
-                }
-            } else {
-                if let Some(parent_index) = self.tree.parent_index(self.index) {
-                    let node_child_offset = self.index - self.tree.child_index(parent_index, 0);
-                    let next_child_offset = node_child_offset + 1;
-                    if next_child_offset < self.tree.max_children_per_node() {
-                        // try the next sibling
-                        self.index = self.tree.child_index(parent_index, next_child_offset);
-                    } else {
-                        self.index = parent_index;
-
-                        let removed_value = self.tree.remove(parent_index);
-                        if matches!(self.order, DepthFirstOrder::PostOrder) {
-                            return Some(
-                                removed_value
-                                    .expect("the value should not have been taken already"),
-                            );
-                        }
+        self.state.next(&mut self.tree)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.tree.len(), Some(self.tree.len()))
+    }
+}
+
+impl<N> ExactSizeIterator for DepthFirstIterator<N> {}
+
+impl<N> FusedIterator for DepthFirstIterator<N> {}
+
+/// The state of a depth-first traversal which takes each value out of the tree as it is visited.
+///
+/// Each pending node is paired with the offset of the next child to visit, so no child is ever
+/// inspected more than once and no subtree has to be rescanned.
+#[derive(Debug, Clone)]
+pub(crate) struct DepthFirstTake {
+    order: DepthFirstOrder,
+    root: Option<usize>,
+    pending: Vec<(usize, usize)>,
+}
+
+impl DepthFirstTake {
+    pub(crate) fn new(root: Option<usize>, order: DepthFirstOrder) -> Self {
+        Self {
+            order,
+            root,
+            pending: vec![],
+        }
+    }
+
+    pub(crate) fn order(&self) -> DepthFirstOrder {
+        self.order
+    }
+
+    pub(crate) fn next<N>(&mut self, tree: &mut EytzingerTree<N>) -> Option<N> {
+        if let Some(root) = self.root.take() {
+            self.pending.push((root, 0));
+
+            if matches!(self.order, DepthFirstOrder::PreOrder) {
+                return Some(Self::take(tree, root));
+            }
+        }
+
+        let max_children_per_node = tree.max_children_per_node();
+
+        'pending: while let Some((index, next_child_offset)) = self.pending.last_mut() {
+            while *next_child_offset < max_children_per_node {
+                let child_index = tree.child_index(*index, *next_child_offset);
+                *next_child_offset += 1;
+
+                if let Some(Some(_)) = tree.value(child_index) {
+                    self.pending.push((child_index, 0));
+
+                    if matches!(self.order, DepthFirstOrder::PreOrder) {
+                        return Some(Self::take(tree, child_index));
                     }
-                } else {
-                    // we have returned back to the root
-                    return None;
+                    continue 'pending;
                 }
             }
+
+            let (index, _) = self.pending.pop().expect("there should be a pending node");
+
+            if matches!(self.order, DepthFirstOrder::PostOrder) {
+                return Some(Self::take(tree, index));
+            }
         }
+
+        None
+    }
+
+    fn take<N>(tree: &mut EytzingerTree<N>, index: usize) -> N {
+        let value = tree
+            .value_mut(index)
+            .and_then(|v| v.take())
+            .expect("the value should not have been taken already");
+
+        tree.len -= 1;
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DepthFirstOrder, EytzingerTree};
+
+    fn chain(len: usize) -> EytzingerTree<usize> {
+        let mut tree = EytzingerTree::new(1);
+        {
+            let mut node = tree.set_root_value(0);
+            for value in 1..len {
+                node = node.to_child_entry(0).or_insert(value);
+            }
+        }
+        tree
+    }
+
+    #[test]
+    fn len_is_exact() {
+        let mut iterator = chain(3).into_depth_first_iterator(DepthFirstOrder::PostOrder);
+
+        assert_eq!(iterator.len(), 3);
+        iterator.next();
+        assert_eq!(iterator.len(), 2);
+    }
+
+    #[test]
+    fn post_order_visits_a_deep_chain_from_the_leaf_up() {
+        let len = 100_000;
+        let values: Vec<_> = chain(len)
+            .into_depth_first_iterator(DepthFirstOrder::PostOrder)
+            .collect();
+
+        assert_eq!(values, (0..len).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn pre_order_visits_a_deep_chain_from_the_root_down() {
+        let len = 100_000;
+        let values: Vec<_> = chain(len)
+            .into_depth_first_iterator(DepthFirstOrder::PreOrder)
+            .collect();
+
+        assert_eq!(values, (0..len).collect::<Vec<_>>());
     }
 }
@@ -1,5 +1,6 @@
 /// The order of depth-first iteration. This does NOT include in-order as the Etzyinger tree does
-/// not guarantee the actual order of nodes by value.
+/// not guarantee the actual order of nodes by value, binary trees may use
+/// [`EytzingerTree::in_order_iter`](crate::EytzingerTree::in_order_iter) instead.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum DepthFirstOrder {
     /// Parent nodes are returned before their children.
@@ -0,0 +1,80 @@
+use crate::{traversal::TraversalRoot, EytzingerTree, Node};
+use std::iter::FusedIterator;
+
+/// An in-order iterator over a binary tree, each node is returned after all of the nodes of its
+/// left (`0`) child and before all of the nodes of its right (`1`) child.
+#[derive(Debug)]
+pub struct InOrderIter<'a, N>
+where
+    N: 'a,
+{
+    root: TraversalRoot<'a, N>,
+    nodes: Vec<Node<'a, N>>,
+}
+
+impl<'a, N> Clone for InOrderIter<'a, N> {
+    fn clone(&self) -> Self {
+        InOrderIter {
+            root: self.root,
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
+impl<'a, N> InOrderIter<'a, N> {
+    pub(crate) fn new(tree: &'a EytzingerTree<N>, node: Option<Node<'a, N>>) -> Self {
+        assert_eq!(
+            tree.max_children_per_node(),
+            2,
+            "in-order iteration requires a binary tree"
+        );
+
+        let root = if let Some(node) = node {
+            TraversalRoot::Node(node)
+        } else {
+            TraversalRoot::Tree(tree)
+        };
+
+        let mut iter = Self {
+            root,
+            nodes: vec![],
+        };
+        iter.push_left_spine(node);
+        iter
+    }
+
+    /// Gets the starting/root node of this iterator or `None` if there was not one. There will be
+    /// no starting node for an empty Eytzinger tree.
+    pub fn starting_node(&self) -> Option<Node<'a, N>> {
+        self.root.starting_node()
+    }
+
+    /// Gets the tree this iterator is for.
+    pub fn tree(&self) -> &'a EytzingerTree<N> {
+        self.root.tree()
+    }
+
+    fn push_left_spine(&mut self, mut node: Option<Node<'a, N>>) {
+        while let Some(current) = node {
+            self.nodes.push(current);
+            node = current.child(0);
+        }
+    }
+}
+
+impl<'a, N> Iterator for InOrderIter<'a, N> {
+    type Item = Node<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.nodes.pop()?;
+        self.push_left_spine(next.child(1));
+
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.nodes.len(), Some(self.tree().len()))
+    }
+}
+
+impl<'a, N> FusedIterator for InOrderIter<'a, N> {}
@@ -0,0 +1,131 @@
+use crate::{EytzingerTree, Node, NodeMut};
+use std::vec;
+
+/// The order of the nodes of a tree captured at one point in time, so the tree can be changed
+/// while the nodes are visited one by one.
+///
+/// Each node is remembered by its position in the tree. Positions which no longer hold a node
+/// when they are reached are skipped, while a position which has been given a new node since the
+/// snapshot was taken returns that node. A snapshot should only be used with the tree it was
+/// taken from, using it with any other tree panics in debug builds.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{traversal::DepthFirstOrder, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(2);
+/// {
+///     let mut root = tree.set_root_value(1);
+///     root.set_child_value(0, 2).set_child_value(0, 3);
+///     root.set_child_value(1, 4);
+/// }
+///
+/// // remove every even node, along with its descendants, while visiting the rest
+/// let mut snapshot = tree.iter_snapshot(DepthFirstOrder::PreOrder);
+/// let mut visited = vec![];
+/// while let Some(mut node) = snapshot.next_mut(&mut tree) {
+///     if *node.value() % 2 == 0 {
+///         node.remove();
+///     } else {
+///         visited.push(*node.value());
+///     }
+/// }
+///
+/// assert_eq!(visited, vec![1]);
+/// assert_eq!(tree.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IterSnapshot {
+    tree_id: u64,
+    indexes: vec::IntoIter<usize>,
+}
+
+impl IterSnapshot {
+    pub(crate) fn new<N>(tree: &EytzingerTree<N>, indexes: Vec<usize>) -> Self {
+        Self {
+            tree_id: tree.tree_id,
+            indexes: indexes.into_iter(),
+        }
+    }
+
+    /// Gets the next node of the snapshot which is still in the tree, or `None` once every node
+    /// has been visited.
+    pub fn next<'a, N>(&mut self, tree: &'a EytzingerTree<N>) -> Option<Node<'a, N>> {
+        self.check_tree(tree);
+
+        let index = self.indexes.find(|&index| tree.node(index).is_some())?;
+        tree.node(index)
+    }
+
+    /// Gets the next node of the snapshot which is still in the tree as a mutable node, or `None`
+    /// once every node has been visited.
+    pub fn next_mut<'a, N>(&mut self, tree: &'a mut EytzingerTree<N>) -> Option<NodeMut<'a, N>> {
+        self.check_tree(tree);
+
+        let index = self.indexes.find(|&index| tree.node(index).is_some())?;
+        tree.node_mut(index).ok()
+    }
+
+    /// Gets the number of positions which have not been visited yet, including any which no
+    /// longer hold a node.
+    pub fn remaining(&self) -> usize {
+        self.indexes.len()
+    }
+
+    fn check_tree<N>(&self, tree: &EytzingerTree<N>) {
+        debug_assert_eq!(
+            self.tree_id, tree.tree_id,
+            "the snapshot was taken from a different tree"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{traversal::DepthFirstOrder, EytzingerTree};
+
+    #[test]
+    fn nodes_added_after_the_snapshot_are_not_visited() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(1, 2);
+
+        let mut snapshot = tree.iter_snapshot(DepthFirstOrder::PostOrder);
+        assert_eq!(snapshot.remaining(), 2);
+
+        let mut visited = vec![];
+        while let Some(mut node) = snapshot.next_mut(&mut tree) {
+            visited.push(*node.value());
+            node.set_child_value(0, 10 + *node.value());
+        }
+
+        assert_eq!(visited, vec![2, 1]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(snapshot.remaining(), 0);
+        assert!(snapshot.next(&tree).is_none());
+    }
+
+    #[test]
+    fn replaced_positions_return_the_new_node() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(0, 2);
+
+        let mut snapshot = tree.iter_snapshot(DepthFirstOrder::PreOrder);
+        let root = snapshot.next(&tree).map(|n| *n.value());
+        tree.root_mut().unwrap().set_child_value(0, 3);
+
+        assert_eq!(root, Some(1));
+        assert_eq!(snapshot.next(&tree).map(|n| *n.value()), Some(3));
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "different tree"))]
+    fn snapshot_of_another_tree_is_rejected() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1);
+        let mut snapshot = tree.iter_snapshot(DepthFirstOrder::PreOrder);
+
+        let other = EytzingerTree::<u32>::new(2);
+        assert!(snapshot.next(&other).is_none());
+    }
+}
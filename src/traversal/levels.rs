@@ -0,0 +1,141 @@
+use crate::{Depth, EytzingerTree, Node};
+use std::iter::FusedIterator;
+use std::{mem, vec};
+
+/// An iterator over the levels of a tree or subtree from the top down, each level is returned as
+/// its depth within the tree and an iterator over its nodes from left to right.
+///
+/// Each level is found from the children of the nodes on the previous level so vacant parts of
+/// the tree are skipped without being visited.
+#[derive(Debug, Clone)]
+pub struct Levels<'a, N> {
+    tree: &'a EytzingerTree<N>,
+    current_level: Vec<usize>,
+    depth: Depth,
+}
+
+impl<'a, N> Levels<'a, N> {
+    pub(crate) fn new(tree: &'a EytzingerTree<N>, node: Option<Node<'a, N>>) -> Self {
+        let (current_level, depth) = match node {
+            Some(node) => (vec![node.index()], node.depth()),
+            None => (vec![], Depth::ROOT),
+        };
+
+        Self {
+            tree,
+            current_level,
+            depth,
+        }
+    }
+}
+
+impl<'a, N> Iterator for Levels<'a, N> {
+    type Item = (Depth, Level<'a, N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current_level.is_empty() {
+            return None;
+        }
+
+        let tree = self.tree;
+        let storage_len = tree.nodes.len();
+
+        let mut next_level = vec![];
+        for &parent_index in &self.current_level {
+            next_level.extend(
+                tree.index_calculator
+                    .stored_child_indexes(parent_index, storage_len)
+                    .filter(|&child_index| tree.nodes[child_index].is_some()),
+            );
+        }
+
+        let level = Level {
+            tree,
+            indexes: mem::replace(&mut self.current_level, next_level).into_iter(),
+        };
+        let depth = self.depth;
+        self.depth = depth.checked_add(1).unwrap_or(Depth(u32::MAX));
+
+        Some((depth, level))
+    }
+}
+
+impl<'a, N> FusedIterator for Levels<'a, N> {}
+
+/// An iterator over the nodes of a single level from left to right, see `Levels`.
+#[derive(Debug, Clone)]
+pub struct Level<'a, N> {
+    tree: &'a EytzingerTree<N>,
+    indexes: vec::IntoIter<usize>,
+}
+
+impl<'a, N> Iterator for Level<'a, N> {
+    type Item = Node<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indexes.next()?;
+        Some(Node {
+            tree: self.tree,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indexes.size_hint()
+    }
+}
+
+impl<'a, N> DoubleEndedIterator for Level<'a, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.indexes.next_back()?;
+        Some(Node {
+            tree: self.tree,
+            index,
+        })
+    }
+}
+
+impl<'a, N> ExactSizeIterator for Level<'a, N> {}
+
+impl<'a, N> FusedIterator for Level<'a, N> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{traversal::Nodes, Depth, EytzingerTree};
+
+    #[test]
+    fn levels_of_a_subtree_have_their_depth_in_the_tree() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            {
+                let mut right = root.set_child_value(1, 3);
+                right.set_child_value(0, 6).set_child_value(1, 13);
+                right.set_child_value(1, 7);
+            }
+            root.set_child_value(0, 2);
+        }
+
+        let levels: Vec<_> = tree
+            .root()
+            .and_then(|n| n.child(1))
+            .unwrap()
+            .levels()
+            .map(|(depth, level)| (depth, level.values_cloned().collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                (Depth(1), vec![3]),
+                (Depth(2), vec![6, 7]),
+                (Depth(3), vec![13]),
+            ]
+        );
+
+        for (depth, level) in tree.levels() {
+            assert!(level.rev().all(|node| node.depth() == depth));
+        }
+        assert_eq!(tree.levels().count(), 4);
+        assert_eq!(EytzingerTree::<u32>::new(2).levels().count(), 0);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{Node, NodeRef};
+use std::iter::FusedIterator;
+
+/// Adapters for iterators over the nodes of a tree, implemented for every such iterator.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{traversal::Nodes, EytzingerTree};
+///
+/// let mut tree = EytzingerTree::<u32>::new(2);
+/// tree.set_root_value(1).set_child_value(1, 2);
+///
+/// let values: Vec<_> = tree.breadth_first_iter().values_cloned().collect();
+/// assert_eq!(values, vec![1, 2]);
+///
+/// let depths: Vec<_> = tree
+///     .breadth_first_iter()
+///     .with_depth()
+///     .map(|(depth, node)| (depth, *node.value()))
+///     .collect();
+/// assert_eq!(depths, vec![(0, 1), (1, 2)]);
+/// ```
+pub trait Nodes<'a, N>: Iterator<Item = Node<'a, N>> + Sized
+where
+    N: 'a,
+{
+    /// Gets an iterator over the values of the nodes.
+    fn values(self) -> Values<Self> {
+        Values { inner: self }
+    }
+
+    /// Gets an iterator over clones of the values of the nodes.
+    fn values_cloned(self) -> ValuesCloned<Self>
+    where
+        N: Clone,
+    {
+        ValuesCloned { inner: self }
+    }
+
+    /// Gets an iterator over references to the nodes which do not borrow the tree, see `NodeRef`.
+    fn ids(self) -> Ids<Self> {
+        Ids { inner: self }
+    }
+
+    /// Gets an iterator over the nodes paired with their depth, the root has a depth of zero.
+    fn with_depth(self) -> WithDepth<Self> {
+        WithDepth { inner: self }
+    }
+}
+
+impl<'a, N, I> Nodes<'a, N> for I
+where
+    N: 'a,
+    I: Iterator<Item = Node<'a, N>>,
+{
+}
+
+macro_rules! node_adapter {
+    ($(#[$attr:meta])* $name:ident, $item:ty, |$node:ident| $map:expr $(, $bound:path)?) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $name<I> {
+            inner: I,
+        }
+
+        impl<'a, N, I> Iterator for $name<I>
+        where
+            N: 'a $(+ $bound)?,
+            I: Iterator<Item = Node<'a, N>>,
+        {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|$node| $map)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                self.inner.nth(n).map(|$node| $map)
+            }
+
+            fn count(self) -> usize {
+                self.inner.count()
+            }
+
+            fn last(self) -> Option<Self::Item> {
+                self.inner.last().map(|$node| $map)
+            }
+        }
+
+        impl<'a, N, I> DoubleEndedIterator for $name<I>
+        where
+            N: 'a $(+ $bound)?,
+            I: DoubleEndedIterator<Item = Node<'a, N>>,
+        {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back().map(|$node| $map)
+            }
+        }
+
+        impl<'a, N, I> ExactSizeIterator for $name<I>
+        where
+            N: 'a $(+ $bound)?,
+            I: ExactSizeIterator<Item = Node<'a, N>>,
+        {
+        }
+
+        impl<'a, N, I> FusedIterator for $name<I>
+        where
+            N: 'a $(+ $bound)?,
+            I: FusedIterator<Item = Node<'a, N>>,
+        {
+        }
+    };
+}
+
+node_adapter!(
+    /// An iterator over the values of nodes, see `Nodes::values`.
+    Values,
+    &'a N,
+    |node| node.value()
+);
+
+node_adapter!(
+    /// An iterator over clones of the values of nodes, see `Nodes::values_cloned`.
+    ValuesCloned,
+    N,
+    |node| node.value().clone(),
+    Clone
+);
+
+node_adapter!(
+    /// An iterator over references to nodes, see `Nodes::ids`.
+    Ids,
+    NodeRef,
+    |node| node.node_ref()
+);
+
+node_adapter!(
+    /// An iterator over nodes paired with their depth, see `Nodes::with_depth`.
+    WithDepth,
+    (usize, Node<'a, N>),
+    |node| (node.tree().index_calculator.depth(node.index()), node)
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        traversal::{DepthFirstOrder, Nodes},
+        EytzingerTree,
+    };
+
+    fn tree() -> EytzingerTree<u32> {
+        let mut tree = EytzingerTree::new(3);
+        {
+            let mut root = tree.set_root_value(1);
+            root.set_child_value(2, 2).set_child_value(0, 3);
+            root.set_child_value(0, 4);
+        }
+        tree
+    }
+
+    #[test]
+    fn adapters_keep_the_traversal_order() {
+        let tree = tree();
+
+        let values: Vec<_> = tree
+            .depth_first_iter(DepthFirstOrder::PostOrder)
+            .values()
+            .collect();
+        assert_eq!(values, vec![&4, &3, &2, &1]);
+
+        let depths: Vec<_> = tree
+            .depth_first_iter(DepthFirstOrder::PreOrder)
+            .with_depth()
+            .map(|(depth, _)| depth)
+            .collect();
+        assert_eq!(depths, vec![0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn ids_resolve_to_the_same_nodes() {
+        let tree = tree();
+
+        let values: Vec<_> = tree
+            .breadth_first_iter()
+            .ids()
+            .map(|id| *tree.resolve(id).unwrap().value())
+            .collect();
+        assert_eq!(
+            values,
+            tree.breadth_first_iter()
+                .values_cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn adapters_forward_skipping() {
+        let tree = tree();
+
+        assert_eq!(tree.breadth_first_iter().values_cloned().nth(2), Some(2));
+        assert_eq!(tree.breadth_first_iter().values().count(), 4);
+        assert_eq!(tree.breadth_first_iter().values_cloned().last(), Some(3));
+    }
+}
@@ -0,0 +1,119 @@
+use super::depth_first_iterator::DepthFirstTake;
+use crate::{DepthFirstOrder, EytzingerTree};
+use std::iter::FusedIterator;
+
+/// A depth-first iterator which takes the owned values of a single subtree out of a tree, leaving
+/// the rest of the tree as it was.
+///
+/// Any values which have not been iterated are dropped along with the iterator, so the whole
+/// subtree is always removed.
+#[derive(Debug)]
+pub struct SubtreeDrain<'a, N> {
+    tree: &'a mut EytzingerTree<N>,
+    state: DepthFirstTake,
+}
+
+impl<'a, N> SubtreeDrain<'a, N> {
+    pub(crate) fn new(
+        tree: &'a mut EytzingerTree<N>,
+        root: Option<usize>,
+        order: DepthFirstOrder,
+    ) -> Self {
+        if let Some(root) = root {
+            tree.bump_generation();
+            tree.record_modified(tree.parent_index(root));
+        }
+
+        Self {
+            tree,
+            state: DepthFirstTake::new(root, order),
+        }
+    }
+
+    /// Gets the order of depth-first iteration.
+    pub fn order(&self) -> DepthFirstOrder {
+        self.state.order()
+    }
+}
+
+impl<'a, N> Iterator for SubtreeDrain<'a, N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state.next(self.tree)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.tree.len()))
+    }
+}
+
+impl<'a, N> FusedIterator for SubtreeDrain<'a, N> {}
+
+impl<'a, N> Drop for SubtreeDrain<'a, N> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DepthFirstOrder, EytzingerTree, NodePath};
+
+    fn tree() -> EytzingerTree<u32> {
+        let mut tree = EytzingerTree::new(2);
+        {
+            let mut root = tree.set_root_value(1);
+            {
+                let mut left = root.set_child_value(0, 2);
+                left.set_child_value(0, 3);
+                left.set_child_value(1, 4);
+            }
+            root.set_child_value(1, 5);
+        }
+        tree
+    }
+
+    #[test]
+    fn drains_only_the_subtree() {
+        let mut tree = tree();
+        let values: Vec<_> = tree
+            .drain_subtree(&NodePath::from(vec![0]), DepthFirstOrder::PostOrder)
+            .collect();
+
+        assert_eq!(values, vec![3, 4, 2]);
+        assert_eq!(tree.len(), 2);
+
+        let remaining: Vec<_> = tree
+            .depth_first_iter(DepthFirstOrder::PreOrder)
+            .map(|n| *n.value())
+            .collect();
+        assert_eq!(remaining, vec![1, 5]);
+    }
+
+    #[test]
+    fn dropping_removes_the_rest_of_the_subtree() {
+        let mut tree = tree();
+        let first = tree
+            .drain_subtree(&NodePath::from(vec![0]), DepthFirstOrder::PreOrder)
+            .next();
+
+        assert_eq!(first, Some(2));
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.root().unwrap().child(0), None);
+    }
+
+    #[test]
+    fn missing_subtree_drains_nothing() {
+        let mut tree = tree();
+        let node_ref = tree.root().unwrap().node_ref();
+
+        assert_eq!(
+            tree.drain_subtree(&NodePath::from(vec![1, 1]), DepthFirstOrder::PreOrder)
+                .count(),
+            0
+        );
+        assert_eq!(tree.len(), 5);
+        assert!(tree.resolve(node_ref).is_some());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{traversal::DepthFirstOrder, EytzingerTree, Node};
+use std::{cmp, iter::FusedIterator};
+
+/// Gets a depth-first iterator over the positions of two trees at once, each position is paired
+/// with the node of each tree at that path or `None` if the tree has no node there. Every position
+/// with a node in either tree is visited, the trees may have different max children per node.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{
+///     traversal::{zip_iter, DepthFirstOrder},
+///     EytzingerTree,
+/// };
+///
+/// let mut a = EytzingerTree::<u32>::new(2);
+/// a.set_root_value(1).set_child_value(0, 2);
+///
+/// let mut b = EytzingerTree::<char>::new(3);
+/// b.set_root_value('a').set_child_value(2, 'c');
+///
+/// let pairs: Vec<_> = zip_iter(&a, &b, DepthFirstOrder::PreOrder)
+///     .map(|(a, b)| (a.map(|n| *n.value()), b.map(|n| *n.value())))
+///     .collect();
+/// assert_eq!(
+///     pairs,
+///     vec![(Some(1), Some('a')), (Some(2), None), (None, Some('c'))]
+/// );
+/// ```
+pub fn zip_iter<'a, 'b, A, B>(
+    a: &'a EytzingerTree<A>,
+    b: &'b EytzingerTree<B>,
+    order: DepthFirstOrder,
+) -> ZipIter<'a, 'b, A, B> {
+    let roots = (a.root(), b.root());
+
+    ZipIter {
+        order,
+        max_children_per_node: cmp::max(a.max_children_per_node(), b.max_children_per_node()),
+        first_pending: (roots.0.is_some() || roots.1.is_some()).then_some(roots),
+        positions: vec![],
+    }
+}
+
+type NodePair<'a, 'b, A, B> = (Option<Node<'a, A>>, Option<Node<'b, B>>);
+
+/// A depth-first iterator over the aligned positions of two trees, see `zip_iter`.
+#[derive(Debug)]
+pub struct ZipIter<'a, 'b, A, B>
+where
+    A: 'a,
+    B: 'b,
+{
+    order: DepthFirstOrder,
+    max_children_per_node: usize,
+    first_pending: Option<NodePair<'a, 'b, A, B>>,
+    // each position on the current path with the next child offset to visit
+    positions: Vec<(NodePair<'a, 'b, A, B>, usize)>,
+}
+
+impl<'a, 'b, A, B> Clone for ZipIter<'a, 'b, A, B> {
+    fn clone(&self) -> Self {
+        ZipIter {
+            order: self.order,
+            max_children_per_node: self.max_children_per_node,
+            first_pending: self.first_pending,
+            positions: self.positions.clone(),
+        }
+    }
+}
+
+impl<'a, 'b, A, B> ZipIter<'a, 'b, A, B> {
+    /// Gets the order of depth-first iteration.
+    pub fn order(&self) -> DepthFirstOrder {
+        self.order
+    }
+}
+
+fn child<N>(node: Option<Node<'_, N>>, child_offset: usize) -> Option<Node<'_, N>> {
+    node.filter(|node| child_offset < node.tree().max_children_per_node())
+        .and_then(|node| node.child(child_offset))
+}
+
+impl<'a, 'b, A, B> Iterator for ZipIter<'a, 'b, A, B> {
+    type Item = (Option<Node<'a, A>>, Option<Node<'b, B>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(roots) = self.first_pending.take() {
+            self.positions.push((roots, 0));
+
+            if matches!(self.order, DepthFirstOrder::PreOrder) {
+                return Some(roots);
+            }
+        }
+
+        while let Some((pair, child_offset)) = self.positions.last_mut() {
+            let pair = *pair;
+            let mut next_children = None;
+            while *child_offset < self.max_children_per_node && next_children.is_none() {
+                let children = (child(pair.0, *child_offset), child(pair.1, *child_offset));
+                *child_offset += 1;
+
+                if children.0.is_some() || children.1.is_some() {
+                    next_children = Some(children);
+                }
+            }
+
+            match next_children {
+                Some(children) => {
+                    self.positions.push((children, 0));
+
+                    if matches!(self.order, DepthFirstOrder::PreOrder) {
+                        return Some(children);
+                    }
+                }
+                None => {
+                    self.positions.pop();
+
+                    if matches!(self.order, DepthFirstOrder::PostOrder) {
+                        return Some(pair);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, 'b, A, B> FusedIterator for ZipIter<'a, 'b, A, B> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        traversal::{zip_iter, DepthFirstOrder},
+        EytzingerTree,
+    };
+
+    fn trees() -> (EytzingerTree<u32>, EytzingerTree<u32>) {
+        let mut a = EytzingerTree::new(2);
+        {
+            let mut root = a.set_root_value(1);
+            root.set_child_value(0, 2).set_child_value(1, 3);
+            root.set_child_value(1, 4);
+        }
+        let mut b = EytzingerTree::new(4);
+        {
+            let mut root = b.set_root_value(10);
+            root.set_child_value(0, 20).set_child_value(3, 30);
+            root.set_child_value(2, 40);
+        }
+        (a, b)
+    }
+
+    fn values(
+        a: &EytzingerTree<u32>,
+        b: &EytzingerTree<u32>,
+        order: DepthFirstOrder,
+    ) -> Vec<(Option<u32>, Option<u32>)> {
+        zip_iter(a, b, order)
+            .map(|(a, b)| (a.map(|n| *n.value()), b.map(|n| *n.value())))
+            .collect()
+    }
+
+    #[test]
+    fn visits_the_union_of_positions() {
+        let (a, b) = trees();
+
+        assert_eq!(
+            values(&a, &b, DepthFirstOrder::PreOrder),
+            vec![
+                (Some(1), Some(10)),
+                (Some(2), Some(20)),
+                (Some(3), None),
+                (None, Some(30)),
+                (Some(4), None),
+                (None, Some(40)),
+            ]
+        );
+        assert_eq!(
+            values(&a, &b, DepthFirstOrder::PostOrder),
+            vec![
+                (Some(3), None),
+                (None, Some(30)),
+                (Some(2), Some(20)),
+                (Some(4), None),
+                (None, Some(40)),
+                (Some(1), Some(10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_trees_yield_the_other_tree() {
+        let (a, _) = trees();
+        let empty = EytzingerTree::new(3);
+
+        assert!(values(&empty, &empty, DepthFirstOrder::PreOrder).is_empty());
+        assert_eq!(
+            values(&empty, &a, DepthFirstOrder::PreOrder),
+            a.depth_first_iter(DepthFirstOrder::PreOrder)
+                .map(|n| (None, Some(*n.value())))
+                .collect::<Vec<_>>()
+        );
+    }
+}
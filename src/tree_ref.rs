@@ -0,0 +1,342 @@
+use crate::{Depth, EytzingerIndexCalculator, EytzingerLayout, EytzingerTree, NodePath};
+use std::{cmp::Ordering, iter::Flatten, slice};
+
+/// A read-only Eytzinger tree over borrowed storage, such as a `static` array baked into the
+/// binary by `static_tree!`.
+///
+/// The storage holds the value of the node at each index of the layout, or `None` where there is
+/// no node, exactly as an `EytzingerTree` stores it. Creating a tree reference never allocates
+/// and can be done at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{EytzingerLayout, EytzingerTreeRef, NodePath};
+///
+/// const NODES: [Option<u32>; 4] = [Some(1), None, Some(2), Some(3)];
+/// static TREE: EytzingerTreeRef<'static, u32> =
+///     EytzingerTreeRef::new(EytzingerLayout::new(3), &NODES);
+///
+/// assert_eq!(TREE.len(), 3);
+/// assert_eq!(TREE.get(&NodePath::from(vec![2])), Some(&3));
+/// ```
+///
+/// Storage which is not a valid tree fails to compile.
+///
+/// ```compile_fail
+/// use lz_eytzinger_tree::{EytzingerLayout, EytzingerTreeRef};
+///
+/// // the node at index 3 is a child of the vacant index 1
+/// const NODES: [Option<u32>; 4] = [Some(1), None, Some(2), Some(3)];
+/// static TREE: EytzingerTreeRef<'static, u32> =
+///     EytzingerTreeRef::new(EytzingerLayout::new(2), &NODES);
+/// ```
+#[derive(Debug)]
+pub struct EytzingerTreeRef<'a, N> {
+    layout: EytzingerLayout,
+    nodes: &'a [Option<N>],
+    len: usize,
+}
+
+impl<'a, N> Clone for EytzingerTreeRef<'a, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N> Copy for EytzingerTreeRef<'a, N> {}
+
+impl<'a, N> EytzingerTreeRef<'a, N> {
+    /// Creates a new tree reference over the storage with the layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parent of any node is vacant or a node is stored in the padding of a padded
+    /// layout, at compile time when used in a constant or static.
+    pub const fn new(layout: EytzingerLayout, nodes: &'a [Option<N>]) -> Self {
+        let mut len = 0;
+
+        let mut index = 0;
+        while index < nodes.len() {
+            if nodes[index].is_some() {
+                if let Some(parent_index) = layout.parent_index(index) {
+                    assert!(
+                        nodes[parent_index].is_some(),
+                        "the parent of every node should exist"
+                    );
+                    assert!(
+                        index - parent_index * layout.stride() - 1 < layout.max_children_per_node(),
+                        "no node should be stored in the padding"
+                    );
+                }
+                len += 1;
+            }
+            index += 1;
+        }
+
+        Self { layout, nodes, len }
+    }
+
+    /// Creates a tree reference over the storage of a tree, which is known to be valid.
+    pub(crate) fn from_tree(tree: &'a EytzingerTree<N>) -> Self {
+        Self {
+            layout: tree.layout(),
+            nodes: &tree.nodes,
+            len: tree.len(),
+        }
+    }
+
+    /// Gets the layout of the nodes in the storage.
+    pub const fn layout(&self) -> EytzingerLayout {
+        self.layout
+    }
+
+    /// Gets the storage of the tree, the value of the node at each index or `None` where there
+    /// is no node.
+    pub const fn as_slice(&self) -> &'a [Option<N>] {
+        self.nodes
+    }
+
+    /// Gets the number of nodes in the tree.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets whether the tree is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the maximum number of children per parent node.
+    pub const fn max_children_per_node(&self) -> usize {
+        self.layout.max_children_per_node()
+    }
+
+    /// Gets the value of the root node, if there is one.
+    pub fn root_value(&self) -> Option<&'a N> {
+        self.value(0)
+    }
+
+    /// Gets the value of the node at the path, if there is one.
+    pub fn get(&self, path: &NodePath) -> Option<&'a N> {
+        let index = path
+            .child_offsets()
+            .iter()
+            .try_fold(0, |index, &child_offset| {
+                self.layout.child_index(index, child_offset)
+            })?;
+
+        self.value(index)
+    }
+
+    /// Gets whether there is a node at the path, see `EytzingerTree::contains_path`.
+    pub fn contains_path(&self, path: &NodePath) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Gets the values of the positions at the depth, see `EytzingerTree::values_at_depth`.
+    pub fn values_at_depth(&self, depth: Depth) -> &'a [Option<N>] {
+        let storage_len = self.nodes.len();
+        let start = match self.layout.first_index_at_depth(depth) {
+            Some(start) if start < storage_len => start,
+            _ => return &[],
+        };
+        let end = depth
+            .checked_add(1)
+            .and_then(|next_depth| self.layout.first_index_at_depth(next_depth))
+            .map_or(storage_len, |end| end.min(storage_len));
+
+        &self.nodes[start..end]
+    }
+
+    /// Gets an iterator over the values of all nodes in breadth-first order.
+    pub fn breadth_first_values(&self) -> Flatten<slice::Iter<'a, Option<N>>> {
+        self.nodes.iter().flatten()
+    }
+
+    /// Searches this binary search tree for a value matching the comparison function, see
+    /// `EytzingerTree::search_by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    pub fn search_by<F>(&self, cmp: F) -> Option<&'a N>
+    where
+        F: FnMut(&N) -> Ordering,
+    {
+        self.search_index_by(cmp)
+            .and_then(|index| self.value(index))
+    }
+
+    /// Searches this binary search tree for a value equal to `value`, see
+    /// `EytzingerTree::search_by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max children per node is not `2`.
+    pub fn search(&self, value: &N) -> Option<&'a N>
+    where
+        N: Ord,
+    {
+        self.search_by(|current| current.cmp(value))
+    }
+
+    /// Creates an owned tree with clones of the values.
+    pub fn to_tree(&self) -> EytzingerTree<N>
+    where
+        N: Clone,
+    {
+        let mut tree = EytzingerTree::with_index_calculator(EytzingerIndexCalculator::with_layout(
+            self.layout,
+        ));
+        tree.nodes = self.nodes.to_vec();
+        tree.len = self.len;
+        tree
+    }
+
+    pub(crate) fn search_index_by<F>(&self, mut cmp: F) -> Option<usize>
+    where
+        F: FnMut(&N) -> Ordering,
+    {
+        assert_eq!(
+            self.max_children_per_node(),
+            2,
+            "searching requires a binary tree"
+        );
+
+        let mut index = 0;
+        while let Some(value) = self.value(index) {
+            let ordering = cmp(value);
+            if ordering == Ordering::Equal {
+                return Some(index);
+            }
+
+            let child_offset = usize::from(ordering == Ordering::Less);
+            index = self.layout.child_index(index, child_offset)?;
+        }
+
+        None
+    }
+
+    fn value(&self, index: usize) -> Option<&'a N> {
+        self.nodes.get(index)?.as_ref()
+    }
+}
+
+/// Declares `static` Eytzinger trees which are baked into the binary, each an
+/// `EytzingerTreeRef<'static, N>` over a `static` array which is checked to be a valid tree at
+/// compile time.
+///
+/// Each tree is given by its max children per node and the value of the node at each index, or
+/// `None` where there is no node. The storage of an existing tree is given by
+/// `EytzingerTree::as_tree_ref`, so a build script may generate the declaration of a tree it
+/// computed by writing the debug representation of each value.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{static_tree, EytzingerTree};
+///
+/// static_tree! {
+///     /// The first primes as a binary search tree.
+///     pub static PRIMES: EytzingerTreeRef<u32> =
+///         (2, [Some(7), Some(3), Some(13), Some(2), Some(5), Some(11), Some(17)]);
+///
+///     static EMPTY: EytzingerTreeRef<&'static str> = (4, []);
+/// }
+///
+/// assert_eq!(PRIMES.search(&11), Some(&11));
+/// assert_eq!(PRIMES.search(&12), None);
+/// assert!(EMPTY.is_empty());
+///
+/// // generating the storage of a tree from a build script
+/// let tree = EytzingerTree::from_sorted_slice(&[2, 3, 5, 7, 11, 13, 17]);
+/// let nodes: Vec<_> = tree.as_tree_ref().as_slice().iter().map(|v| format!("{:?}", v)).collect();
+/// assert_eq!(
+///     nodes.join(", "),
+///     "Some(7), Some(3), Some(13), Some(2), Some(5), Some(11), Some(17)"
+/// );
+/// ```
+#[macro_export]
+macro_rules! static_tree {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: EytzingerTreeRef<$value:ty> =
+            ($max_children_per_node:expr, [$($node:expr),* $(,)?]);
+    )*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::EytzingerTreeRef<'static, $value> = {
+                const NODES: &[::core::option::Option<$value>] = &[$($node),*];
+                $crate::EytzingerTreeRef::new(
+                    $crate::EytzingerLayout::new($max_children_per_node),
+                    NODES,
+                )
+            };
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Depth, EytzingerLayout, EytzingerTree, EytzingerTreeBuilder, EytzingerTreeRef};
+
+    static_tree! {
+        static TERNARY: EytzingerTreeRef<char> =
+            (3, [Some('a'), Some('b'), None, Some('c'), None, Some('d')]);
+    }
+
+    #[test]
+    fn static_trees_are_read_by_the_layout() {
+        let mut expected = EytzingerTree::new(3);
+        {
+            let mut root = expected.set_root_value('a');
+            root.set_child_value(0, 'b').set_child_value(1, 'd');
+            root.set_child_value(2, 'c');
+        }
+
+        assert_eq!(TERNARY.len(), 4);
+        assert_eq!(TERNARY.to_tree(), expected);
+        assert_eq!(
+            TERNARY.values_at_depth(Depth(1)),
+            &[Some('b'), None, Some('c')]
+        );
+        assert_eq!(TERNARY.values_at_depth(Depth(2)), &[None, Some('d')]);
+        assert!(TERNARY.values_at_depth(Depth(3)).is_empty());
+    }
+
+    #[test]
+    fn as_tree_ref_round_trips_through_to_tree() {
+        let tree = EytzingerTree::from_sorted_iter(0..20u32);
+        let tree_ref = tree.as_tree_ref();
+
+        assert_eq!(tree_ref.len(), 20);
+        assert_eq!(tree_ref.to_tree(), tree);
+        for value in 0..20 {
+            assert_eq!(tree_ref.search(&value), Some(&value));
+        }
+        assert!(tree_ref
+            .breadth_first_values()
+            .eq(tree.breadth_first_iter().map(|n| n.value())));
+
+        let mut padded = EytzingerTreeBuilder::new()
+            .max_children_per_node(3)
+            .pad_arity(true)
+            .build();
+        padded
+            .set_root_value(1)
+            .set_child_value(2, 2)
+            .set_child_value(1, 3);
+
+        let tree_ref = EytzingerTreeRef::new(padded.layout(), padded.as_tree_ref().as_slice());
+        assert_eq!(tree_ref.layout(), EytzingerLayout::new(3).padded());
+        assert_eq!(tree_ref.to_tree(), padded);
+    }
+
+    #[test]
+    #[should_panic(expected = "padding")]
+    fn values_in_the_padding_are_rejected() {
+        let nodes = [Some(1), Some(2), Some(3), Some(4), Some(5)];
+        EytzingerTreeRef::new(EytzingerLayout::new(3).padded(), &nodes);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{EytzingerTree, Node};
+use std::{
+    fmt::{self, Debug},
+    iter::FusedIterator,
+};
+
+/// A map from sequences of symbols in `0..K` to values, stored as a trie in a `K`-ary Eytzinger
+/// tree.
+///
+/// Each symbol of a key selects a child offset, so the node for a key is found by computing its
+/// index from the index of its parent without following any pointers. Nodes along the path of a
+/// key exist even when no value is stored for them.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ArrayTrie<const K: usize, V> {
+    tree: EytzingerTree<Option<V>>,
+    len: usize,
+}
+
+impl<const K: usize, V> ArrayTrie<K, V> {
+    /// Creates a new empty trie.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is zero.
+    pub fn new() -> Self {
+        Self {
+            tree: EytzingerTree::new(K),
+            len: 0,
+        }
+    }
+
+    /// Gets the number of values in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Gets whether the trie has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the Eytzinger tree the trie is stored in, where each node holds the value for the key
+    /// spelt out by its path.
+    pub fn tree(&self) -> &EytzingerTree<Option<V>> {
+        &self.tree
+    }
+
+    /// Inserts a value for the key.
+    ///
+    /// # Returns
+    ///
+    /// The value which was previously stored for the key, if there was one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any symbol of the key is not less than `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::trie::ArrayTrie;
+    ///
+    /// let mut trie = ArrayTrie::<4, &str>::new();
+    /// assert_eq!(trie.insert(&[0, 3], "a"), None);
+    /// assert_eq!(trie.insert(&[0, 3], "b"), Some("a"));
+    ///
+    /// assert_eq!(trie.len(), 1);
+    /// assert_eq!(trie.get(&[0, 3]), Some(&"b"));
+    /// assert_eq!(trie.get(&[0]), None);
+    /// ```
+    pub fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let mut node = self.tree.root_entry_mut().or_insert(None);
+        for &symbol in key {
+            assert!(
+                usize::from(symbol) < K,
+                "the symbols of the key should be less than K"
+            );
+            node = node.to_child_entry(usize::from(symbol)).or_insert(None);
+        }
+
+        let previous = node.value_mut().replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Gets the value stored for the key, if there is one.
+    pub fn get(&self, key: &[u8]) -> Option<&V> {
+        self.node(key)?.value().as_ref()
+    }
+
+    /// Gets the value stored for the key mutably, if there is one.
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut V> {
+        if key.iter().any(|&symbol| usize::from(symbol) >= K) {
+            return None;
+        }
+
+        let mut node = self.tree.root_mut()?;
+        for &symbol in key {
+            node = node.to_child(usize::from(symbol)).ok()?;
+        }
+        node.into_value_mut().as_mut()
+    }
+
+    /// Gets whether a value is stored for the key.
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the value stored for the key, along with any nodes which no longer lead to a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::trie::ArrayTrie;
+    ///
+    /// let mut trie = ArrayTrie::<2, u32>::new();
+    /// trie.insert(&[1, 1, 0], 5);
+    ///
+    /// assert_eq!(trie.remove(&[1, 1]), None);
+    /// assert_eq!(trie.remove(&[1, 1, 0]), Some(5));
+    /// assert!(trie.is_empty());
+    /// assert!(trie.tree().is_empty());
+    /// ```
+    pub fn remove(&mut self, key: &[u8]) -> Option<V> {
+        if key.iter().any(|&symbol| usize::from(symbol) >= K) {
+            return None;
+        }
+
+        let mut node = self.tree.root_mut()?;
+        for &symbol in key {
+            node = node.to_child(usize::from(symbol)).ok()?;
+        }
+
+        let value = node.value_mut().take()?;
+        self.len -= 1;
+
+        while node.value().is_none() && node.child_iter().next().is_none() {
+            match node.remove().1.to_parent() {
+                Ok(parent) => node = parent,
+                Err(_) => break,
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Gets an iterator over the keys and values of the trie in lexicographic order of the keys.
+    pub fn iter(&self) -> PrefixIter<'_, K, V> {
+        self.iter_prefix(&[])
+    }
+
+    /// Gets an iterator over the keys and values where the key starts with the prefix, in
+    /// lexicographic order of the keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lz_eytzinger_tree::trie::ArrayTrie;
+    ///
+    /// let mut trie = ArrayTrie::<3, u32>::new();
+    /// trie.insert(&[1, 2], 12);
+    /// trie.insert(&[1], 1);
+    /// trie.insert(&[2], 2);
+    /// trie.insert(&[1, 0, 2], 102);
+    ///
+    /// let entries: Vec<_> = trie.iter_prefix(&[1]).collect();
+    /// assert_eq!(
+    ///     entries,
+    ///     vec![(vec![1], &1), (vec![1, 0, 2], &102), (vec![1, 2], &12)]
+    /// );
+    /// ```
+    pub fn iter_prefix(&self, prefix: &[u8]) -> PrefixIter<'_, K, V> {
+        PrefixIter {
+            pending: self
+                .node(prefix)
+                .map(|node| (node, prefix.to_vec()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn node(&self, key: &[u8]) -> Option<Node<'_, Option<V>>> {
+        let mut node = self.tree.root()?;
+        for &symbol in key {
+            if usize::from(symbol) >= K {
+                return None;
+            }
+            node = node.child(usize::from(symbol))?;
+        }
+        Some(node)
+    }
+}
+
+impl<const K: usize, V> Default for ArrayTrie<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const K: usize, V> Debug for ArrayTrie<K, V>
+where
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the keys and values of an `ArrayTrie` in lexicographic order of the keys,
+/// see `ArrayTrie::iter_prefix`.
+#[derive(Debug)]
+pub struct PrefixIter<'a, const K: usize, V> {
+    pending: Vec<(Node<'a, Option<V>>, Vec<u8>)>,
+}
+
+impl<'a, const K: usize, V> Iterator for PrefixIter<'a, K, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, key)) = self.pending.pop() {
+            for symbol in (0..K).rev() {
+                if let Some(child) = node.child(symbol) {
+                    let mut child_key = key.clone();
+                    child_key.push(symbol as u8);
+                    self.pending.push((child, child_key));
+                }
+            }
+
+            if let Some(value) = node.value() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, const K: usize, V> FusedIterator for PrefixIter<'a, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_keeps_nodes_leading_to_other_values() {
+        let mut trie = ArrayTrie::<2, u32>::new();
+        trie.insert(&[0, 1], 1);
+        trie.insert(&[0, 1, 1], 2);
+        trie.insert(&[1], 3);
+
+        assert_eq!(trie.remove(&[0, 1, 1]), Some(2));
+        assert_eq!(trie.tree().len(), 4);
+
+        assert_eq!(trie.remove(&[0, 1]), Some(1));
+        assert_eq!(trie.tree().len(), 2);
+        assert_eq!(trie.remove(&[0, 1]), None);
+
+        let entries: Vec<_> = trie.iter().collect();
+        assert_eq!(entries, vec![(vec![1], &3)]);
+    }
+
+    #[test]
+    fn out_of_alphabet_keys_are_absent() {
+        let mut trie = ArrayTrie::<2, u32>::new();
+        trie.insert(&[], 1);
+
+        assert_eq!(trie.get(&[]), Some(&1));
+        assert_eq!(trie.get(&[2]), None);
+        assert_eq!(trie.get_mut(&[5]), None);
+        assert_eq!(trie.remove(&[9]), None);
+        assert_eq!(trie.iter_prefix(&[3]).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_panics_on_out_of_alphabet_symbol() {
+        let mut trie = ArrayTrie::<2, u32>::new();
+        trie.insert(&[2], 1);
+    }
+}
@@ -0,0 +1,122 @@
+use std::mem;
+
+/// The modification ticks of a versioned tree, see `EytzingerTree::enable_versioning`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Versions {
+    tick: u64,
+    node_ticks: Vec<u64>,
+}
+
+impl Versions {
+    pub(crate) fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub(crate) fn last_modified(&self, index: usize) -> u64 {
+        self.node_ticks.get(index).copied().unwrap_or(0)
+    }
+
+    /// Advances the tick and records it as the last modification of each index.
+    pub(crate) fn record<I>(&mut self, indexes: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.tick += 1;
+
+        for index in indexes {
+            if index >= self.node_ticks.len() {
+                self.node_ticks.resize(index + 1, 0);
+            }
+            self.node_ticks[index] = self.tick;
+        }
+    }
+
+    /// Gets the number of bytes allocated for the ticks.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.node_ticks.capacity() * mem::size_of::<u64>()
+    }
+
+    /// Forgets the ticks of every index, for when the nodes are moved to new indexes.
+    pub(crate) fn forget_indexes(&mut self) {
+        self.node_ticks.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{traversal::Nodes, Depth, EytzingerTree};
+
+    fn modified_since(tree: &EytzingerTree<u32>, tick: u64) -> Vec<u32> {
+        tree.nodes_modified_since(tick)
+            .map(|n| *n.value())
+            .collect()
+    }
+
+    #[test]
+    fn every_mutation_advances_the_tick() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1).set_child_value(0, 2);
+        assert_eq!(tree.current_tick(), None);
+
+        tree.enable_versioning();
+        assert_eq!(tree.current_tick(), Some(0));
+        assert!(modified_since(&tree, 0).is_empty());
+
+        *tree.root_mut().unwrap().value_mut() += 10;
+        let after_root = tree.current_tick().unwrap();
+        assert_eq!(modified_since(&tree, 0), vec![11]);
+
+        tree.root_mut().unwrap().set_child_value(1, 3);
+        assert_eq!(modified_since(&tree, after_root), vec![3]);
+        assert_eq!(modified_since(&tree, 0), vec![11, 3]);
+    }
+
+    #[test]
+    fn removing_a_subtree_modifies_its_parent() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1)
+            .set_child_value(0, 2)
+            .set_child_value(1, 3);
+        tree.enable_versioning();
+
+        tree.root_mut()
+            .unwrap()
+            .to_child(0)
+            .ok()
+            .unwrap()
+            .remove_child_value(1);
+        assert_eq!(modified_since(&tree, 0), vec![2]);
+
+        let tick = tree.current_tick().unwrap();
+        tree.clear();
+        assert!(tree.current_tick().unwrap() > tick);
+    }
+
+    #[test]
+    fn bulk_changes_share_a_tick() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.enable_versioning();
+        tree.set_root_value(1)
+            .set_child_value(0, 2)
+            .set_child_value(1, 3);
+        let tick = tree.current_tick().unwrap();
+
+        tree.map_in_place(|value| *value += 1);
+        assert_eq!(tree.current_tick(), Some(tick + 1));
+        assert_eq!(modified_since(&tree, tick), vec![2, 3, 4]);
+
+        for mut subtree in tree.subtrees_at_depth_mut(Depth(1)) {
+            *subtree.value_mut() += 1;
+        }
+        assert_eq!(modified_since(&tree, tick + 1), vec![4, 4]);
+        assert!(tree
+            .breadth_first_iter()
+            .all(|n| n.last_modified().is_some()));
+
+        tree.disable_versioning();
+        assert_eq!(
+            tree.breadth_first_iter().values().count(),
+            modified_since(&tree, 0).len()
+        );
+    }
+}
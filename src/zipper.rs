@@ -0,0 +1,180 @@
+//! An owning cursor for navigating and editing an Eytzinger tree without borrowing it.
+
+use crate::{EytzingerTree, NodePath};
+
+/// Owns an Eytzinger tree along with a focus on one of its positions, which may be vacant.
+///
+/// Every method takes the zipper by value and gives it back, so edits can be chained without
+/// the lifetimes of `NodeMut`. The parent of the focus always exists, so setting the value at
+/// the focus always results in a valid tree.
+///
+/// # Examples
+///
+/// ```
+/// use lz_eytzinger_tree::{zipper::Zipper, EytzingerTree};
+///
+/// let tree = Zipper::new(EytzingerTree::new(2))
+///     .set(1)
+///     .down(0)
+///     .and_then(|z| z.set(2).down(1))
+///     .map(|z| z.set(3))
+///     .and_then(Zipper::up)
+///     .and_then(Zipper::up)
+///     .and_then(|z| z.down(1))
+///     .map(|z| z.set(4))
+///     .unwrap()
+///     .finish();
+///
+/// let values: Vec<_> = tree.breadth_first_iter().map(|n| *n.value()).collect();
+/// assert_eq!(values, vec![1, 2, 4, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Zipper<N> {
+    tree: EytzingerTree<N>,
+    index: usize,
+}
+
+impl<N> Zipper<N> {
+    /// Creates a new zipper focused on the root of the tree.
+    pub fn new(tree: EytzingerTree<N>) -> Self {
+        Self { tree, index: 0 }
+    }
+
+    /// Gets the tree this zipper owns.
+    pub fn tree(&self) -> &EytzingerTree<N> {
+        &self.tree
+    }
+
+    /// Gets the path of the focus.
+    pub fn path(&self) -> NodePath {
+        self.tree.path(self.index)
+    }
+
+    /// Gets whether there is no node at the focus.
+    pub fn is_vacant(&self) -> bool {
+        self.value().is_none()
+    }
+
+    /// Gets the value at the focus or `None` if it is vacant.
+    pub fn value(&self) -> Option<&N> {
+        self.tree.value(self.index).and_then(Option::as_ref)
+    }
+
+    /// Gets the mutable value at the focus or `None` if it is vacant.
+    pub fn value_mut(&mut self) -> Option<&mut N> {
+        self.tree.value_mut(self.index).and_then(Option::as_mut)
+    }
+
+    /// Moves the focus to the child at the specified index, which may be vacant.
+    ///
+    /// # Returns
+    ///
+    /// The moved zipper or the unchanged zipper if the focus is vacant or the index is not less
+    /// than the max children per node.
+    pub fn down(self, index: usize) -> Result<Self, Self> {
+        if self.is_vacant() {
+            return Err(self);
+        }
+
+        match self
+            .tree
+            .index_calculator
+            .checked_child_index(self.index, index)
+        {
+            Some(child_index) => Ok(Self {
+                index: child_index,
+                ..self
+            }),
+            None => Err(self),
+        }
+    }
+
+    /// Moves the focus to the parent.
+    ///
+    /// # Returns
+    ///
+    /// The moved zipper or the unchanged zipper if the focus is the root.
+    pub fn up(self) -> Result<Self, Self> {
+        match self.tree.parent_index(self.index) {
+            Some(parent_index) => Ok(Self {
+                index: parent_index,
+                ..self
+            }),
+            None => Err(self),
+        }
+    }
+
+    /// Moves the focus to the root.
+    pub fn top(self) -> Self {
+        Self { index: 0, ..self }
+    }
+
+    /// Sets the value at the focus, keeping any children.
+    pub fn set(mut self, value: N) -> Self {
+        self.tree.set_value(self.index, value);
+        self
+    }
+
+    /// Removes the node at the focus and all of its descendants, the focus stays on the now
+    /// vacant position.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, if there was one, and the zipper.
+    pub fn take(mut self) -> (Option<N>, Self) {
+        let value = self.tree.remove(self.index);
+        (value, self)
+    }
+
+    /// Gives up the focus and returns the tree.
+    pub fn finish(self) -> EytzingerTree<N> {
+        self.tree
+    }
+}
+
+impl<N> From<EytzingerTree<N>> for Zipper<N> {
+    fn from(tree: EytzingerTree<N>) -> Self {
+        Self::new(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{zipper::Zipper, EytzingerTree, NodePath};
+
+    #[test]
+    fn cannot_move_below_a_vacant_focus() {
+        let zipper = Zipper::new(EytzingerTree::<u32>::new(2));
+        let zipper = zipper.down(0).unwrap_err();
+
+        let zipper = zipper.set(1).down(1).unwrap();
+        assert!(zipper.is_vacant());
+        assert_eq!(zipper.path(), NodePath::from(vec![1]));
+
+        let zipper = zipper.down(0).unwrap_err();
+        let zipper = zipper.up().unwrap().down(2).unwrap_err();
+        assert!(zipper.up().is_err());
+    }
+
+    #[test]
+    fn take_removes_the_focused_subtree() {
+        let mut tree = EytzingerTree::<u32>::new(2);
+        tree.set_root_value(1)
+            .set_child_value(0, 2)
+            .set_child_value(0, 3);
+
+        let zipper = Zipper::from(tree).down(0).unwrap();
+        let (value, zipper) = zipper.take();
+        assert_eq!(value, Some(2));
+        assert!(zipper.is_vacant());
+
+        let (value, zipper) = zipper.take();
+        assert_eq!(value, None);
+
+        let mut zipper = zipper.top();
+        *zipper.value_mut().unwrap() += 10;
+        let tree = zipper.finish();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.root().map(|n| *n.value()), Some(11));
+    }
+}